@@ -0,0 +1,81 @@
+use crate::Character;
+use std::cmp::{PartialEq, Eq, PartialOrd, Ord, Ordering};
+use std::fmt;
+
+/// MPの値と元のキャラクターを持つ型（CharacterHP の MP 版）
+#[derive(Clone, Debug)]
+pub struct CharacterMP {
+    pub character: Character,
+    pub mp_value: i32,
+}
+
+impl CharacterMP {
+    pub fn new(character: Character) -> Self {
+        let mp_value = character.mp;
+        Self { character, mp_value }
+    }
+
+    pub fn from_character_with_mp(character: Character, mp_value: i32) -> Self {
+        Self { character, mp_value }
+    }
+
+    pub fn get_character(&self) -> &Character {
+        &self.character
+    }
+
+    pub fn get_mp(&self) -> i32 {
+        self.mp_value
+    }
+}
+
+// 数値としての基本操作
+impl From<CharacterMP> for i32 {
+    fn from(char_mp: CharacterMP) -> Self {
+        char_mp.mp_value
+    }
+}
+
+impl From<&CharacterMP> for i32 {
+    fn from(char_mp: &CharacterMP) -> Self {
+        char_mp.mp_value
+    }
+}
+
+// 比較演算
+impl PartialEq for CharacterMP {
+    fn eq(&self, other: &Self) -> bool {
+        self.mp_value == other.mp_value
+    }
+}
+
+impl Eq for CharacterMP {}
+
+impl PartialOrd for CharacterMP {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CharacterMP {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.mp_value.cmp(&other.mp_value)
+    }
+}
+
+impl PartialEq<i32> for CharacterMP {
+    fn eq(&self, other: &i32) -> bool {
+        self.mp_value == *other
+    }
+}
+
+impl PartialOrd<i32> for CharacterMP {
+    fn partial_cmp(&self, other: &i32) -> Option<Ordering> {
+        self.mp_value.partial_cmp(other)
+    }
+}
+
+impl fmt::Display for CharacterMP {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.mp_value)
+    }
+}
@@ -1,11 +1,14 @@
 pub mod character;
 pub mod character_hp;
+pub mod character_mp;
+#[allow(clippy::module_inception)]
 pub mod core;
 pub mod actions;
 pub mod numeric;
 
 pub use character::{Character, Team, TeamSide};
 pub use character_hp::CharacterHP;
+pub use character_mp::CharacterMP;
 pub use core::{Action, BattleState, RuleNode, NodeError, NodeResult};
 pub use actions::{StrikeAction, HealAction};
 pub use numeric::Numeric;
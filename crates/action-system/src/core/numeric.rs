@@ -30,6 +30,16 @@ impl Numeric for CharacterHP {
     }
 }
 
+impl Numeric for crate::CharacterMP {
+    fn to_i32(&self) -> i32 {
+        self.get_mp()
+    }
+    
+    fn clone_box(&self) -> Box<dyn Numeric> {
+        Box::new(self.clone())
+    }
+}
+
 impl Numeric for crate::Character {
     fn to_i32(&self) -> i32 {
         self.hp
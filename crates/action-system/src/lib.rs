@@ -5,15 +5,16 @@ pub mod nodes;
 pub mod system;
 
 // Re-export essential types only
-pub use core::{Character, Team, TeamSide, CharacterHP, Action, BattleState, RuleNode, NodeError, NodeResult, Numeric};
+pub use core::{Character, Team, TeamSide, CharacterHP, CharacterMP, Action, BattleState, RuleNode, NodeError, NodeResult, Numeric};
 // Export Node trait and related types for external crates
 pub use nodes::unified_node::{CoreNode as Node, BoxedNode};
-pub use nodes::condition::{ConditionCheckNode, RandomConditionNode, CharacterTeamNode, GreaterThanNode, LessThanNode};
-pub use nodes::value::{ConstantValueNode, EnemyNode, HeroNode, NumericNode};
-pub use nodes::character::{BattleContext, ActingCharacterNode, CharacterToHpNode, CharacterHpValueNode, CharacterHpToCharacterNode, ElementNode};
+pub use nodes::condition::{ConditionCheckNode, RandomConditionNode, CharacterTeamNode, ComparisonNode, ComparisonOperator, NotEqConditionNode, AndNode, OrNode, NotNode, IfNode, FirstOfNode};
+pub use nodes::value::{ConstantValueNode, EnemyNode, HeroNode, NumericNode, ArithmeticNode, ArithmeticOperator};
+pub use nodes::character::{BattleContext, ActingCharacterNode, CharacterToHpNode, CharacterHpValueNode, CharacterHpToCharacterNode, CharacterToMpNode, CharacterMpToCharacterNode, CharacterStat, CharacterStatNode, ElementNode};
 pub use nodes::evaluation_context::EvaluationContext;
+pub use nodes::variable::LetNode;
 pub use nodes::action::{StrikeActionNode, HealActionNode};
-pub use nodes::array::{AllCharactersNode, TeamMembersNode, TeamMembersNodeWithNode, CountArrayNode, RandomPickNode, FilterListNode, MappingNode, AllTeamSidesNode, MaxNode, MinNode};
+pub use nodes::array::{AllCharactersNode, TeamMembersNode, TeamMembersNodeWithNode, CountArrayNode, RandomPickNode, FilterListNode, MappingNode, AllTeamSidesNode, MaxNode, MinNode, ExtremeByNode, SumNode, AverageNode, AnyNode, AllNode, IsEmptyNode, SortByNode, TakeNode, FirstNode, LastNode, ReverseNode};
 pub use system::ActionCalculationSystem;
 
 #[cfg(test)]
@@ -164,7 +165,7 @@ mod tests {
         use crate::nodes::array::FilterListNode;
         use crate::nodes::character::ElementNode;
         use crate::nodes::character::character_hp_value_node::CharacterHpValueNode;
-        use crate::nodes::condition::GreaterThanNode;
+        use crate::nodes::condition::{ComparisonNode, ComparisonOperator};
         use crate::nodes::value::ConstantValueNode;
         
         let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
@@ -188,7 +189,8 @@ mod tests {
         
         // Create FilterList that filters characters with HP > 50
         let team_array = Box::new(TeamMembersNode::new(TeamSide::Player));
-        let hp_condition = Box::new(GreaterThanNode::new(
+        let hp_condition = Box::new(ComparisonNode::new(
+            ComparisonOperator::GreaterThan,
             Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(ElementNode::<Character>::new()))))), // Use Element to reference current character
             Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50)))),
         ));
@@ -220,7 +222,7 @@ mod tests {
         
         let element_node = ElementNode::<Character>::new();
         let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
-        eval_context.current_element = Some(UnknownValue::Character(current_element.clone()));
+        eval_context.bindings.push(UnknownValue::Character(current_element.clone()));
         let result: Character = element_node.evaluate(&mut eval_context).unwrap();
         
         // Should return the current element
@@ -14,6 +14,12 @@ impl AllCharactersNode {
     }
 }
 
+impl Default for AllCharactersNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a> Node<Vec<Character>, EvaluationContext<'a>> for AllCharactersNode {
     fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<Vec<Character>> {
         let battle_context = eval_context.get_battle_context();
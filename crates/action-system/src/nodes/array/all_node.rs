@@ -0,0 +1,33 @@
+// AllNode - true if every element satisfies the condition (short-circuit)
+use crate::core::NodeResult;
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::array::mapping_node::AsUnknownValue;
+
+/// 条件内では Element で現在の要素を参照する（空配列は true）
+pub struct AllNode<T: Clone + Send + Sync + 'static> {
+    array: BoxedNode<Vec<T>>,
+    condition: BoxedNode<bool>,
+}
+
+impl<T: Clone + Send + Sync + 'static> AllNode<T> {
+    pub fn new(array: BoxedNode<Vec<T>>, condition: BoxedNode<bool>) -> Self {
+        Self { array, condition }
+    }
+}
+
+impl<'a, T> Node<bool, EvaluationContext<'a>> for AllNode<T>
+where
+    T: Clone + Send + Sync + AsUnknownValue + 'static,
+{
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<bool> {
+        let items = self.array.evaluate(eval_context)?;
+        for item in items {
+            let mut element_eval_context = eval_context.with_binding(item.as_unknown_value());
+            if !self.condition.evaluate(&mut element_eval_context)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,75 @@
+// AnyNode - true if any element satisfies the condition (short-circuit)
+use crate::core::NodeResult;
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::array::mapping_node::AsUnknownValue;
+
+/// 条件内では Element で現在の要素を参照する（空配列は false）
+pub struct AnyNode<T: Clone + Send + Sync + 'static> {
+    array: BoxedNode<Vec<T>>,
+    condition: BoxedNode<bool>,
+}
+
+impl<T: Clone + Send + Sync + 'static> AnyNode<T> {
+    pub fn new(array: BoxedNode<Vec<T>>, condition: BoxedNode<bool>) -> Self {
+        Self { array, condition }
+    }
+}
+
+impl<'a, T> Node<bool, EvaluationContext<'a>> for AnyNode<T>
+where
+    T: Clone + Send + Sync + AsUnknownValue + 'static,
+{
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<bool> {
+        let items = self.array.evaluate(eval_context)?;
+        for item in items {
+            let mut element_eval_context = eval_context.with_binding(item.as_unknown_value());
+            if self.condition.evaluate(&mut element_eval_context)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::array::{AllNode, TeamMembersNode};
+    use crate::nodes::character::{CharacterToHpNode, ElementNode};
+    use crate::nodes::condition::{ComparisonNode, ComparisonOperator};
+    use crate::nodes::value::{ConstantValueNode, NumericNode};
+    use crate::{BattleContext, Character, Team, TeamSide};
+    use rand::SeedableRng;
+
+    // HP(Element) < threshold
+    fn hp_below(threshold: i32) -> BoxedNode<bool> {
+        Box::new(ComparisonNode::new(
+            ComparisonOperator::LessThan,
+            Box::new(NumericNode::new(Box::new(CharacterToHpNode::new(Box::new(ElementNode::<Character>::new()))))),
+            Box::new(NumericNode::new(Box::new(ConstantValueNode::new(threshold)))),
+        ))
+    }
+
+    #[test]
+    fn test_any_and_all_allies_below_hp() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        let mut hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        hero.hp = 25;
+        let mage = Character::new(2, "Mage".to_string(), 80, 50, 10);
+        let player_team = Team::new("Player".to_string(), vec![hero.clone(), mage]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let allies = || Box::new(TeamMembersNode::new(TeamSide::Player));
+        assert!(AnyNode::new(allies(), hp_below(30)).evaluate(&mut eval_context).unwrap());
+        assert!(!AllNode::new(allies(), hp_below(30)).evaluate(&mut eval_context).unwrap());
+        assert!(AllNode::new(allies(), hp_below(100)).evaluate(&mut eval_context).unwrap());
+
+        // 空配列: Any は false、All は true
+        let enemies = || Box::new(TeamMembersNode::new(TeamSide::Enemy));
+        assert!(!AnyNode::new(enemies(), hp_below(30)).evaluate(&mut eval_context).unwrap());
+        assert!(AllNode::new(enemies(), hp_below(30)).evaluate(&mut eval_context).unwrap());
+    }
+}
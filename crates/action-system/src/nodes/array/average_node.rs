@@ -0,0 +1,61 @@
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::core::{NodeResult, Numeric};
+use crate::nodes::evaluation_context::EvaluationContext;
+
+/// Array内の数値の平均を返すノード（0方向に切り捨て。空配列は Break）
+pub struct AverageNode<T: Numeric + Clone> {
+    array_node: BoxedNode<Vec<T>>,
+}
+
+impl<T: Numeric + Clone> AverageNode<T> {
+    pub fn new(array_node: BoxedNode<Vec<T>>) -> Self {
+        Self { array_node }
+    }
+}
+
+impl<'a, T: Numeric + Clone> Node<i32, EvaluationContext<'a>> for AverageNode<T> {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<i32> {
+        let array = self.array_node.evaluate(eval_context)?;
+        
+        if array.is_empty() {
+            return Err(crate::NodeError::Break);
+        }
+        
+        let sum: i64 = array.iter().map(|value| i64::from(value.to_i32())).sum();
+        Ok((sum / array.len() as i64) as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BattleContext, Character, Team, TeamSide};
+    use rand::SeedableRng;
+
+    struct ConstantArrayNode(Vec<i32>);
+
+    impl<'a> Node<Vec<i32>, EvaluationContext<'a>> for ConstantArrayNode {
+        fn evaluate(&self, _eval_context: &mut EvaluationContext) -> NodeResult<Vec<i32>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn test_average() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        let character = Character::new(1, "Test".to_string(), 100, 50, 10);
+        let team = Team::new("Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let average = AverageNode::new(Box::new(ConstantArrayNode(vec![10, 20, 40])));
+        assert_eq!(average.evaluate(&mut eval_context).unwrap(), 23);
+
+        let average = AverageNode::new(Box::new(ConstantArrayNode(vec![i32::MAX, i32::MAX])));
+        assert_eq!(average.evaluate(&mut eval_context).unwrap(), i32::MAX);
+
+        // 空配列は Break（FirstOf なら次の候補へ進む）
+        let average = AverageNode::new(Box::new(ConstantArrayNode(vec![])));
+        assert!(matches!(average.evaluate(&mut eval_context), Err(crate::NodeError::Break)));
+    }
+}
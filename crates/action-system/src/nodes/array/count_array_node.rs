@@ -1,23 +1,22 @@
-// CountArrayNode - counts elements in character arrays
+// CountArrayNode - counts elements in arrays
 
 use crate::core::NodeResult;
 use crate::nodes::evaluation_context::EvaluationContext;
 use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
-use crate::Character;
 
-pub struct CountArrayNode {
-    array_node: BoxedNode<Vec<Character>>,
+pub struct CountArrayNode<T> {
+    array_node: BoxedNode<Vec<T>>,
 }
 
-impl CountArrayNode {
-    pub fn new(array_node: BoxedNode<Vec<Character>>) -> Self {
+impl<T> CountArrayNode<T> {
+    pub fn new(array_node: BoxedNode<Vec<T>>) -> Self {
         Self { array_node }
     }
 }
 
-impl<'a> Node<i32, EvaluationContext<'a>> for CountArrayNode {
+impl<'a, T: Send + Sync + 'static> Node<i32, EvaluationContext<'a>> for CountArrayNode<T> {
     fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<i32> {
-        let characters = self.array_node.evaluate(eval_context)?;
-        Ok(characters.len() as i32)
+        let array = self.array_node.evaluate(eval_context)?;
+        Ok(array.len() as i32)
     }
-}
\ No newline at end of file
+}
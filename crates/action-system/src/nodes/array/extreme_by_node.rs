@@ -0,0 +1,113 @@
+use std::cmp::Ordering;
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::core::{NodeResult, Numeric};
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::array::mapping_node::AsUnknownValue;
+
+/// key（Element を各要素に束縛して評価）が最大・最小の要素をそのまま返すノード（MaxBy / MinBy）
+/// 同値の場合は配列内で先に現れた要素を返す。空配列は Break
+pub struct ExtremeByNode<T: Clone + Send + Sync + 'static> {
+    array: BoxedNode<Vec<T>>,
+    key: BoxedNode<Box<dyn Numeric>>,
+    /// 今の候補と比べてこの向きの key を持つ要素に入れ替える
+    ordering: Ordering,
+}
+
+impl<T: Clone + Send + Sync + 'static> ExtremeByNode<T> {
+    pub fn max(array: BoxedNode<Vec<T>>, key: BoxedNode<Box<dyn Numeric>>) -> Self {
+        Self { array, key, ordering: Ordering::Greater }
+    }
+
+    pub fn min(array: BoxedNode<Vec<T>>, key: BoxedNode<Box<dyn Numeric>>) -> Self {
+        Self { array, key, ordering: Ordering::Less }
+    }
+}
+
+impl<'a, T> Node<T, EvaluationContext<'a>> for ExtremeByNode<T>
+where
+    T: Clone + Send + Sync + AsUnknownValue + 'static,
+{
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<T> {
+        let items = self.array.evaluate(eval_context)?;
+        
+        let mut best: Option<(i32, T)> = None;
+        for item in items {
+            let mut element_eval_context = eval_context.with_binding(item.as_unknown_value());
+            let key = self.key.evaluate(&mut element_eval_context)?.to_i32();
+            if best.as_ref().is_none_or(|(best_key, _)| key.cmp(best_key) == self.ordering) {
+                best = Some((key, item));
+            }
+        }
+        
+        best.map(|(_, item)| item).ok_or(crate::NodeError::Break)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::array::TeamMembersNode;
+    use crate::nodes::character::{ActingCharacterNode, CharacterStat, CharacterStatNode, ElementNode};
+    use crate::nodes::condition::FirstOfNode;
+    use crate::nodes::value::NumericNode;
+    use crate::{BattleContext, Character, Team, TeamSide};
+    use rand::SeedableRng;
+
+    fn stat_of_element(stat: CharacterStat) -> BoxedNode<Box<dyn Numeric>> {
+        Box::new(NumericNode::new(Box::new(CharacterStatNode::new(stat, Box::new(ElementNode::<Character>::new())))))
+    }
+
+    #[test]
+    fn test_max_by_and_min_by_return_original_element() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        let hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        // HP割合: Orc 50%, Goblin 50%, Slime 80%。攻撃力: Orc 30, Goblin 30, Slime 5
+        let mut orc = Character::new(2, "Orc".to_string(), 200, 0, 30);
+        orc.hp = 100;
+        let mut goblin = Character::new(3, "Goblin".to_string(), 60, 0, 30);
+        goblin.hp = 30;
+        let mut slime = Character::new(4, "Slime".to_string(), 50, 0, 5);
+        slime.hp = 40;
+        let player_team = Team::new("Player".to_string(), vec![hero.clone()]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![orc, goblin, slime]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let enemies = || Box::new(TeamMembersNode::new(TeamSide::Enemy));
+
+        let highest_attack = ExtremeByNode::max(enemies(), stat_of_element(CharacterStat::Attack));
+        assert_eq!(highest_attack.evaluate(&mut eval_context).unwrap().name, "Orc", "tie goes to the first element");
+
+        let lowest_hp_percent = ExtremeByNode::min(enemies(), stat_of_element(CharacterStat::HpPercent));
+        assert_eq!(lowest_hp_percent.evaluate(&mut eval_context).unwrap().name, "Orc", "tie goes to the first element");
+
+        let highest_hp_percent = ExtremeByNode::max(enemies(), stat_of_element(CharacterStat::HpPercent));
+        assert_eq!(highest_hp_percent.evaluate(&mut eval_context).unwrap().name, "Slime");
+
+        let lowest_attack = ExtremeByNode::min(enemies(), stat_of_element(CharacterStat::Attack));
+        assert_eq!(lowest_attack.evaluate(&mut eval_context).unwrap().name, "Slime");
+    }
+
+    #[test]
+    fn test_max_by_and_min_by_empty_array() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        let hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        let player_team = Team::new("Player".to_string(), vec![hero.clone()]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let enemies = || Box::new(TeamMembersNode::new(TeamSide::Enemy));
+        let max_by = ExtremeByNode::max(enemies(), stat_of_element(CharacterStat::Attack));
+        assert!(matches!(max_by.evaluate(&mut eval_context), Err(crate::NodeError::Break)));
+        let min_by = ExtremeByNode::min(enemies(), stat_of_element(CharacterStat::Attack));
+        assert!(matches!(min_by.evaluate(&mut eval_context), Err(crate::NodeError::Break)));
+
+        // FirstOf は次の候補へ進む
+        let first_of = FirstOfNode::<Character>::new(vec![
+            Box::new(ExtremeByNode::min(enemies(), stat_of_element(CharacterStat::HpPercent))),
+            Box::new(ActingCharacterNode),
+        ]);
+        assert_eq!(first_of.evaluate(&mut eval_context).unwrap().name, "Hero");
+    }
+}
@@ -33,7 +33,7 @@ where
         for item in items {
             // Create an evaluation context with the current item as the element being processed
             // This allows the Element node to reference the current item being evaluated
-            let mut element_eval_context = eval_context.with_current_element_from_context(item.as_unknown_value());
+            let mut element_eval_context = eval_context.with_binding(item.as_unknown_value());
             
             // Evaluate condition with the element-specific context
             let condition_result = self.condition.evaluate(&mut element_eval_context)?;
@@ -52,7 +52,7 @@ where
 mod tests {
     use super::*;
     use crate::nodes::array::team_members_node::TeamMembersNode;
-    use crate::nodes::condition::GreaterThanNode;
+    use crate::nodes::condition::{ComparisonNode, ComparisonOperator};
     use crate::nodes::character::character_hp_value_node::CharacterHpValueNode;
     use crate::nodes::character::element_node::ElementNode;
     use crate::nodes::value::constant_value_node::ConstantValueNode;
@@ -84,7 +84,8 @@ mod tests {
         
         use crate::nodes::value::NumericNode;
         
-        let hp_condition = Box::new(GreaterThanNode::new(
+        let hp_condition = Box::new(ComparisonNode::new(
+            ComparisonOperator::GreaterThan,
             Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(ElementNode::<Character>::new()))))),
             Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50)))),
         ));
@@ -117,7 +118,8 @@ mod tests {
         
         // Create FilterList that filters characters with HP > 90 (none should match)
         let team_array = Box::new(TeamMembersNode::new(TeamSide::Player));
-        let hp_condition = Box::new(GreaterThanNode::new(
+        let hp_condition = Box::new(ComparisonNode::new(
+            ComparisonOperator::GreaterThan,
             Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(ElementNode::<Character>::new()))))),
             Box::new(NumericNode::new(Box::new(ConstantValueNode::new(90)))),
         ));
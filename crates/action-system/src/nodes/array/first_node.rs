@@ -0,0 +1,22 @@
+// First node - returns the first element of an array
+use crate::core::{NodeError, NodeResult};
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::nodes::evaluation_context::EvaluationContext;
+
+/// 空配列なら Break
+pub struct FirstNode<T> {
+    array: BoxedNode<Vec<T>>,
+}
+
+impl<T> FirstNode<T> {
+    pub fn new(array: BoxedNode<Vec<T>>) -> Self {
+        Self { array }
+    }
+}
+
+impl<'a, T: Clone + Send + Sync + 'static> Node<T, EvaluationContext<'a>> for FirstNode<T> {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<T> {
+        let items = self.array.evaluate(eval_context)?;
+        items.into_iter().next().ok_or(NodeError::Break)
+    }
+}
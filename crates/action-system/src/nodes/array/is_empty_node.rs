@@ -0,0 +1,22 @@
+// IsEmptyNode - true if the array has no elements
+
+use crate::core::NodeResult;
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+
+pub struct IsEmptyNode<T> {
+    array_node: BoxedNode<Vec<T>>,
+}
+
+impl<T> IsEmptyNode<T> {
+    pub fn new(array_node: BoxedNode<Vec<T>>) -> Self {
+        Self { array_node }
+    }
+}
+
+impl<'a, T: Send + Sync + 'static> Node<bool, EvaluationContext<'a>> for IsEmptyNode<T> {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<bool> {
+        let array = self.array_node.evaluate(eval_context)?;
+        Ok(array.is_empty())
+    }
+}
@@ -0,0 +1,22 @@
+// Last node - returns the last element of an array
+use crate::core::{NodeError, NodeResult};
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::nodes::evaluation_context::EvaluationContext;
+
+/// 空配列なら Break
+pub struct LastNode<T> {
+    array: BoxedNode<Vec<T>>,
+}
+
+impl<T> LastNode<T> {
+    pub fn new(array: BoxedNode<Vec<T>>) -> Self {
+        Self { array }
+    }
+}
+
+impl<'a, T: Clone + Send + Sync + 'static> Node<T, EvaluationContext<'a>> for LastNode<T> {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<T> {
+        let mut items = self.array.evaluate(eval_context)?;
+        items.pop().ok_or(NodeError::Break)
+    }
+}
@@ -7,6 +7,7 @@ use crate::nodes::evaluation_context::EvaluationContext;
 use crate::nodes::unknown_value::UnknownValue;
 use crate::Character;
 use crate::core::character_hp::CharacterHP;
+use crate::core::character_mp::CharacterMP;
 use crate::TeamSide;
 use std::any::Any;
 
@@ -33,6 +34,12 @@ impl AsUnknownValue for CharacterHP {
     }
 }
 
+impl AsUnknownValue for CharacterMP {
+    fn as_unknown_value(&self) -> UnknownValue {
+        UnknownValue::CharacterMP(self.clone())
+    }
+}
+
 impl AsUnknownValue for TeamSide {
     fn as_unknown_value(&self) -> UnknownValue {
         UnknownValue::TeamSide(*self)
@@ -85,7 +92,7 @@ where
         for element in input_array {
             // Create an evaluation context with the current element
             let unknown_value = element.as_unknown_value();
-            let mut element_eval_context = eval_context.with_current_element_from_context(unknown_value);
+            let mut element_eval_context = eval_context.with_binding(unknown_value);
             
             // Apply the transformation function
             let transformed_element = self.transform_node.evaluate(&mut element_eval_context)?;
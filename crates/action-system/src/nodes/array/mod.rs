@@ -7,6 +7,17 @@ pub mod mapping_node;
 pub mod all_team_sides_node;
 pub mod max_node;
 pub mod min_node;
+pub mod extreme_by_node;
+pub mod sum_node;
+pub mod average_node;
+pub mod any_node;
+pub mod all_node;
+pub mod is_empty_node;
+pub mod sort_by_node;
+pub mod take_node;
+pub mod first_node;
+pub mod last_node;
+pub mod reverse_node;
 
 // Re-export core array implementations
 pub use all_characters_node::AllCharactersNode;
@@ -17,4 +28,15 @@ pub use filter_list_node::FilterListNode;
 pub use mapping_node::{MappingNode, AsUnknownValue};
 pub use all_team_sides_node::AllTeamSidesNode;
 pub use max_node::MaxNode;
-pub use min_node::MinNode;
\ No newline at end of file
+pub use min_node::MinNode;
+pub use extreme_by_node::ExtremeByNode;
+pub use sum_node::SumNode;
+pub use average_node::AverageNode;
+pub use any_node::AnyNode;
+pub use all_node::AllNode;
+pub use is_empty_node::IsEmptyNode;
+pub use sort_by_node::SortByNode;
+pub use take_node::TakeNode;
+pub use first_node::FirstNode;
+pub use last_node::LastNode;
+pub use reverse_node::ReverseNode;
\ No newline at end of file
@@ -0,0 +1,22 @@
+// Reverse node - reverses the order of array elements
+use crate::core::NodeResult;
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::nodes::evaluation_context::EvaluationContext;
+
+pub struct ReverseNode<T> {
+    array: BoxedNode<Vec<T>>,
+}
+
+impl<T> ReverseNode<T> {
+    pub fn new(array: BoxedNode<Vec<T>>) -> Self {
+        Self { array }
+    }
+}
+
+impl<'a, T: Clone + Send + Sync + 'static> Node<Vec<T>, EvaluationContext<'a>> for ReverseNode<T> {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<Vec<T>> {
+        let mut items = self.array.evaluate(eval_context)?;
+        items.reverse();
+        Ok(items)
+    }
+}
@@ -0,0 +1,112 @@
+// SortBy node - sorts array elements in ascending order of a numeric key
+use crate::core::{NodeResult, Numeric};
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::array::mapping_node::AsUnknownValue;
+
+/// キーは Element を現在の要素に束縛して要素ごとに1回だけ評価する（安定ソート）
+pub struct SortByNode<T: Clone + Send + Sync + 'static> {
+    array: BoxedNode<Vec<T>>,
+    key: BoxedNode<Box<dyn Numeric>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> SortByNode<T> {
+    pub fn new(array: BoxedNode<Vec<T>>, key: BoxedNode<Box<dyn Numeric>>) -> Self {
+        Self { array, key }
+    }
+}
+
+impl<'a, T> Node<Vec<T>, EvaluationContext<'a>> for SortByNode<T>
+where
+    T: Clone + Send + Sync + AsUnknownValue + 'static,
+{
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<Vec<T>> {
+        let items = self.array.evaluate(eval_context)?;
+        
+        let mut keyed = Vec::with_capacity(items.len());
+        for item in items {
+            let mut element_eval_context = eval_context.with_binding(item.as_unknown_value());
+            let key = self.key.evaluate(&mut element_eval_context)?.to_i32();
+            keyed.push((key, item));
+        }
+        
+        keyed.sort_by_key(|(key, _)| *key);
+        Ok(keyed.into_iter().map(|(_, item)| item).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::array::{FirstNode, LastNode, ReverseNode, TakeNode, TeamMembersNode};
+    use crate::nodes::character::{CharacterToHpNode, ElementNode};
+    use crate::nodes::value::{ConstantValueNode, NumericNode};
+    use crate::{BattleContext, Character, NodeError, Team, TeamSide};
+    use rand::SeedableRng;
+
+    fn enemies_by_hp() -> BoxedNode<Vec<Character>> {
+        Box::new(SortByNode::new(
+            Box::new(TeamMembersNode::new(TeamSide::Enemy)),
+            Box::new(NumericNode::new(Box::new(CharacterToHpNode::new(Box::new(ElementNode::<Character>::new()))))),
+        ))
+    }
+
+    fn names(characters: Vec<Character>) -> Vec<String> {
+        characters.into_iter().map(|character| character.name).collect()
+    }
+
+    #[test]
+    fn test_sort_take_first_last_reverse() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        let hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        let mut orc = Character::new(2, "Orc".to_string(), 100, 0, 10);
+        orc.hp = 70;
+        let mut goblin = Character::new(3, "Goblin".to_string(), 100, 0, 10);
+        goblin.hp = 20;
+        let mut slime = Character::new(4, "Slime".to_string(), 100, 0, 10);
+        slime.hp = 40;
+        let player_team = Team::new("Player".to_string(), vec![hero.clone()]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![orc, goblin, slime]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let sorted = enemies_by_hp().evaluate(&mut eval_context).unwrap();
+        assert_eq!(names(sorted), vec!["Goblin", "Slime", "Orc"]);
+
+        let reversed = ReverseNode::new(enemies_by_hp()).evaluate(&mut eval_context).unwrap();
+        assert_eq!(names(reversed), vec!["Orc", "Slime", "Goblin"]);
+
+        // 2番目にHPが低い敵 = Last(Take(SortBy(...), 2))
+        let second_weakest = LastNode::new(Box::new(TakeNode::new(
+            enemies_by_hp(),
+            Box::new(ConstantValueNode::new(2)),
+        )));
+        assert_eq!(second_weakest.evaluate(&mut eval_context).unwrap().name, "Slime");
+
+        let weakest = FirstNode::new(enemies_by_hp()).evaluate(&mut eval_context).unwrap();
+        assert_eq!(weakest.name, "Goblin");
+
+        // 要素数を超える Take は Break
+        let too_many = TakeNode::new(enemies_by_hp(), Box::new(ConstantValueNode::new(4)));
+        assert!(matches!(too_many.evaluate(&mut eval_context), Err(NodeError::Break)));
+    }
+
+    #[test]
+    fn test_empty_array_breaks() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        let hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        let player_team = Team::new("Player".to_string(), vec![hero.clone()]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        // 配列を返す操作は空配列をそのまま返す
+        assert!(enemies_by_hp().evaluate(&mut eval_context).unwrap().is_empty());
+        assert!(ReverseNode::new(enemies_by_hp()).evaluate(&mut eval_context).unwrap().is_empty());
+
+        assert!(matches!(FirstNode::new(enemies_by_hp()).evaluate(&mut eval_context), Err(NodeError::Break)));
+        assert!(matches!(LastNode::new(enemies_by_hp()).evaluate(&mut eval_context), Err(NodeError::Break)));
+        let take = TakeNode::new(enemies_by_hp(), Box::new(ConstantValueNode::new(1)));
+        assert!(matches!(take.evaluate(&mut eval_context), Err(NodeError::Break)));
+    }
+}
@@ -0,0 +1,49 @@
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::core::{NodeResult, Numeric};
+use crate::nodes::evaluation_context::EvaluationContext;
+
+/// Array内の数値の合計を返すノード（空配列は0、オーバーフローは飽和）
+pub struct SumNode<T: Numeric + Clone> {
+    array_node: BoxedNode<Vec<T>>,
+}
+
+impl<T: Numeric + Clone> SumNode<T> {
+    pub fn new(array_node: BoxedNode<Vec<T>>) -> Self {
+        Self { array_node }
+    }
+}
+
+impl<'a, T: Numeric + Clone> Node<i32, EvaluationContext<'a>> for SumNode<T> {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<i32> {
+        let array = self.array_node.evaluate(eval_context)?;
+        Ok(array.iter().fold(0i32, |sum, value| sum.saturating_add(value.to_i32())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::array::MappingNode;
+    use crate::nodes::character::{CharacterToHpNode, ElementNode};
+    use crate::{AllCharactersNode, BattleContext, Character, CharacterHP, Team, TeamSide};
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_sum_of_hp() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        let mut hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        hero.hp = 40;
+        let slime = Character::new(2, "Slime".to_string(), 30, 0, 5);
+        let player_team = Team::new("Player".to_string(), vec![hero.clone()]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![slime]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let hp_array = MappingNode::<Character, CharacterHP>::new(
+            Box::new(AllCharactersNode::new()),
+            Box::new(CharacterToHpNode::new(Box::new(ElementNode::<Character>::new()))),
+        );
+        let sum = SumNode::new(Box::new(hp_array));
+        assert_eq!(sum.evaluate(&mut eval_context).unwrap(), 70);
+    }
+}
@@ -0,0 +1,30 @@
+// Take node - takes the first N elements of an array
+use crate::core::{NodeError, NodeResult};
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::nodes::evaluation_context::EvaluationContext;
+
+/// 先頭から count 個を取り出す（count が 1..=要素数 の範囲外なら Break）
+pub struct TakeNode<T> {
+    array: BoxedNode<Vec<T>>,
+    count: BoxedNode<i32>,
+}
+
+impl<T> TakeNode<T> {
+    pub fn new(array: BoxedNode<Vec<T>>, count: BoxedNode<i32>) -> Self {
+        Self { array, count }
+    }
+}
+
+impl<'a, T: Clone + Send + Sync + 'static> Node<Vec<T>, EvaluationContext<'a>> for TakeNode<T> {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<Vec<T>> {
+        let mut items = self.array.evaluate(eval_context)?;
+        let count = self.count.evaluate(eval_context)?;
+        
+        if count < 1 || count as usize > items.len() {
+            return Err(NodeError::Break);
+        }
+        
+        items.truncate(count as usize);
+        Ok(items)
+    }
+}
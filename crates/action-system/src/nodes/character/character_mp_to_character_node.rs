@@ -0,0 +1,22 @@
+// MP Character node - returns Character from a CharacterMP node
+
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::core::character_mp::CharacterMP;
+
+pub struct CharacterMpToCharacterNode {
+    pub character_mp_node: BoxedNode<CharacterMP>,
+}
+
+impl CharacterMpToCharacterNode {
+    pub fn new(character_mp_node: BoxedNode<CharacterMP>) -> Self {
+        Self { character_mp_node }
+    }
+}
+
+impl<'a> Node<crate::Character, EvaluationContext<'a>> for CharacterMpToCharacterNode {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> crate::core::NodeResult<crate::Character> {
+        let character_mp = self.character_mp_node.evaluate(eval_context)?;
+        Ok(character_mp.get_character().clone())
+    }
+}
@@ -0,0 +1,88 @@
+// Character stat node - reads an i32 stat (attack, max HP, max MP, HP%) from a character
+
+use crate::core::NodeResult;
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::Character;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CharacterStat {
+    Attack,
+    MaxHp,
+    MaxMp,
+    /// 最大HPに対する現在HPの割合（0〜100、切り捨て。最大HPが0なら0）
+    HpPercent,
+}
+
+impl CharacterStat {
+    pub fn read(self, character: &Character) -> i32 {
+        match self {
+            CharacterStat::Attack => character.attack,
+            CharacterStat::MaxHp => character.max_hp,
+            CharacterStat::MaxMp => character.max_mp,
+            CharacterStat::HpPercent => {
+                if character.max_hp > 0 {
+                    (i64::from(character.hp) * 100 / i64::from(character.max_hp)) as i32
+                } else {
+                    0
+                }
+            }
+        }
+    }
+}
+
+pub struct CharacterStatNode {
+    stat: CharacterStat,
+    character_node: BoxedNode<Character>,
+}
+
+impl CharacterStatNode {
+    pub fn new(stat: CharacterStat, character_node: BoxedNode<Character>) -> Self {
+        Self { stat, character_node }
+    }
+}
+
+impl<'a> Node<i32, EvaluationContext<'a>> for CharacterStatNode {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<i32> {
+        let character = self.character_node.evaluate(eval_context)?;
+        Ok(self.stat.read(&character))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActingCharacterNode, Team, TeamSide};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_character_stat_node() {
+        let mut acting_character = Character::new(1, "Knight".to_string(), 120, 40, 33);
+        acting_character.hp = 45;
+
+        let player_team = Team::new("Player Team".to_string(), vec![acting_character.clone()]);
+        let enemy_team = Team::new("Enemy Team".to_string(), vec![]);
+        let battle_context = crate::BattleContext::new(&acting_character, TeamSide::Player, &player_team, &enemy_team);
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let expected = [
+            (CharacterStat::Attack, 33),
+            (CharacterStat::MaxHp, 120),
+            (CharacterStat::MaxMp, 40),
+            (CharacterStat::HpPercent, 37), // 45 / 120 = 37.5%
+        ];
+        for (stat, value) in expected {
+            let node = CharacterStatNode::new(stat, Box::new(ActingCharacterNode));
+            assert_eq!(node.evaluate(&mut eval_context).unwrap(), value, "{:?}", stat);
+        }
+    }
+
+    #[test]
+    fn test_hp_percent_with_zero_max_hp() {
+        let mut character = Character::new(1, "Ghost".to_string(), 0, 0, 1);
+        character.hp = 0;
+        assert_eq!(CharacterStat::HpPercent.read(&character), 0);
+    }
+}
@@ -0,0 +1,48 @@
+// Character MP node - returns CharacterMP from a character node
+
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::core::character_mp::CharacterMP;
+
+pub struct CharacterToMpNode {
+    pub character_node: BoxedNode<crate::Character>,
+}
+
+impl CharacterToMpNode {
+    pub fn new(character_node: BoxedNode<crate::Character>) -> Self {
+        Self { character_node }
+    }
+}
+
+impl<'a> Node<CharacterMP, EvaluationContext<'a>> for CharacterToMpNode {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> crate::core::NodeResult<CharacterMP> {
+        let character = self.character_node.evaluate(eval_context)?;
+        Ok(CharacterMP::new(character))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Character, Team, TeamSide, ActingCharacterNode};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_character_to_mp_node() {
+        let mut acting_character = Character::new(3, "Mage".to_string(), 100, 50, 25);
+        acting_character.mp = 12;
+        
+        let player_team = Team::new("Player Team".to_string(), vec![acting_character.clone()]);
+        let enemy_team = Team::new("Enemy Team".to_string(), vec![]);
+        let battle_context = crate::BattleContext::new(&acting_character, TeamSide::Player, &player_team, &enemy_team);
+        
+        let mut rng = StdRng::seed_from_u64(42);
+        let node = CharacterToMpNode::new(Box::new(ActingCharacterNode));
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        let result = node.evaluate(&mut eval_context).unwrap();
+        
+        assert_eq!(result.get_mp(), 12);
+        assert_eq!(result.get_character().id, 3);
+    }
+}
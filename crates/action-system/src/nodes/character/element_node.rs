@@ -1,20 +1,28 @@
-// Element node - references the current element being processed in array operations
+// Element node - references a value bound by an enclosing array operation or Let
 use crate::core::NodeResult;
 use crate::nodes::unified_node::CoreNode as Node;
 use crate::nodes::evaluation_context::EvaluationContext;
 use std::convert::TryInto;
 use std::marker::PhantomData;
 
-/// Generic node that returns the current element being processed in array operations
-/// This is typically used within FilterList conditions to reference the element being evaluated
+/// Generic node that returns a bound value from the evaluation context
+/// `depth` counts bindings outward from the innermost one; the compiler resolves
+/// Element, Var and named parameters to it statically
 #[derive(Debug)]
 pub struct ElementNode<T> {
+    depth: usize,
     phantom: PhantomData<T>,
 }
 
 impl<T> ElementNode<T> {
+    /// The innermost binding (the element currently being processed)
     pub fn new() -> Self {
+        Self::at(0)
+    }
+
+    pub fn at(depth: usize) -> Self {
         Self {
+            depth,
             phantom: PhantomData,
         }
     }
@@ -33,7 +41,7 @@ where
     T::Error: std::fmt::Display,
 {
     fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<T> {
-        match &eval_context.current_element {
+        match eval_context.binding(self.depth) {
             Some(value) => {
                 value.clone()
                     .try_into()
@@ -42,7 +50,7 @@ where
                     ))
             }
             None => Err(crate::core::NodeError::EvaluationError(
-                "No current element available - ElementNode requires array context".to_string()
+                format!("No value bound at depth {} - ElementNode requires array context", self.depth)
             )),
         }
     }
@@ -76,7 +84,7 @@ mod tests {
         
         let mut rng1 = rng.clone();
         let mut eval_context1 = EvaluationContext::new(&battle_context1, &mut rng1);
-        eval_context1.current_element = Some(UnknownValue::Character(character2.clone()));
+        eval_context1.bindings.push(UnknownValue::Character(character2.clone()));
         let result1: crate::Character = element_node.evaluate(&mut eval_context1).unwrap();
         assert_eq!(result1.id, 2); // Should return character2 (current element)
         assert_eq!(result1.hp, 75);
@@ -84,7 +92,7 @@ mod tests {
         // Test with character2 as acting character, character1 as current element
         let battle_context2 = BattleContext::new(&character2, TeamSide::Enemy, &player_team, &enemy_team);
         let mut eval_context2 = EvaluationContext::new(&battle_context2, &mut rng);
-        eval_context2.current_element = Some(UnknownValue::Character(character1.clone()));
+        eval_context2.bindings.push(UnknownValue::Character(character1.clone()));
         
         let result2: crate::Character = element_node.evaluate(&mut eval_context2).unwrap();
         assert_eq!(result2.id, 1); // Should return character1 (current element)
@@ -103,7 +111,7 @@ mod tests {
         // Test with Value element - should succeed with Node<i32>
         let mut rng2 = rng.clone();
         let mut value_eval_context = EvaluationContext::new(&battle_context, &mut rng2);
-        value_eval_context.current_element = Some(UnknownValue::Value(42));
+        value_eval_context.bindings.push(UnknownValue::Value(42));
         let element_node_i32 = ElementNode::<i32>::new();
         let value_result: NodeResult<i32> = element_node_i32.evaluate(&mut value_eval_context);
         assert!(value_result.is_ok(), "ElementNode should succeed with Value element");
@@ -112,7 +120,7 @@ mod tests {
         // Test with TeamSide element - should succeed with Node<TeamSide>
         let mut rng3 = rng.clone();
         let mut team_eval_context = EvaluationContext::new(&battle_context, &mut rng3);
-        team_eval_context.current_element = Some(UnknownValue::TeamSide(TeamSide::Enemy));
+        team_eval_context.bindings.push(UnknownValue::TeamSide(TeamSide::Enemy));
         let element_node_team = ElementNode::<TeamSide>::new();
         let team_result: NodeResult<crate::TeamSide> = element_node_team.evaluate(&mut team_eval_context);
         assert!(team_result.is_ok(), "ElementNode should succeed with TeamSide element");
@@ -122,7 +130,7 @@ mod tests {
         let mut rng4 = rng.clone();
         let character_hp = crate::core::character_hp::CharacterHP::new(character.clone());
         let mut hp_eval_context = EvaluationContext::new(&battle_context, &mut rng4);
-        hp_eval_context.current_element = Some(UnknownValue::CharacterHP(character_hp.clone()));
+        hp_eval_context.bindings.push(UnknownValue::CharacterHP(character_hp.clone()));
         let element_node_hp = ElementNode::<crate::core::character_hp::CharacterHP>::new();
         let hp_result: NodeResult<crate::core::character_hp::CharacterHP> = element_node_hp.evaluate(&mut hp_eval_context);
         assert!(hp_result.is_ok(), "ElementNode should succeed with CharacterHP element");
@@ -149,21 +157,37 @@ mod tests {
         let element_node = ElementNode::<Character>::new();
         let mut rng4 = rng.clone();
         let mut eval_context = EvaluationContext::new(&battle_context, &mut rng4);
-        eval_context.current_element = Some(UnknownValue::Character(current_element.clone()));
+        eval_context.bindings.push(UnknownValue::Character(current_element.clone()));
         
         // Test unified implementation
         let result: crate::Character = element_node.evaluate(&mut eval_context).unwrap();
         assert_eq!(result.id, 123);
         
         // Test as boxed trait object
-        let boxed_node: BoxedNode<crate::Character> = Box::new(ElementNode::<Character>::default());
+        let boxed_node: BoxedNode<crate::Character> = Box::<ElementNode<Character>>::default();
         let boxed_result = boxed_node.evaluate(&mut eval_context).unwrap();
         assert_eq!(boxed_result.id, 123);
         
         // Test with new element context
         let element_character = Character::new(456, "NewElement".to_string(), 60, 60, 20);
-        let mut element_eval_context = eval_context.with_current_element_from_context(UnknownValue::Character(element_character.clone()));
+        let mut element_eval_context = eval_context.with_binding(UnknownValue::Character(element_character.clone()));
         let element_result: crate::Character = element_node.evaluate(&mut element_eval_context).unwrap();
         assert_eq!(element_result.id, 456); // Should return new element character
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_element_node_resolves_outer_bindings_by_depth() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        let character = Character::new(1, "Test".to_string(), 100, 100, 20);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let mut outer_context = eval_context.with_binding(UnknownValue::Character(character.clone()));
+        let mut inner_context = outer_context.with_binding(UnknownValue::Value(7));
+
+        assert_eq!(ElementNode::<i32>::new().evaluate(&mut inner_context).unwrap(), 7);
+        assert_eq!(ElementNode::<Character>::at(1).evaluate(&mut inner_context).unwrap().id, 1);
+        assert!(ElementNode::<Character>::at(2).evaluate(&mut inner_context).is_err());
+    }
+}
@@ -3,6 +3,9 @@ pub mod acting_character_node;
 pub mod character_to_hp_node;
 pub mod character_hp_value_node;
 pub mod character_hp_to_character_node;
+pub mod character_to_mp_node;
+pub mod character_mp_to_character_node;
+pub mod character_stat_node;
 pub mod element_node;
 
 pub use character_nodes::{BattleContext};
@@ -10,4 +13,7 @@ pub use acting_character_node::ActingCharacterNode;
 pub use character_to_hp_node::CharacterToHpNode;
 pub use character_hp_value_node::CharacterHpValueNode;
 pub use character_hp_to_character_node::CharacterHpToCharacterNode;
+pub use character_to_mp_node::CharacterToMpNode;
+pub use character_mp_to_character_node::CharacterMpToCharacterNode;
+pub use character_stat_node::{CharacterStat, CharacterStatNode};
 pub use element_node::ElementNode;
\ No newline at end of file
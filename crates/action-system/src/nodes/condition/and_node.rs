@@ -0,0 +1,76 @@
+// And node - true only if both conditions are true (short-circuit)
+
+use crate::core::NodeResult;
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::unified_node::{BoxedNode, CoreNode as Node};
+
+pub struct AndNode {
+    left: BoxedNode<bool>,
+    right: BoxedNode<bool>,
+}
+
+impl AndNode {
+    pub fn new(left: BoxedNode<bool>, right: BoxedNode<bool>) -> Self {
+        Self { left, right }
+    }
+}
+
+impl<'a> Node<bool, EvaluationContext<'a>> for AndNode {
+    fn evaluate(&self, eval_context: &mut EvaluationContext<'a>) -> NodeResult<bool> {
+        // 左が false なら右は評価しない（RNGも消費しない）
+        if !self.left.evaluate(eval_context)? {
+            return Ok(false);
+        }
+        self.right.evaluate(eval_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::condition::RandomConditionNode;
+    use crate::nodes::test_nodes::ConstantBoolNode;
+    use crate::{BattleContext, Character, Team, TeamSide};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_and_truth_table() {
+        let character = Character::new(1, "Test".to_string(), 100, 50, 10);
+        let team = Team::new("Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for (left, right) in [(true, true), (true, false), (false, true), (false, false)] {
+            let node = AndNode::new(Box::new(ConstantBoolNode(left)), Box::new(ConstantBoolNode(right)));
+            let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+            assert_eq!(node.evaluate(&mut eval_context).unwrap(), left && right);
+        }
+    }
+
+    #[test]
+    fn test_and_short_circuits_random_condition() {
+        let character = Character::new(1, "Test".to_string(), 100, 50, 10);
+        let team = Team::new("Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+
+        let node = AndNode::new(Box::new(ConstantBoolNode(false)), Box::new(RandomConditionNode));
+        let mut rng = StdRng::seed_from_u64(42);
+        {
+            let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+            assert!(!node.evaluate(&mut eval_context).unwrap());
+        }
+        // RNGは消費されていない
+        let mut fresh_rng = StdRng::seed_from_u64(42);
+        assert_eq!(rng.gen::<u64>(), fresh_rng.gen::<u64>());
+
+        let node = AndNode::new(Box::new(ConstantBoolNode(true)), Box::new(RandomConditionNode));
+        let mut rng = StdRng::seed_from_u64(42);
+        {
+            let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+            node.evaluate(&mut eval_context).unwrap();
+        }
+        let mut fresh_rng = StdRng::seed_from_u64(42);
+        assert_ne!(rng.gen::<u64>(), fresh_rng.gen::<u64>());
+    }
+}
@@ -0,0 +1,353 @@
+// Comparison node - compares two Numeric values (GreaterThan / LessThan / GreaterOrEqual / LessOrEqual)
+
+use std::cmp::Ordering;
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::core::{NodeResult, Numeric};
+use crate::nodes::evaluation_context::EvaluationContext;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComparisonOperator {
+    GreaterThan,
+    LessThan,
+    GreaterOrEqual,
+    LessOrEqual,
+}
+
+impl ComparisonOperator {
+    pub fn apply(self, left: i32, right: i32) -> bool {
+        let ordering = left.cmp(&right);
+        match self {
+            ComparisonOperator::GreaterThan => ordering == Ordering::Greater,
+            ComparisonOperator::LessThan => ordering == Ordering::Less,
+            ComparisonOperator::GreaterOrEqual => ordering != Ordering::Less,
+            ComparisonOperator::LessOrEqual => ordering != Ordering::Greater,
+        }
+    }
+}
+
+/// 2つの Numeric を to_i32() の値で比べるノード
+pub struct ComparisonNode {
+    operator: ComparisonOperator,
+    left_node: BoxedNode<Box<dyn Numeric>>,
+    right_node: BoxedNode<Box<dyn Numeric>>,
+}
+
+impl ComparisonNode {
+    pub fn new(
+        operator: ComparisonOperator,
+        left_node: BoxedNode<Box<dyn Numeric>>,
+        right_node: BoxedNode<Box<dyn Numeric>>,
+    ) -> Self {
+        Self { operator, left_node, right_node }
+    }
+}
+
+impl<'a> Node<bool, EvaluationContext<'a>> for ComparisonNode {
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<bool> {
+        let left_value = self.left_node.evaluate(eval_context)?;
+        let right_value = self.right_node.evaluate(eval_context)?;
+        
+        Ok(self.operator.apply(left_value.to_i32(), right_value.to_i32()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Character, CharacterHP};
+    use crate::nodes::character::BattleContext;
+    use crate::nodes::value::{ConstantValueNode, NumericNode};
+    use crate::TeamSide;
+    use crate::Team;
+    use rand::SeedableRng;
+
+    // Test helper for constant CharacterHP
+    struct ConstantCharacterHPNode {
+        character_hp: CharacterHP,
+    }
+
+    impl ConstantCharacterHPNode {
+        fn new(character_hp: CharacterHP) -> Self {
+            Self { character_hp }
+        }
+    }
+
+    impl<'a> Node<CharacterHP, EvaluationContext<'a>> for ConstantCharacterHPNode {
+        fn evaluate(&self, _eval_context: &mut EvaluationContext) -> NodeResult<CharacterHP> {
+            Ok(self.character_hp.clone())
+        }
+    }
+
+    fn value(value: i32) -> BoxedNode<Box<dyn Numeric>> {
+        Box::new(NumericNode::new(Box::new(ConstantValueNode::new(value))))
+    }
+
+    #[test]
+    fn test_operators() {
+        use ComparisonOperator::*;
+        for (left, right) in [(49, 50), (50, 50), (51, 50)] {
+            assert_eq!(GreaterThan.apply(left, right), left > right);
+            assert_eq!(LessThan.apply(left, right), left < right);
+            assert_eq!(GreaterOrEqual.apply(left, right), left >= right);
+            assert_eq!(LessOrEqual.apply(left, right), left <= right);
+        }
+    }
+
+    #[test]
+    fn test_greater_than_i32() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50))));
+        let right_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(30))));
+        let gt_node = ComparisonNode::new(ComparisonOperator::GreaterThan, left_node, right_node);
+        
+        let result = gt_node.evaluate(&mut eval_context).unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn test_greater_than_i32_false() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(20))));
+        let right_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(30))));
+        let gt_node = ComparisonNode::new(ComparisonOperator::GreaterThan, left_node, right_node);
+        
+        let result = gt_node.evaluate(&mut eval_context).unwrap();
+        assert!(!result);
+    }
+
+    #[test]
+    fn test_character_hp_vs_value_greater_than() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let test_char = Character::new(1, "TestChar".to_string(), 100, 100, 10);
+        let char_hp = CharacterHP::from_character_with_hp(test_char, 80);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantCharacterHPNode::new(char_hp))));
+        let right_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50))));
+        let gt_node = ComparisonNode::new(ComparisonOperator::GreaterThan, left_node, right_node);
+        
+        let result = gt_node.evaluate(&mut eval_context).unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn test_value_vs_character_hp_greater_than() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let test_char = Character::new(1, "TestChar".to_string(), 100, 100, 10);
+        let char_hp = CharacterHP::from_character_with_hp(test_char, 30);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50))));
+        let right_node = Box::new(NumericNode::new(Box::new(ConstantCharacterHPNode::new(char_hp))));
+        let gt_node = ComparisonNode::new(ComparisonOperator::GreaterThan, left_node, right_node);
+        
+        let result = gt_node.evaluate(&mut eval_context).unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn test_character_hp_vs_character_hp_greater_than() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let test_char1 = Character::new(1, "TestChar1".to_string(), 100, 100, 10);
+        let test_char2 = Character::new(2, "TestChar2".to_string(), 100, 100, 10);
+        let char_hp1 = CharacterHP::from_character_with_hp(test_char1, 80);
+        let char_hp2 = CharacterHP::from_character_with_hp(test_char2, 60);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantCharacterHPNode::new(char_hp1))));
+        let right_node = Box::new(NumericNode::new(Box::new(ConstantCharacterHPNode::new(char_hp2))));
+        let gt_node = ComparisonNode::new(ComparisonOperator::GreaterThan, left_node, right_node);
+        
+        let result = gt_node.evaluate(&mut eval_context).unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn test_less_than_i32() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(30))));
+        let right_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50))));
+        let lt_node = ComparisonNode::new(ComparisonOperator::LessThan, left_node, right_node);
+        
+        let result = lt_node.evaluate(&mut eval_context).unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn test_less_than_i32_false() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50))));
+        let right_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(30))));
+        let lt_node = ComparisonNode::new(ComparisonOperator::LessThan, left_node, right_node);
+        
+        let result = lt_node.evaluate(&mut eval_context).unwrap();
+        assert!(!result);
+    }
+
+    #[test]
+    fn test_character_hp_vs_value_less_than() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let test_char = Character::new(1, "TestChar".to_string(), 100, 100, 10);
+        let char_hp = CharacterHP::from_character_with_hp(test_char, 30);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantCharacterHPNode::new(char_hp))));
+        let right_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50))));
+        let lt_node = ComparisonNode::new(ComparisonOperator::LessThan, left_node, right_node);
+        
+        let result = lt_node.evaluate(&mut eval_context).unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn test_value_vs_character_hp_less_than() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let test_char = Character::new(1, "TestChar".to_string(), 100, 100, 10);
+        let char_hp = CharacterHP::from_character_with_hp(test_char, 80);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50))));
+        let right_node = Box::new(NumericNode::new(Box::new(ConstantCharacterHPNode::new(char_hp))));
+        let lt_node = ComparisonNode::new(ComparisonOperator::LessThan, left_node, right_node);
+        
+        let result = lt_node.evaluate(&mut eval_context).unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn test_character_hp_vs_character_hp_less_than() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let test_char1 = Character::new(1, "TestChar1".to_string(), 100, 100, 10);
+        let test_char2 = Character::new(2, "TestChar2".to_string(), 100, 100, 10);
+        let char_hp1 = CharacterHP::from_character_with_hp(test_char1, 60);
+        let char_hp2 = CharacterHP::from_character_with_hp(test_char2, 80);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantCharacterHPNode::new(char_hp1))));
+        let right_node = Box::new(NumericNode::new(Box::new(ConstantCharacterHPNode::new(char_hp2))));
+        let lt_node = ComparisonNode::new(ComparisonOperator::LessThan, left_node, right_node);
+        
+        let result = lt_node.evaluate(&mut eval_context).unwrap();
+        assert!(result);
+    }
+
+    #[test]
+    fn test_greater_or_equal_boundary() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        for (left, right, expected) in [(50, 50, true), (49, 50, false)] {
+            let node = ComparisonNode::new(ComparisonOperator::GreaterOrEqual, value(left), value(right));
+            assert_eq!(node.evaluate(&mut eval_context).unwrap(), expected, "{} >= {}", left, right);
+        }
+    }
+
+    #[test]
+    fn test_character_hp_vs_value_greater_or_equal() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let test_char = Character::new(1, "TestChar".to_string(), 100, 100, 10);
+        let char_hp = CharacterHP::from_character_with_hp(test_char, 50);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantCharacterHPNode::new(char_hp))));
+        let node = ComparisonNode::new(ComparisonOperator::GreaterOrEqual, left_node, value(50));
+        
+        assert!(node.evaluate(&mut eval_context).unwrap());
+    }
+
+    #[test]
+    fn test_less_or_equal_boundary() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        for (left, right, expected) in [(50, 50, true), (51, 50, false)] {
+            let node = ComparisonNode::new(ComparisonOperator::LessOrEqual, value(left), value(right));
+            assert_eq!(node.evaluate(&mut eval_context).unwrap(), expected, "{} <= {}", left, right);
+        }
+    }
+
+    #[test]
+    fn test_character_hp_vs_value_less_or_equal() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+        
+        let test_char = Character::new(1, "TestChar".to_string(), 100, 100, 10);
+        let char_hp = CharacterHP::from_character_with_hp(test_char, 50);
+        
+        let left_node = Box::new(NumericNode::new(Box::new(ConstantCharacterHPNode::new(char_hp))));
+        let node = ComparisonNode::new(ComparisonOperator::LessOrEqual, left_node, value(50));
+        
+        assert!(node.evaluate(&mut eval_context).unwrap());
+    }
+}
@@ -23,6 +23,25 @@ impl<'a, T: PartialEq + fmt::Debug + Clone + Send + Sync + 'static> Node<bool, E
     }
 }
 
+pub struct NotEqConditionNode<T> {
+    left: BoxedNode<T>,
+    right: BoxedNode<T>,
+}
+
+impl<T> NotEqConditionNode<T> {
+    pub fn new(left: BoxedNode<T>, right: BoxedNode<T>) -> Self {
+        Self { left, right }
+    }
+}
+
+impl<'a, T: PartialEq + fmt::Debug + Clone + Send + Sync + 'static> Node<bool, EvaluationContext<'a>> for NotEqConditionNode<T> {
+    fn evaluate(&self, context: &mut EvaluationContext) -> NodeResult<bool> {
+        let left_value = self.left.evaluate(context)?;
+        let right_value = self.right.evaluate(context)?;
+        Ok(left_value != right_value)
+    }
+}
+
 
 pub struct CharacterTeamNode {
     character_node: BoxedNode<crate::Character>,
@@ -53,4 +72,35 @@ impl<'a> Node<TeamSide, EvaluationContext<'a>> for CharacterTeamNode {
             character.id
         )))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::character::{ActingCharacterNode, BattleContext};
+    use crate::nodes::value::{ConstantValueNode, EnemyNode, HeroNode};
+    use crate::{Character, Team};
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_eq_and_not_eq() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        let character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        // TeamSide
+        let eq = EqConditionNode::<TeamSide>::new(Box::new(EnemyNode), Box::new(HeroNode));
+        let not_eq = NotEqConditionNode::<TeamSide>::new(Box::new(EnemyNode), Box::new(HeroNode));
+        assert!(!eq.evaluate(&mut eval_context).unwrap());
+        assert!(not_eq.evaluate(&mut eval_context).unwrap());
+
+        // i32
+        let not_eq = NotEqConditionNode::<i32>::new(Box::new(ConstantValueNode::new(50)), Box::new(ConstantValueNode::new(50)));
+        assert!(!not_eq.evaluate(&mut eval_context).unwrap());
+
+        // Character
+        let not_eq = NotEqConditionNode::<Character>::new(Box::new(ActingCharacterNode), Box::new(ActingCharacterNode));
+        assert!(!not_eq.evaluate(&mut eval_context).unwrap());
+    }
+}
@@ -0,0 +1,66 @@
+// FirstOf node - tries alternatives in order and falls through on Break
+
+use crate::core::{NodeError, NodeResult};
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::unified_node::{BoxedNode, CoreNode as Node};
+
+/// 候補を先頭から評価し、最初に Break しなかった結果を返す。
+/// 全ての候補が Break した場合は Break（それ以外のエラーはそのまま伝播する）
+pub struct FirstOfNode<T> {
+    alternatives: Vec<BoxedNode<T>>,
+}
+
+impl<T> FirstOfNode<T> {
+    pub fn new(alternatives: Vec<BoxedNode<T>>) -> Self {
+        Self { alternatives }
+    }
+}
+
+impl<'a, T: 'static> Node<T, EvaluationContext<'a>> for FirstOfNode<T> {
+    fn evaluate(&self, eval_context: &mut EvaluationContext<'a>) -> NodeResult<T> {
+        for alternative in &self.alternatives {
+            match alternative.evaluate(eval_context) {
+                Err(NodeError::Break) => continue,
+                result => return result,
+            }
+        }
+        Err(NodeError::Break)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::test_nodes::{BreakNode, FailingNode};
+    use crate::nodes::value::ConstantValueNode;
+    use crate::{BattleContext, Character, Team, TeamSide};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_first_of_node() {
+        let character = Character::new(1, "Test".to_string(), 100, 50, 10);
+        let team = Team::new("Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let node = FirstOfNode::new(vec![
+            Box::new(BreakNode),
+            Box::new(ConstantValueNode::new(2)),
+            Box::new(ConstantValueNode::new(3)),
+        ]);
+        assert_eq!(node.evaluate(&mut eval_context).unwrap(), 2);
+
+        // 全て Break なら Break
+        let node = FirstOfNode::<i32>::new(vec![Box::new(BreakNode), Box::new(BreakNode)]);
+        assert!(matches!(node.evaluate(&mut eval_context), Err(NodeError::Break)));
+
+        // Break 以外のエラーは次の候補を試さずに伝播する
+        let node = FirstOfNode::<i32>::new(vec![
+            Box::new(FailingNode(NodeError::EvaluationError("boom".to_string()))),
+            Box::new(ConstantValueNode::new(2)),
+        ]);
+        assert!(matches!(node.evaluate(&mut eval_context), Err(NodeError::EvaluationError(_))));
+    }
+}
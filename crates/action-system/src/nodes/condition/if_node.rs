@@ -0,0 +1,76 @@
+// If node - evaluates one of two branches depending on a condition
+
+use crate::core::NodeResult;
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::unified_node::{BoxedNode, CoreNode as Node};
+
+/// 値を返す条件分岐。選ばれなかった分岐は評価しない
+pub struct IfNode<T> {
+    condition: BoxedNode<bool>,
+    then: BoxedNode<T>,
+    otherwise: BoxedNode<T>,
+}
+
+impl<T> IfNode<T> {
+    pub fn new(condition: BoxedNode<bool>, then: BoxedNode<T>, otherwise: BoxedNode<T>) -> Self {
+        Self { condition, then, otherwise }
+    }
+}
+
+impl<'a, T: 'static> Node<T, EvaluationContext<'a>> for IfNode<T> {
+    fn evaluate(&self, eval_context: &mut EvaluationContext<'a>) -> NodeResult<T> {
+        if self.condition.evaluate(eval_context)? {
+            self.then.evaluate(eval_context)
+        } else {
+            self.otherwise.evaluate(eval_context)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::NodeError;
+    use crate::nodes::character::ActingCharacterNode;
+    use crate::nodes::test_nodes::{BreakNode, ConstantBoolNode};
+    use crate::nodes::value::ConstantValueNode;
+    use crate::{BattleContext, Character, Team, TeamSide};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_if_node_selects_branch() {
+        let character = Character::new(1, "Test".to_string(), 100, 50, 10);
+        let team = Team::new("Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let number = |condition| {
+            IfNode::<i32>::new(
+                Box::new(ConstantBoolNode(condition)),
+                Box::new(ConstantValueNode::new(1)),
+                Box::new(ConstantValueNode::new(2)),
+            )
+        };
+        assert_eq!(number(true).evaluate(&mut eval_context).unwrap(), 1);
+        assert_eq!(number(false).evaluate(&mut eval_context).unwrap(), 2);
+
+        // 選ばれなかった分岐は評価されない
+        let character_node = IfNode::<Character>::new(
+            Box::new(ConstantBoolNode(true)),
+            Box::new(ActingCharacterNode),
+            Box::new(BreakNode),
+        );
+        assert_eq!(character_node.evaluate(&mut eval_context).unwrap().id, 1);
+
+        // 条件の Break はそのまま伝播する
+        let result = IfNode::<i32>::new(
+            Box::new(BreakNode),
+            Box::new(ConstantValueNode::new(1)),
+            Box::new(ConstantValueNode::new(2)),
+        )
+        .evaluate(&mut eval_context);
+        assert!(matches!(result, Err(NodeError::Break)));
+    }
+}
@@ -1,11 +1,19 @@
 pub mod condition_check_node;
 pub mod random_condition_node;
 pub mod eq_condition_node;
-pub mod greater_than_node;
-pub mod less_than_node;
+pub mod comparison_node;
+pub mod and_node;
+pub mod or_node;
+pub mod not_node;
+pub mod if_node;
+pub mod first_of_node;
 
 pub use random_condition_node::RandomConditionNode;
 pub use condition_check_node::ConditionCheckNode;
-pub use eq_condition_node::{EqConditionNode, CharacterTeamNode};
-pub use greater_than_node::GreaterThanNode;
-pub use less_than_node::LessThanNode;
\ No newline at end of file
+pub use eq_condition_node::{EqConditionNode, NotEqConditionNode, CharacterTeamNode};
+pub use comparison_node::{ComparisonNode, ComparisonOperator};
+pub use and_node::AndNode;
+pub use or_node::OrNode;
+pub use not_node::NotNode;
+pub use if_node::IfNode;
+pub use first_of_node::FirstOfNode;
\ No newline at end of file
@@ -0,0 +1,47 @@
+// Not node - negates a condition
+
+use crate::core::NodeResult;
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::unified_node::{BoxedNode, CoreNode as Node};
+
+pub struct NotNode {
+    condition: BoxedNode<bool>,
+}
+
+impl NotNode {
+    pub fn new(condition: BoxedNode<bool>) -> Self {
+        Self { condition }
+    }
+}
+
+impl<'a> Node<bool, EvaluationContext<'a>> for NotNode {
+    fn evaluate(&self, eval_context: &mut EvaluationContext<'a>) -> NodeResult<bool> {
+        Ok(!self.condition.evaluate(eval_context)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::NodeError;
+    use crate::nodes::test_nodes::{BreakNode, ConstantBoolNode};
+    use crate::{BattleContext, Character, Team, TeamSide};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_not_node() {
+        let character = Character::new(1, "Test".to_string(), 100, 50, 10);
+        let team = Team::new("Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        assert!(!NotNode::new(Box::new(ConstantBoolNode(true))).evaluate(&mut eval_context).unwrap());
+        assert!(NotNode::new(Box::new(ConstantBoolNode(false))).evaluate(&mut eval_context).unwrap());
+
+        // Break はそのまま伝播する（否定しない）
+        let result = NotNode::new(Box::new(BreakNode)).evaluate(&mut eval_context);
+        assert!(matches!(result, Err(NodeError::Break)));
+    }
+}
@@ -91,7 +91,7 @@ mod tests {
         let first_result = results[0];
         let has_different_result = results.iter().any(|&result| result != first_result);
         
-        assert_eq!(has_different_result, true, "Multiple evaluations with same RNG should produce different results");
+        assert!(has_different_result, "Multiple evaluations with same RNG should produce different results");
     }
 
     #[test]
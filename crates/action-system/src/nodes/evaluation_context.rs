@@ -1,13 +1,15 @@
 // Evaluation context - manages the context for node evaluation including current element being processed
 use crate::nodes::character::BattleContext;
 use crate::nodes::unknown_value::UnknownValue;
+use std::ops::{Deref, DerefMut};
 
-/// Context for evaluating nodes, includes battle context, current element, and RNG
+/// Context for evaluating nodes, includes battle context, bound values, and RNG
 pub struct EvaluationContext<'a> {
     /// The battle context containing teams and acting character
     pub battle_context: &'a BattleContext<'a>,
-    /// The current element being processed (used by Element node in array operations)
-    pub current_element: Option<UnknownValue>,
+    /// Values bound by array operations and Let, innermost last
+    /// The compiler resolves Element / Var / named parameters to a depth in this stack
+    pub bindings: Vec<UnknownValue>,
     /// Random number generator for node evaluation
     pub rng: &'a mut dyn rand::RngCore,
 }
@@ -17,7 +19,7 @@ impl<'a> EvaluationContext<'a> {
     pub fn new(battle_context: &'a BattleContext<'a>, rng: &'a mut dyn rand::RngCore) -> Self {
         Self {
             battle_context,
-            current_element: None,
+            bindings: Vec::new(),
             rng,
         }
     }
@@ -28,14 +30,41 @@ impl<'a> EvaluationContext<'a> {
         self.battle_context
     }
     
-    /// Creates a new EvaluationContext with a different current element
-    /// This method takes ownership of the RNG to avoid borrowing issues
-    pub fn with_current_element_from_context(&mut self, element: UnknownValue) -> EvaluationContext<'_> {
-        EvaluationContext {
-            battle_context: self.battle_context,
-            current_element: Some(element),
-            rng: &mut *self.rng,
-        }
+    /// Pushes an innermost binding for the lifetime of the returned scope
+    /// The binding is popped when the scope is dropped, so the stack is never copied
+    pub fn with_binding(&mut self, value: UnknownValue) -> BindingScope<'_, 'a> {
+        self.bindings.push(value);
+        BindingScope { context: self }
+    }
+    
+    /// Gets the binding `depth` levels out from the innermost one (0 = innermost)
+    pub fn binding(&self, depth: usize) -> Option<&UnknownValue> {
+        self.bindings.iter().rev().nth(depth)
+    }
+}
+
+/// EvaluationContext with one extra binding, removed again on drop
+pub struct BindingScope<'c, 'a> {
+    context: &'c mut EvaluationContext<'a>,
+}
+
+impl<'a> Deref for BindingScope<'_, 'a> {
+    type Target = EvaluationContext<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.context
+    }
+}
+
+impl DerefMut for BindingScope<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.context
+    }
+}
+
+impl Drop for BindingScope<'_, '_> {
+    fn drop(&mut self) {
+        self.context.bindings.pop();
     }
 }
 
@@ -57,25 +86,58 @@ mod tests {
         let battle_context = BattleContext::new(&acting_character, TeamSide::Player, &team, &team);
         
         let mut eval_context1 = EvaluationContext::new(&battle_context, &mut rng1);
-        eval_context1.current_element = Some(UnknownValue::Character(element1.clone()));
+        eval_context1.bindings.push(UnknownValue::Character(element1.clone()));
         
         // Check the original context first
-        if let Some(UnknownValue::Character(character)) = &eval_context1.current_element {
+        if let Some(UnknownValue::Character(character)) = eval_context1.binding(0) {
             assert_eq!(character.id, 2);
         } else {
             panic!("Expected Character element");
         }
         
         // Now create the new context with a different element
-        let eval_context2 = eval_context1.with_current_element_from_context(UnknownValue::Character(element2.clone()));
+        let eval_context2 = eval_context1.with_binding(UnknownValue::Character(element2.clone()));
         
-        if let Some(UnknownValue::Character(character)) = &eval_context2.current_element {
+        if let Some(UnknownValue::Character(character)) = eval_context2.binding(0) {
             assert_eq!(character.id, 3);
         } else {
             panic!("Expected Character element");
         }
     }
     
+    #[test]
+    fn test_nested_bindings_unwind() {
+        use crate::nodes::array::{AnyNode, TeamMembersNode};
+        use crate::nodes::test_nodes::BreakNode;
+        use crate::nodes::unified_node::CoreNode as Node;
+        use crate::NodeError;
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+
+        let acting_character = Character::new(1, "Acting".to_string(), 100, 100, 20);
+        let team = Team::new("Test Team".to_string(), vec![acting_character.clone()]);
+        let battle_context = BattleContext::new(&acting_character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        {
+            let mut outer = eval_context.with_binding(UnknownValue::Value(1));
+            {
+                let inner = outer.with_binding(UnknownValue::Value(2));
+                assert!(matches!(inner.binding(0), Some(UnknownValue::Value(2))));
+                assert!(matches!(inner.binding(1), Some(UnknownValue::Value(1))));
+            }
+            // 内側のスコープを抜けると外側の束縛だけが残る
+            assert_eq!(outer.bindings.len(), 1);
+            assert!(matches!(outer.binding(0), Some(UnknownValue::Value(1))));
+        }
+        assert!(eval_context.bindings.is_empty());
+
+        // エラーで抜けても束縛は戻る
+        let any = AnyNode::new(Box::new(TeamMembersNode::new(TeamSide::Player)), Box::new(BreakNode));
+        assert!(matches!(any.evaluate(&mut eval_context), Err(NodeError::Break)));
+        assert!(eval_context.bindings.is_empty());
+    }
+
     #[test]
     fn test_evaluation_context_with_different_element_types() {
         use rand::SeedableRng;
@@ -91,8 +153,8 @@ mod tests {
         // Test with character element
         let character_element = Character::new(2, "Element".to_string(), 80, 100, 15);
         let mut eval_context_char = EvaluationContext::new(&battle_context, &mut rng1);
-        eval_context_char.current_element = Some(UnknownValue::Character(character_element));
-        if let Some(UnknownValue::Character(character)) = &eval_context_char.current_element {
+        eval_context_char.bindings.push(UnknownValue::Character(character_element));
+        if let Some(UnknownValue::Character(character)) = eval_context_char.binding(0) {
             assert_eq!(character.id, 2);
         } else {
             panic!("Expected Character element");
@@ -100,8 +162,8 @@ mod tests {
         
         // Test with value element
         let mut eval_context_value = EvaluationContext::new(&battle_context, &mut rng2);
-        eval_context_value.current_element = Some(UnknownValue::Value(42));
-        if let Some(UnknownValue::Value(value)) = &eval_context_value.current_element {
+        eval_context_value.bindings.push(UnknownValue::Value(42));
+        if let Some(UnknownValue::Value(value)) = eval_context_value.binding(0) {
             assert_eq!(*value, 42);
         } else {
             panic!("Expected Value element");
@@ -109,8 +171,8 @@ mod tests {
         
         // Test with team side element
         let mut eval_context_team = EvaluationContext::new(&battle_context, &mut rng3);
-        eval_context_team.current_element = Some(UnknownValue::TeamSide(TeamSide::Enemy));
-        if let Some(UnknownValue::TeamSide(side)) = &eval_context_team.current_element {
+        eval_context_team.bindings.push(UnknownValue::TeamSide(TeamSide::Enemy));
+        if let Some(UnknownValue::TeamSide(side)) = eval_context_team.binding(0) {
             assert_eq!(*side, TeamSide::Enemy);
         } else {
             panic!("Expected TeamSide element");
@@ -120,8 +182,8 @@ mod tests {
         let character_for_hp = Character::new(3, "HP Element".to_string(), 70, 100, 18);
         let character_hp = crate::core::character_hp::CharacterHP::new(character_for_hp);
         let mut eval_context_hp = EvaluationContext::new(&battle_context, &mut rng4);
-        eval_context_hp.current_element = Some(UnknownValue::CharacterHP(character_hp.clone()));
-        if let Some(UnknownValue::CharacterHP(hp)) = &eval_context_hp.current_element {
+        eval_context_hp.bindings.push(UnknownValue::CharacterHP(character_hp.clone()));
+        if let Some(UnknownValue::CharacterHP(hp)) = eval_context_hp.binding(0) {
             assert_eq!(hp.hp_value, 70);
         } else {
             panic!("Expected CharacterHP element");
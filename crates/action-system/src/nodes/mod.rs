@@ -3,8 +3,11 @@ pub mod value;
 pub mod character;
 pub mod action;
 pub mod array;
+pub mod variable;
 pub mod evaluation_context;
 pub mod unified_node;
 pub mod unknown_value;
+#[cfg(test)]
+pub(crate) mod test_nodes;
 
 // No re-exports - use explicit module paths
\ No newline at end of file
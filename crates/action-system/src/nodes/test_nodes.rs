@@ -0,0 +1,32 @@
+// Test nodes - fixed results for node tests
+
+use crate::core::{NodeError, NodeResult};
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::unified_node::CoreNode as Node;
+
+/// 常に同じ真偽値を返す
+pub struct ConstantBoolNode(pub bool);
+
+impl<'a> Node<bool, EvaluationContext<'a>> for ConstantBoolNode {
+    fn evaluate(&self, _eval_context: &mut EvaluationContext<'a>) -> NodeResult<bool> {
+        Ok(self.0)
+    }
+}
+
+/// どの型としても Break する（評価されないことの確認にも使う）
+pub struct BreakNode;
+
+impl<'a, T> Node<T, EvaluationContext<'a>> for BreakNode {
+    fn evaluate(&self, _eval_context: &mut EvaluationContext<'a>) -> NodeResult<T> {
+        Err(NodeError::Break)
+    }
+}
+
+/// どの型としても指定したエラーを返す
+pub struct FailingNode(pub NodeError);
+
+impl<'a, T> Node<T, EvaluationContext<'a>> for FailingNode {
+    fn evaluate(&self, _eval_context: &mut EvaluationContext<'a>) -> NodeResult<T> {
+        Err(self.0.clone())
+    }
+}
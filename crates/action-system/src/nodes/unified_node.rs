@@ -79,7 +79,7 @@ mod tests {
         
         let condition_node = TestConditionNode { result: true };
         let result = condition_node.evaluate(&mut eval_context).unwrap();
-        assert_eq!(result, true);
+        assert!(result);
     }
 
     #[test]
@@ -118,6 +118,6 @@ mod tests {
         let condition_result = condition_node.evaluate(&mut eval_context).unwrap();
         
         assert_eq!(char_result, 99);
-        assert_eq!(condition_result, false);
+        assert!(!condition_result);
     }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use crate::Character;
 use crate::core::character_hp::CharacterHP;
+use crate::core::character_mp::CharacterMP;
 use std::convert::TryFrom;
 
 /// Represents different types of values that can be used as unknown values in node evaluation
@@ -14,6 +15,8 @@ pub enum UnknownValue {
     TeamSide(crate::TeamSide),
     /// A character HP value
     CharacterHP(CharacterHP),
+    /// A character MP value
+    CharacterMP(CharacterMP),
 }
 
 /// Error type for UnknownValue conversions
@@ -88,6 +91,20 @@ impl TryFrom<UnknownValue> for CharacterHP {
     }
 }
 
+impl TryFrom<UnknownValue> for CharacterMP {
+    type Error = UnknownValueConversionError;
+    
+    fn try_from(value: UnknownValue) -> Result<Self, Self::Error> {
+        match value {
+            UnknownValue::CharacterMP(mp) => Ok(mp),
+            _ => Err(UnknownValueConversionError {
+                expected: "CharacterMP",
+                actual: value.type_name(),
+            }),
+        }
+    }
+}
+
 impl UnknownValue {
     /// Returns the name of the type this value contains
     pub fn type_name(&self) -> &'static str {
@@ -96,6 +113,7 @@ impl UnknownValue {
             UnknownValue::Value(_) => "Value",
             UnknownValue::TeamSide(_) => "TeamSide",
             UnknownValue::CharacterHP(_) => "CharacterHP",
+            UnknownValue::CharacterMP(_) => "CharacterMP",
         }
     }
 }
\ No newline at end of file
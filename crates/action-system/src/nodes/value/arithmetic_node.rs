@@ -0,0 +1,125 @@
+// Arithmetic node - calculates an i32 from two Numeric values
+//
+// オーバーフローは i32 の範囲に飽和させる。0 除算は EvaluationError
+
+use crate::core::{NodeError, NodeResult, Numeric};
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::unified_node::{BoxedNode, CoreNode as Node};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArithmeticOperator {
+    Add,
+    Sub,
+    Mul,
+    /// 0 方向への切り捨て
+    Div,
+    /// left の right パーセント（left * right / 100、0 方向への切り捨て）
+    Percent,
+}
+
+impl ArithmeticOperator {
+    pub fn apply(self, left: i32, right: i32) -> NodeResult<i32> {
+        match self {
+            ArithmeticOperator::Add => Ok(left.saturating_add(right)),
+            ArithmeticOperator::Sub => Ok(left.saturating_sub(right)),
+            ArithmeticOperator::Mul => Ok(left.saturating_mul(right)),
+            ArithmeticOperator::Div => {
+                if right == 0 {
+                    return Err(NodeError::EvaluationError("Division by zero".to_string()));
+                }
+                Ok(left.saturating_div(right))
+            }
+            ArithmeticOperator::Percent => {
+                let value = i64::from(left) * i64::from(right) / 100;
+                Ok(value.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32)
+            }
+        }
+    }
+}
+
+pub struct ArithmeticNode {
+    operator: ArithmeticOperator,
+    left: BoxedNode<Box<dyn Numeric>>,
+    right: BoxedNode<Box<dyn Numeric>>,
+}
+
+impl ArithmeticNode {
+    pub fn new(
+        operator: ArithmeticOperator,
+        left: BoxedNode<Box<dyn Numeric>>,
+        right: BoxedNode<Box<dyn Numeric>>,
+    ) -> Self {
+        Self { operator, left, right }
+    }
+}
+
+impl<'a> Node<i32, EvaluationContext<'a>> for ArithmeticNode {
+    fn evaluate(&self, eval_context: &mut EvaluationContext<'a>) -> NodeResult<i32> {
+        let left = self.left.evaluate(eval_context)?.to_i32();
+        let right = self.right.evaluate(eval_context)?.to_i32();
+        self.operator.apply(left, right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::character::{ActingCharacterNode, BattleContext, CharacterToHpNode};
+    use crate::nodes::value::{ConstantValueNode, NumericNode};
+    use crate::{Character, Team, TeamSide};
+    use rand::SeedableRng;
+
+    fn numeric(value: i32) -> BoxedNode<Box<dyn Numeric>> {
+        Box::new(NumericNode::new(Box::new(ConstantValueNode::new(value))))
+    }
+
+    #[test]
+    fn test_operators() {
+        use ArithmeticOperator::*;
+        assert_eq!(Add.apply(30, 12).unwrap(), 42);
+        assert_eq!(Sub.apply(30, 42).unwrap(), -12);
+        assert_eq!(Mul.apply(6, 7).unwrap(), 42);
+        assert_eq!(Div.apply(100, 3).unwrap(), 33);
+        assert_eq!(Div.apply(-7, 2).unwrap(), -3);
+        assert_eq!(Percent.apply(80, 25).unwrap(), 20);
+        assert_eq!(Percent.apply(99, 50).unwrap(), 49);
+    }
+
+    #[test]
+    fn test_overflow_saturates() {
+        use ArithmeticOperator::*;
+        assert_eq!(Add.apply(i32::MAX, 1).unwrap(), i32::MAX);
+        assert_eq!(Sub.apply(i32::MIN, 1).unwrap(), i32::MIN);
+        assert_eq!(Mul.apply(i32::MAX, -2).unwrap(), i32::MIN);
+        assert_eq!(Div.apply(i32::MIN, -1).unwrap(), i32::MAX);
+        assert_eq!(Percent.apply(i32::MAX, 300).unwrap(), i32::MAX);
+    }
+
+    #[test]
+    fn test_division_by_zero_is_error() {
+        assert!(matches!(
+            ArithmeticOperator::Div.apply(10, 0),
+            Err(NodeError::EvaluationError(_))
+        ));
+        // Percent は 0% なら 0
+        assert_eq!(ArithmeticOperator::Percent.apply(10, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_arithmetic_node_with_character_hp() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+        let mut character = Character::new(1, "Test".to_string(), 100, 100, 10);
+        character.hp = 45;
+        let team = Team::new("Test Team".to_string(), vec![character.clone()]);
+        let battle_context = BattleContext::new(&character, TeamSide::Player, &team, &team);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        // HP + 30
+        let hp = Box::new(NumericNode::new(Box::new(CharacterToHpNode::new(Box::new(ActingCharacterNode)))));
+        let node = ArithmeticNode::new(ArithmeticOperator::Add, hp, numeric(30));
+        assert_eq!(node.evaluate(&mut eval_context).unwrap(), 75);
+
+        let node = ArithmeticNode::new(ArithmeticOperator::Div, numeric(90), numeric(3));
+        assert_eq!(node.evaluate(&mut eval_context).unwrap(), 30);
+    }
+}
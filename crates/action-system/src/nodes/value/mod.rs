@@ -1,7 +1,9 @@
 pub mod constant_value_node;
 pub mod team_side_constant_node;
 pub mod numeric_node;
+pub mod arithmetic_node;
 
 pub use constant_value_node::ConstantValueNode;
 pub use team_side_constant_node::{EnemyNode, HeroNode};
-pub use numeric_node::NumericNode;
\ No newline at end of file
+pub use numeric_node::NumericNode;
+pub use arithmetic_node::{ArithmeticNode, ArithmeticOperator};
\ No newline at end of file
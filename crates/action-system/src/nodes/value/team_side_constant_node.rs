@@ -12,6 +12,12 @@ impl EnemyNode {
     }
 }
 
+impl Default for EnemyNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a> Node<TeamSide, EvaluationContext<'a>> for EnemyNode {
     fn evaluate(&self, _context: &mut EvaluationContext) -> NodeResult<TeamSide> {
         Ok(TeamSide::Enemy)
@@ -27,6 +33,12 @@ impl HeroNode {
     }
 }
 
+impl Default for HeroNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a> Node<TeamSide, EvaluationContext<'a>> for HeroNode {
     fn evaluate(&self, _context: &mut EvaluationContext) -> NodeResult<TeamSide> {
         Ok(TeamSide::Player)
@@ -0,0 +1,113 @@
+// Let node - evaluates a value once and binds it while evaluating the body
+use crate::core::NodeResult;
+use crate::nodes::unified_node::{CoreNode as Node, BoxedNode};
+use crate::nodes::evaluation_context::EvaluationContext;
+use crate::nodes::array::mapping_node::AsUnknownValue;
+
+/// value は1回だけ評価され、body 内の Var（束縛の深さに解決済み）は常に同じ値を返す
+pub struct LetNode<T, U> {
+    value: BoxedNode<T>,
+    body: BoxedNode<U>,
+}
+
+impl<T, U> LetNode<T, U> {
+    pub fn new(value: BoxedNode<T>, body: BoxedNode<U>) -> Self {
+        Self { value, body }
+    }
+}
+
+impl<'a, T, U> Node<U, EvaluationContext<'a>> for LetNode<T, U>
+where
+    T: AsUnknownValue + 'static,
+    U: 'static,
+{
+    fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<U> {
+        let value = self.value.evaluate(eval_context)?;
+        let mut scoped_eval_context = eval_context.with_binding(value.as_unknown_value());
+        self.body.evaluate(&mut scoped_eval_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::array::{AllCharactersNode, FilterListNode, RandomPickNode};
+    use crate::nodes::character::ElementNode;
+    use crate::nodes::condition::EqConditionNode;
+    use crate::{BattleContext, Character, NodeError, Team, TeamSide};
+    use rand::SeedableRng;
+
+    struct PairNode<T>(BoxedNode<T>, BoxedNode<T>);
+
+    impl<'a, T: 'static> Node<(T, T), EvaluationContext<'a>> for PairNode<T> {
+        fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<(T, T)> {
+            Ok((self.0.evaluate(eval_context)?, self.1.evaluate(eval_context)?))
+        }
+    }
+
+    fn setup() -> (Character, Team, Team) {
+        let hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        let enemies = (2..10).map(|id| Character::new(id, format!("Enemy{}", id), 100, 0, 10)).collect();
+        let player_team = Team::new("Player".to_string(), vec![hero.clone()]);
+        let enemy_team = Team::new("Enemy".to_string(), enemies);
+        (hero, player_team, enemy_team)
+    }
+
+    #[test]
+    fn test_let_evaluates_value_once() {
+        let (hero, player_team, enemy_team) = setup();
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+
+        for seed in 0..10 {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+            let node = LetNode::new(
+                Box::new(RandomPickNode::<Character>::new(Box::new(AllCharactersNode::new()))),
+                Box::new(PairNode::<Character>(
+                    Box::new(ElementNode::new()),
+                    Box::new(ElementNode::new()),
+                )),
+            );
+            let (first, second) = node.evaluate(&mut eval_context).unwrap();
+            assert_eq!(first.id, second.id);
+        }
+    }
+
+    #[test]
+    fn test_variable_is_visible_inside_array_operations() {
+        let (hero, player_team, enemy_team) = setup();
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        // Let x = 3 in Let y = 5 in FilterList(AllCharacters, Element.id == y)
+        // 条件内では要素が深さ0、y が深さ1、x が深さ2
+        let filter = FilterListNode::<Character>::new(
+            Box::new(AllCharactersNode::new()),
+            Box::new(EqConditionNode::<i32>::new(
+                Box::new(IdOfElement),
+                Box::new(ElementNode::at(1)),
+            )),
+        );
+        let node = LetNode::new(
+            Box::new(crate::nodes::value::ConstantValueNode::new(3)),
+            Box::new(LetNode::new(Box::new(crate::nodes::value::ConstantValueNode::new(5)), Box::new(filter))),
+        );
+        let filtered = node.evaluate(&mut eval_context).unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].id, 5);
+
+        // スコープ外では参照できない
+        let outside = ElementNode::<i32>::new();
+        assert!(matches!(outside.evaluate(&mut eval_context), Err(NodeError::EvaluationError(_))));
+    }
+
+    struct IdOfElement;
+
+    impl<'a> Node<i32, EvaluationContext<'a>> for IdOfElement {
+        fn evaluate(&self, eval_context: &mut EvaluationContext) -> NodeResult<i32> {
+            let character: Character = ElementNode::new().evaluate(eval_context)?;
+            Ok(character.id)
+        }
+    }
+}
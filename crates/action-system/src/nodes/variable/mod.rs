@@ -0,0 +1,3 @@
+pub mod let_node;
+
+pub use let_node::LetNode;
@@ -42,7 +42,7 @@ impl ActionCalculationSystem {
 mod tests {
     use super::*;
     use crate::{Character, Team, TeamSide};
-    use crate::{ConditionCheckNode, StrikeActionNode, HealActionNode, RandomConditionNode, GreaterThanNode, ConstantValueNode, ActingCharacterNode, CharacterHpValueNode};
+    use crate::{ConditionCheckNode, StrikeActionNode, HealActionNode, RandomConditionNode, ComparisonNode, ComparisonOperator, ConstantValueNode, ActingCharacterNode, CharacterHpValueNode};
     use crate::nodes::value::NumericNode;
     use rand::SeedableRng;
 
@@ -141,7 +141,8 @@ mod tests {
         // Create HP-based rules
         let hp_rules: Vec<RuleNode> = vec![
             Box::new(ConditionCheckNode::new(
-                Box::new(GreaterThanNode::new(
+                Box::new(ComparisonNode::new(
+                    ComparisonOperator::GreaterThan,
                     Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50)))),
                     Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(ActingCharacterNode))))),
                 )),
@@ -290,7 +291,8 @@ mod tests {
         // 複雑な条件: HP < 30 AND ランダム条件が真の場合のみHeal
         let complex_rules: Vec<RuleNode> = vec![
             Box::new(ConditionCheckNode::new(
-                Box::new(GreaterThanNode::new(
+                Box::new(ComparisonNode::new(
+                    ComparisonOperator::GreaterThan,
                     Box::new(NumericNode::new(Box::new(ConstantValueNode::new(30)))),
                     Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(ActingCharacterNode))))),
                 )),
@@ -336,7 +338,8 @@ mod tests {
         // HP > 90の場合のみランダム条件チェック、そうでなければStrike
         let nested_rules: Vec<RuleNode> = vec![
             Box::new(ConditionCheckNode::new(
-                Box::new(GreaterThanNode::new(
+                Box::new(ComparisonNode::new(
+                    ComparisonOperator::GreaterThan,
                     Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(ActingCharacterNode))))),
                     Box::new(NumericNode::new(Box::new(ConstantValueNode::new(90)))),
                 )),
@@ -375,14 +378,16 @@ mod tests {
         // HP > 70: Strike, HP > 30: Heal, その他: Strike
         let threshold_rules: Vec<RuleNode> = vec![
             Box::new(ConditionCheckNode::new(
-                Box::new(GreaterThanNode::new(
+                Box::new(ComparisonNode::new(
+                    ComparisonOperator::GreaterThan,
                     Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(ActingCharacterNode))))),
                     Box::new(NumericNode::new(Box::new(ConstantValueNode::new(70)))),
                 )),
                 Box::new(StrikeActionNode::new(Box::new(ActingCharacterNode))),
             )),
             Box::new(ConditionCheckNode::new(
-                Box::new(GreaterThanNode::new(
+                Box::new(ComparisonNode::new(
+                    ComparisonOperator::GreaterThan,
                     Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(ActingCharacterNode))))),
                     Box::new(NumericNode::new(Box::new(ConstantValueNode::new(30)))),
                 )),
@@ -433,7 +438,8 @@ mod tests {
         // ActingCharacterのHPと他キャラクターのHPを比較
         let char_comparison_rules: Vec<RuleNode> = vec![
             Box::new(ConditionCheckNode::new(
-                Box::new(GreaterThanNode::new(
+                Box::new(ComparisonNode::new(
+                    ComparisonOperator::GreaterThan,
                     Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(ActingCharacterNode))))),
                     Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(RandomPickNode::<Character>::new(Box::new(AllCharactersNode::new()))))))),
                 )),
@@ -471,7 +477,8 @@ mod tests {
         // 定数値同士の比較
         let constant_comparison_rules: Vec<RuleNode> = vec![
             Box::new(ConditionCheckNode::new(
-                Box::new(GreaterThanNode::new(
+                Box::new(ComparisonNode::new(
+                    ComparisonOperator::GreaterThan,
                     Box::new(NumericNode::new(Box::new(ConstantValueNode::new(100)))),
                     Box::new(NumericNode::new(Box::new(ConstantValueNode::new(50)))),
                 )),
@@ -519,14 +526,16 @@ mod tests {
         // 絶対に満たされない条件のみ
         let impossible_rules: Vec<RuleNode> = vec![
             Box::new(ConditionCheckNode::new(
-                Box::new(GreaterThanNode::new(
+                Box::new(ComparisonNode::new(
+                    ComparisonOperator::GreaterThan,
                     Box::new(NumericNode::new(Box::new(ConstantValueNode::new(10)))),
                     Box::new(NumericNode::new(Box::new(ConstantValueNode::new(100)))),
                 )),
                 Box::new(HealActionNode::new(Box::new(ActingCharacterNode))),
             )),
             Box::new(ConditionCheckNode::new(
-                Box::new(GreaterThanNode::new(
+                Box::new(ComparisonNode::new(
+                    ComparisonOperator::GreaterThan,
                     Box::new(NumericNode::new(Box::new(CharacterHpValueNode::new(Box::new(ActingCharacterNode))))),
                     Box::new(NumericNode::new(Box::new(ConstantValueNode::new(200)))),
                 )),
@@ -296,8 +296,8 @@ mod integration_tests {
         assert_eq!(team_battle.player_team.member_count(), 2);
         assert_eq!(team_battle.enemy_team.member_count(), 2);
         assert_eq!(team_battle.current_turn, 0);
-        assert_eq!(team_battle.battle_over, false);
-        assert_eq!(team_battle.is_player_turn(), true);
+        assert!(!team_battle.battle_over);
+        assert!(team_battle.is_player_turn());
     }
 
     #[test]
@@ -1,5 +1,6 @@
 // Display text logic for UI tokens - Bevy UI specific
 
+use std::ops::Range;
 use token_input::FlatTokenInput;
 
 pub trait UITokenDisplay {
@@ -24,3 +25,20 @@ pub fn format_rule_tokens(rule_row: &[FlatTokenInput]) -> String {
     }
 }
 
+// エラーの原因になったトークンを【】で囲んで整形する
+pub fn format_rule_tokens_marked(rule_row: &[FlatTokenInput], marked: Option<&Range<usize>>) -> String {
+    let Some(marked) = marked else {
+        return format_rule_tokens(rule_row);
+    };
+    rule_row.iter()
+        .enumerate()
+        .map(|(i, token)| {
+            if marked.contains(&i) {
+                format!("【{}】", token.display_text())
+            } else {
+                token.display_text()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
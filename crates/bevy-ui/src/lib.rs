@@ -19,4 +19,4 @@ pub use plugin::GamePlugin;
 // Re-export ui-core types for convenience
 pub use ui_core::{GameState, GameMode, CurrentRules, FlatTokenInput, convert_flat_to_structured};
 // Re-export display text functions
-pub use display_text::format_rule_tokens;
\ No newline at end of file
+pub use display_text::{format_rule_tokens, format_rule_tokens_marked};
\ No newline at end of file
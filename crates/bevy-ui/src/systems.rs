@@ -6,7 +6,7 @@ use crate::{GameTeamBattle, BevyGameState, BevyCurrentRules};
 use crate::ui::{BattleUI, LatestLogUI};
 use crate::default_teams::{create_default_player_team, create_default_enemy_team, DEFAULT_ENEMY_RULES_PATH};
 use crate::battle_display::{format_battle_display, format_latest_log};
-use ui_core::BattleOrchestrator;
+use ui_core::{BattleOrchestrator, CompileError};
 use json_rule::load_rules_from_file;
 
 // チーム戦闘のセットアップ
@@ -19,23 +19,38 @@ pub fn setup_team_battle(mut commands: Commands, current_rules: Res<BevyCurrentR
         .expect("Failed to load enemy rules from JSON file");
     
     let rng = rand::rngs::StdRng::from_entropy();
-    let team_battle = BattleOrchestrator::create_battle(
+    match BattleOrchestrator::create_battle(
         &current_rules.0,
         player_team,
         enemy_team,
         &enemy_rule_set,
         rng,
-    );
-    println!("Loaded team battle rules: UI rules for players, JSON for enemies");
-    commands.insert_resource(GameTeamBattle(team_battle));
+    ) {
+        Ok(team_battle) => {
+            println!("Loaded team battle rules: UI rules for players, JSON for enemies");
+            commands.insert_resource(GameTeamBattle(team_battle));
+        }
+        // 戦闘は作らずルール作成モードのまま。戦闘開始時に作り直す
+        Err(errors) => report_rule_errors(&errors),
+    }
+}
+
+// 変換できなかったルールをコンソールに出力
+fn report_rule_errors(errors: &[CompileError]) {
+    for error in errors {
+        eprintln!("ルールを変換できません: {}", error);
+    }
 }
 
 // チーム戦闘リスタート処理
 pub fn handle_team_restart(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut game_team_battle: ResMut<GameTeamBattle>,
+    game_team_battle: Option<ResMut<GameTeamBattle>>,
     current_rules: Res<BevyCurrentRules>,
 ) {
+    let Some(mut game_team_battle) = game_team_battle else {
+        return;
+    };
     let shift_pressed = keyboard_input.just_pressed(KeyCode::ShiftLeft) ||
                        keyboard_input.just_pressed(KeyCode::ShiftRight);
     
@@ -48,22 +63,28 @@ pub fn handle_team_restart(
             .expect("Failed to load enemy rules from JSON file");
         
         let rng = rand::rngs::StdRng::from_entropy();
-        game_team_battle.0 = BattleOrchestrator::create_battle(
+        match BattleOrchestrator::create_battle(
             &current_rules.0,
             player_team,
             enemy_team,
             &enemy_rule_set,
             rng,
-        );
+        ) {
+            Ok(team_battle) => game_team_battle.0 = team_battle,
+            Err(errors) => {
+                report_rule_errors(&errors);
+                return;
+            }
+        }
         println!("チーム戦闘をリスタートしました");
     }
 }
 
 // UIで作成したルールをチーム戦闘システムに適用する
 pub fn apply_rules_to_battle(
-    game_state: Res<BevyGameState>,
+    mut commands: Commands,
+    mut game_state: ResMut<BevyGameState>,
     current_rules: Res<BevyCurrentRules>,
-    mut game_team_battle: ResMut<GameTeamBattle>,
 ) {
     if BattleOrchestrator::should_start_new_battle(&game_state.0, game_state.is_changed()) {
         let player_team = create_default_player_team();
@@ -74,13 +95,21 @@ pub fn apply_rules_to_battle(
             .expect("Failed to load enemy rules from JSON file");
         
         let rng = rand::rngs::StdRng::from_entropy();
-        game_team_battle.0 = BattleOrchestrator::create_battle(
+        match BattleOrchestrator::create_battle(
             &current_rules.0,
             player_team,
             enemy_team,
             &enemy_rule_set,
             rng,
-        );
+        ) {
+            Ok(team_battle) => commands.insert_resource(GameTeamBattle(team_battle)),
+            Err(errors) => {
+                // 変換できないルールがあれば戦闘を始めずにルール作成に戻る
+                report_rule_errors(&errors);
+                game_state.0.switch_to_rule_creation();
+                return;
+            }
+        }
         println!("新しいチーム戦闘を開始しました。");
     }
 }
@@ -89,8 +118,11 @@ pub fn apply_rules_to_battle(
 pub fn handle_team_battle_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     game_state: Res<BevyGameState>,
-    mut game_team_battle: ResMut<GameTeamBattle>,
+    game_team_battle: Option<ResMut<GameTeamBattle>>,
 ) {
+    let Some(mut game_team_battle) = game_team_battle else {
+        return;
+    };
     let space_pressed = keyboard_input.just_pressed(KeyCode::Space);
     
     if BattleOrchestrator::should_execute_turn(
@@ -109,7 +141,7 @@ pub fn handle_team_battle_input(
 // チーム戦闘専用のUI更新
 pub fn update_team_battle_ui(
     game_state: Res<BevyGameState>,
-    game_team_battle: Res<GameTeamBattle>,
+    game_team_battle: Option<Res<GameTeamBattle>>,
     mut ui_query: Query<&mut Text, With<BattleUI>>,
 ) {
     for mut text in ui_query.iter_mut() {
@@ -118,7 +150,7 @@ pub fn update_team_battle_ui(
                 "rule_creation_mode" => "ルール作成中...\nスペースキーで戦闘開始".to_string(),
                 _ => String::new(),
             };
-        } else {
+        } else if let Some(game_team_battle) = &game_team_battle {
             text.0 = format_battle_display(&game_team_battle.0);
         }
     }
@@ -127,7 +159,7 @@ pub fn update_team_battle_ui(
 // チーム戦闘専用の最新ログUI更新
 pub fn update_team_latest_log_ui(
     game_state: Res<BevyGameState>,
-    game_team_battle: Res<GameTeamBattle>,
+    game_team_battle: Option<Res<GameTeamBattle>>,
     mut latest_log_query: Query<&mut Text, (With<LatestLogUI>, Without<BattleUI>)>
 ) {
     for mut text in latest_log_query.iter_mut() {
@@ -136,7 +168,7 @@ pub fn update_team_latest_log_ui(
                 "rule_creation_log" => "ルール作成モード：トークンを組み合わせて行動ルールを作成してください".to_string(),
                 _ => String::new(),
             };
-        } else {
+        } else if let Some(game_team_battle) = &game_team_battle {
             text.0 = format_latest_log(&game_team_battle.0);
         }
     }
@@ -1,8 +1,8 @@
 use bevy::prelude::*;
 use bevy::render::view::screenshot::{Screenshot, save_to_disk};
 use battle::TeamBattle;
-use ui_core::{GameState, GameMode, CurrentRules, FlatTokenInput};
-use crate::display_text::format_rule_tokens;
+use ui_core::{GameState, GameMode, CurrentRules, FlatTokenInput, RowCompletion, RuleStorage};
+use crate::display_text::{format_rule_tokens, format_rule_tokens_marked};
 
 #[derive(Resource)]
 pub struct GameFont {
@@ -87,8 +87,13 @@ pub struct MenuState {
     pub selected_row: usize,
     pub selected_token: usize,
     pub available_tokens: Vec<FlatTokenInput>,
+    // 保存・読み込みに使うスロット（SAVE_SLOTS の添字）
+    pub save_slot: usize,
 }
 
+// ルールの保存スロット（Tabで切り替え）
+pub const SAVE_SLOTS: [&str; 3] = ["slot1", "slot2", "slot3"];
+
 #[derive(Default, PartialEq)]
 pub enum MenuMode {
     #[default]
@@ -102,29 +107,9 @@ impl MenuState {
             mode: MenuMode::RowSelection,
             selected_row: 0,
             selected_token: 0,
-            available_tokens: vec![
-                FlatTokenInput::Check,
-                FlatTokenInput::Strike,
-                FlatTokenInput::Heal,
-                FlatTokenInput::TrueOrFalse,
-                FlatTokenInput::GreaterThan,
-                FlatTokenInput::Number(50),
-                FlatTokenInput::CharacterToHp,
-                FlatTokenInput::CharacterHpToCharacter,
-                FlatTokenInput::ActingCharacter,
-                FlatTokenInput::AllCharacters,
-                FlatTokenInput::TeamMembers,
-                FlatTokenInput::RandomPick,
-                FlatTokenInput::FilterList,
-                FlatTokenInput::Map,
-                FlatTokenInput::Eq,
-                FlatTokenInput::CharacterTeam,
-                FlatTokenInput::Element,
-                FlatTokenInput::Enemy,
-                FlatTokenInput::Hero,
-                FlatTokenInput::Max,
-                FlatTokenInput::Min,
-            ],
+            // トークン一覧はレジストリの登録順
+            available_tokens: token_input::token_registry::registry().flat_tokens(),
+            save_slot: 0,
         }
     }
 }
@@ -469,7 +454,31 @@ pub fn handle_rule_editing(
             } else if keyboard_input.just_pressed(KeyCode::Backspace) {
                 // 選択中の行の最後のトークンを削除
                 current_rules.0.rules[menu_state.selected_row].pop();
+            } else if keyboard_input.just_pressed(KeyCode::Tab) {
+                menu_state.save_slot = (menu_state.save_slot + 1) % SAVE_SLOTS.len();
+            } else if keyboard_input.just_pressed(KeyCode::F5) {
+                // 選択中のスロットに保存（S はスクリーンショット）
+                let slot = SAVE_SLOTS[menu_state.save_slot];
+                match RuleStorage::default().save(slot, &current_rules.0) {
+                    Ok(()) => println!("ルールを{}に保存しました", slot),
+                    Err(error) => eprintln!("ルールを保存できません: {}", error),
+                }
+            } else if keyboard_input.just_pressed(KeyCode::F9) {
+                // 選択中のスロットから読み込み
+                let slot = SAVE_SLOTS[menu_state.save_slot];
+                match RuleStorage::default().load(slot) {
+                    Ok(rules) => {
+                        current_rules.0 = rules;
+                        current_rules.0.selected_row = menu_state.selected_row;
+                        println!("ルールを{}から読み込みました", slot);
+                    }
+                    Err(error) => eprintln!("ルールを読み込めません: {}", error),
+                }
             } else if keyboard_input.just_pressed(KeyCode::Space) {
+                // 変換できない行があれば戦闘を開始しない（エラーはルール表示に出る）
+                if current_rules.0.convert_to_rule_nodes().is_err() {
+                    return;
+                }
                 // ルール作成完了 → 戦闘モードに移行
                 game_state.0.mode = GameMode::Battle;
                 // メニュー状態をリセット
@@ -489,8 +498,14 @@ pub fn handle_rule_editing(
                     menu_state.selected_token += 1;
                 }
             } else if keyboard_input.just_pressed(KeyCode::Enter) {
-                // 選択されたトークンを追加
+                // 選択されたトークンを追加（次の穴に置けないトークンは追加しない）
                 if let Some(token) = menu_state.available_tokens.get(menu_state.selected_token) {
+                    let accepted = current_rules.0
+                        .current_row_completion(std::slice::from_ref(token))
+                        .is_ok_and(|completion| completion.accepts(token));
+                    if !accepted {
+                        return;
+                    }
                     let selected_row = current_rules.0.selected_row;
                     current_rules.0.rules[selected_row].push(token.clone());
                     // 行選択モードに戻る
@@ -519,7 +534,8 @@ pub fn update_rule_display(
                 match menu_state.mode {
                     MenuMode::RowSelection => {
                         display_text.push_str("【ルール作成モード - 行選択】\n");
-                        display_text.push_str("↑↓: 行選択  Enter: トークン追加  Backspace: 削除  スペース: 戦闘開始\n\n");
+                        display_text.push_str("↑↓: 行選択  Enter: トークン追加  Backspace: 削除  スペース: 戦闘開始\n");
+                        display_text.push_str(&format!("F5: 保存  F9: 読込  Tab: スロット切替（{}）\n\n", SAVE_SLOTS[menu_state.save_slot]));
                     }
                     MenuMode::TokenSelection => {
                         display_text.push_str("【ルール作成モード - トークン選択】\n");
@@ -535,10 +551,15 @@ pub fn update_rule_display(
                         format!("  行{}: ", i + 1)
                     };
                     
+                    let marked = current_rules.0.row_surplus_tokens(i);
                     display_text.push_str(&prefix);
-                    display_text.push_str(&format_rule_tokens(rule_row));
+                    display_text.push_str(&format_rule_tokens_marked(rule_row, marked.as_ref()));
                     
                     display_text.push('\n');
+                    
+                    if let Some(error) = current_rules.0.row_error(i) {
+                        display_text.push_str(&format!("      エラー: {}\n", error));
+                    }
                 }
             }
             GameMode::Battle => {
@@ -570,6 +591,7 @@ pub fn update_battle_rule_display(
 pub fn update_token_inventory_display(
     game_state: Res<BevyGameState>,
     menu_state: Res<MenuState>,
+    current_rules: Res<BevyCurrentRules>,
     mut inventory_query: Query<&mut Text, With<TokenInventory>>,
 ) {
     for mut text in inventory_query.iter_mut() {
@@ -583,7 +605,15 @@ pub fn update_token_inventory_display(
                         display_text.push_str("スペースキーを押すと\n戦闘開始できます");
                     }
                     MenuMode::TokenSelection => {
-                        display_text.push_str("トークンを選択してください:\n\n");
+                        display_text.push_str("トークンを選択してください:\n");
+                        
+                        // 次の穴に置けないトークンには × を付ける
+                        let completion = current_rules.0.current_row_completion(&menu_state.available_tokens).ok();
+                        match completion.as_ref() {
+                            Some(completion) if completion.is_complete() => display_text.push_str("（行は完成しています）\n\n"),
+                            Some(RowCompletion { expected_type: Some(ty), .. }) => display_text.push_str(&format!("（次の型: {}）\n\n", ty)),
+                            _ => display_text.push('\n'),
+                        }
                         
                         for (i, token) in menu_state.available_tokens.iter().enumerate() {
                             let prefix = if i == menu_state.selected_token {
@@ -591,8 +621,13 @@ pub fn update_token_inventory_display(
                             } else {
                                 "  "
                             };
+                            let mark = if completion.as_ref().is_some_and(|completion| completion.accepts(token)) {
+                                ""
+                            } else {
+                                "× "
+                            };
                             
-                            display_text.push_str(&format!("{}{}\n", prefix, token.display_text()));
+                            display_text.push_str(&format!("{}{}{}\n", prefix, mark, token.display_text()));
                         }
                     }
                 }
@@ -633,6 +668,7 @@ pub fn update_right_panel_visibility(
 }
 
 // ルールエディタの位置を戦闘モードに応じて調整
+#[allow(clippy::type_complexity)]
 pub fn update_rule_editor_position(
     game_state: Res<BevyGameState>,
     mut rule_editor_query: Query<&mut Node, With<RuleEditor>>,
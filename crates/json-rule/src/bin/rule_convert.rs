@@ -0,0 +1,25 @@
+// ルールファイルの形式を変換する（形式は入力・出力の拡張子で選ぶ）
+// cargo run -p json-rule --bin rule_convert -- rules/enemy_rules.json rules/enemy_rules.yaml
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    let [input, output] = arguments.as_slice() else {
+        eprintln!("usage: rule_convert <input> <output>");
+        return ExitCode::FAILURE;
+    };
+    // 読み込み時に版の移行と型検査を済ませる
+    let result = json_rule::load_rules_from_file(input)
+        .map_err(|e| format!("{}: {}", input, e))
+        .and_then(|rule_set| {
+            json_rule::save_rules_to_file(output, &rule_set).map_err(|e| format!("{}: {}", output, e))
+        });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,7 @@
+// ルールファイルの JSON Schema を標準出力に書き出す
+// cargo run -p json-rule --bin rule_schema > rules/rule_set.schema.json
+
+fn main() {
+    let schema = json_rule::rule_set_schema();
+    println!("{}", serde_json::to_string_pretty(&schema).expect("schema is always serializable"));
+}
@@ -0,0 +1,183 @@
+// ルールファイルの形式 - JSON / YAML / TOML / RON を拡張子で選ぶ
+//
+// どの形式も中身は JSON と同じ構造（StructuredTokenInput の serde 表現）。
+// JSON 以外は一度 serde_json::Value に読んでから版を上げる
+
+use crate::migration::migrate;
+use crate::rule_loader::{parse_json_source, rule_set_to_json, validated_in_file, VersionedRuleSet};
+use serde_json::Value;
+use std::path::Path;
+use token_input::RuleSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleFormat {
+    Json,
+    Yaml,
+    Toml,
+    Ron,
+}
+
+impl RuleFormat {
+    pub const ALL: [RuleFormat; 4] = [RuleFormat::Json, RuleFormat::Yaml, RuleFormat::Toml, RuleFormat::Ron];
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(RuleFormat::Json),
+            "yaml" | "yml" => Some(RuleFormat::Yaml),
+            "toml" => Some(RuleFormat::Toml),
+            "ron" => Some(RuleFormat::Ron),
+            _ => None,
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(Self::from_extension)
+            .ok_or_else(|| format!("Unsupported rule file extension: {}", path.display()))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RuleFormat::Json => "JSON",
+            RuleFormat::Yaml => "YAML",
+            RuleFormat::Toml => "TOML",
+            RuleFormat::Ron => "RON",
+        }
+    }
+
+    /// 読み込み（古い版は移行し、型検査まで行う）
+    pub fn parse(self, content: &str) -> Result<RuleSet, String> {
+        self.parse_source(content, None)
+    }
+
+    /// `file` は検査エラーに添える。行・列は JSON のみ（YAML / TOML / RON はファイル名とパス）
+    pub(crate) fn parse_source(self, content: &str, file: Option<&Path>) -> Result<RuleSet, String> {
+        let parse_error = |e: &dyn std::fmt::Display| format!("Failed to parse {}: {}", self.name(), e);
+        let document: Value = match self {
+            RuleFormat::Json => return parse_json_source(content, file),
+            RuleFormat::Yaml => serde_yaml::from_str(content).map_err(|e| parse_error(&e))?,
+            RuleFormat::Toml => toml::from_str(content).map_err(|e| parse_error(&e))?,
+            // 構造体の記法 `(name: ..)` は ron::Value を経由しないとマップとして読めない
+            RuleFormat::Ron => ron::from_str::<ron::Value>(content)
+                .map_err(|e| parse_error(&e))?
+                .into_rust()
+                .map_err(|e| parse_error(&e))?,
+        };
+        let rule_set = serde_json::from_value(migrate(document)?).map_err(|e| parse_error(&e))?;
+        validated_in_file(rule_set, file)
+    }
+
+    /// 現在の版として書き出す
+    pub fn write(self, rule_set: &RuleSet) -> Result<String, String> {
+        let versioned = VersionedRuleSet::new(rule_set);
+        let write_error = |e: &dyn std::fmt::Display| format!("Failed to write {}: {}", self.name(), e);
+        match self {
+            RuleFormat::Json => Ok(rule_set_to_json(rule_set)),
+            RuleFormat::Yaml => serde_yaml::to_string(&versioned).map_err(|e| write_error(&e)),
+            RuleFormat::Toml => toml::to_string(&versioned).map_err(|e| write_error(&e)),
+            RuleFormat::Ron => {
+                let config = ron::ser::PrettyConfig::default();
+                ron::ser::to_string_pretty(&versioned, config).map_err(|e| write_error(&e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::load_rules_from_file;
+    use token_input::StructuredTokenInput;
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(RuleFormat::from_path(Path::new("rules/enemy.yml")), Ok(RuleFormat::Yaml));
+        assert_eq!(RuleFormat::from_path(Path::new("enemy.YAML")), Ok(RuleFormat::Yaml));
+        assert_eq!(RuleFormat::from_path(Path::new("enemy.toml")), Ok(RuleFormat::Toml));
+        assert_eq!(RuleFormat::from_path(Path::new("enemy.ron")), Ok(RuleFormat::Ron));
+        assert_eq!(
+            RuleFormat::from_path(Path::new("enemy.xml")).unwrap_err(),
+            "Unsupported rule file extension: enemy.xml"
+        );
+    }
+
+    #[test]
+    fn test_every_format_round_trips() {
+        let rule_set = load_rules_from_file("../../rules/enemy_rules.json").unwrap();
+        for format in RuleFormat::ALL {
+            let written = format.write(&rule_set).unwrap();
+            let parsed = format.parse(&written).unwrap_or_else(|e| panic!("{}: {}\n{}", format.name(), e, written));
+            assert_eq!(parsed, rule_set, "{} should round trip", format.name());
+        }
+    }
+
+    #[test]
+    fn test_hand_written_formats() {
+        // 版のない YAML も移行される
+        let yaml = "
+rules:
+  - type: Check
+    condition:
+      type: TrueOrFalse
+    then_action:
+      type: Strike
+      target:
+        type: ActingCharacter
+";
+        let from_yaml = RuleFormat::Yaml.parse(yaml).unwrap();
+
+        let toml = r#"
+version = 2
+
+[[rules]]
+type = "Check"
+condition = { type = "TrueOrFalseRandom" }
+
+[rules.then_action]
+type = "Strike"
+target = { type = "ActingCharacter" }
+"#;
+        assert_eq!(RuleFormat::Toml.parse(toml).unwrap(), from_yaml);
+
+        let ron = r#"{
+            "version": 2,
+            "rules": [{
+                "type": "Check",
+                "condition": {"type": "TrueOrFalseRandom"},
+                "then_action": {"type": "Strike", "target": {"type": "ActingCharacter"}},
+            }],
+        }"#;
+        assert_eq!(RuleFormat::Ron.parse(ron).unwrap(), from_yaml);
+    }
+
+    #[test]
+    fn test_non_json_files_report_file_and_path() {
+        let rule_set = RuleSet::new(vec![StructuredTokenInput::Strike { target: Box::new(StructuredTokenInput::Enemy) }]);
+        for format in [RuleFormat::Yaml, RuleFormat::Toml, RuleFormat::Ron] {
+            let extension = format.name().to_ascii_lowercase();
+            let path = std::env::temp_dir().join(format!("json-rule-format-{}.{}", std::process::id(), extension));
+            std::fs::write(&path, format.write(&rule_set).unwrap()).unwrap();
+            let error = load_rules_from_file(&path).unwrap_err();
+            let _ = std::fs::remove_file(&path);
+            // 行・列は付かない
+            assert_eq!(
+                error,
+                format!("Invalid rules: {}: rules[0].target: Type mismatch: expected Character, found TeamSide", path.display()),
+                "{}", format.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_errors_name_the_format() {
+        let error = RuleFormat::Yaml.parse("rules: [").unwrap_err();
+        assert!(error.starts_with("Failed to parse YAML: "), "{}", error);
+
+        let error = RuleFormat::Toml.parse("[[rules]]\ntype = \"Teleport\"\n").unwrap_err();
+        assert!(error.starts_with("Failed to parse TOML: unknown token type `Teleport`"), "{}", error);
+
+        let error = RuleFormat::Ron.parse(r#"{"rules": [{"type": "Strike", "target": {"type": "Enemy"}}]}"#).unwrap_err();
+        assert_eq!(error, "Invalid rules: rules[0].target: Type mismatch: expected Character, found TeamSide");
+    }
+}
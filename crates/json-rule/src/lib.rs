@@ -1,7 +1,15 @@
 // Rule system crate - JSON rule loading and conversion
 
 pub mod rule_loader;
+pub mod schema;
+pub mod migration;
+pub mod formats;
+pub mod spans;
 
 // Re-export public types  
 pub use token_input::{RuleSet, StructuredTokenInput};
-pub use rule_loader::{load_rules_from_file, parse_rules_from_json};
\ No newline at end of file
+pub use rule_loader::{load_rules_from_file, parse_rules_from_json, parse_rules_from_text, rule_set_to_json, save_rules_to_file, validate_rule_set};
+pub use formats::RuleFormat;
+pub use spans::{JsonSpans, Span};
+pub use migration::{migrate, CURRENT_VERSION};
+pub use schema::rule_set_schema;
\ No newline at end of file
@@ -0,0 +1,182 @@
+// ルールファイルの版管理 - 古い版の JSON を1版ずつ現在の版に上げる
+//
+// トークンの名前や形を変えたら CURRENT_VERSION を上げ、前の版から上げる関数を
+// MIGRATIONS の末尾に足す。`version` のないファイルは版1として扱う。
+// 値のパスを変える移行は keeps_paths を false にする（エラー位置が元のファイルとずれるため、パスだけで報告する）
+
+use serde_json::Value;
+
+pub const CURRENT_VERSION: u64 = 2;
+
+const UNVERSIONED: u64 = 1;
+
+struct Migration {
+    upgrade: fn(&mut Value),
+    /// 全ての値が移行前と同じパスに残る（トークン名の置き換えなど）
+    keeps_paths: bool,
+}
+
+/// `MIGRATIONS[i]` は版 i+1 を版 i+2 に上げる
+const MIGRATIONS: [Migration; (CURRENT_VERSION - UNVERSIONED) as usize] = [
+    Migration { upgrade: rename_true_or_false, keeps_paths: true },
+];
+
+/// ファイルに書かれた版
+pub fn document_version(document: &Value) -> Result<u64, String> {
+    let object = document.as_object().ok_or("Rule file must be a JSON object")?;
+    match object.get("version") {
+        None => Ok(UNVERSIONED),
+        Some(version) => version
+            .as_u64()
+            .filter(|version| *version >= UNVERSIONED)
+            .ok_or_else(|| format!("Invalid rule file version: {}", version)),
+    }
+}
+
+/// 現在の版に上げる。より新しい版のファイルは読めない
+pub fn migrate(mut document: Value) -> Result<Value, String> {
+    let version = document_version(&document)?;
+    if version > CURRENT_VERSION {
+        return Err(format!(
+            "Rule file version {} is newer than the supported version {}",
+            version, CURRENT_VERSION
+        ));
+    }
+    for migration in pending(version) {
+        (migration.upgrade)(&mut document);
+    }
+    document["version"] = Value::from(CURRENT_VERSION);
+    Ok(document)
+}
+
+/// `version` の文書を上げても値のパスが変わらないか（元のファイルの位置でエラーを報告できるか）
+pub fn migration_keeps_paths(version: u64) -> bool {
+    pending(version).iter().all(|migration| migration.keeps_paths)
+}
+
+fn pending(version: u64) -> &'static [Migration] {
+    let start = version.saturating_sub(UNVERSIONED) as usize;
+    MIGRATIONS.get(start..).unwrap_or_default()
+}
+
+// 版1 → 2: UIの平坦トークンと同じ名前で書かれた TrueOrFalse を TrueOrFalseRandom にする
+fn rename_true_or_false(document: &mut Value) {
+    rename_token_type(document, "TrueOrFalse", "TrueOrFalseRandom");
+}
+
+/// 文書中の全トークン（`type` を持つオブジェクト）の名前を置き換える
+fn rename_token_type(value: &mut Value, from: &str, to: &str) {
+    match value {
+        Value::Object(object) => {
+            if object.get("type").and_then(Value::as_str) == Some(from) {
+                object.insert("type".to_string(), Value::from(to));
+            }
+            for child in object.values_mut() {
+                rename_token_type(child, from, to);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                rename_token_type(item, from, to);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_version_1_to_2_renames_true_or_false() {
+        let document = json!({
+            "definitions": [{ "name": "coin", "body": { "type": "TrueOrFalse" } }],
+            "rules": [{
+                "type": "Check",
+                "condition": { "type": "Not", "condition": { "type": "TrueOrFalse" } },
+                "then_action": { "type": "Heal", "target": { "type": "ActingCharacter" } },
+            }],
+        });
+        let mut migrated = document.clone();
+        rename_true_or_false(&mut migrated);
+        assert_eq!(migrated["definitions"][0]["body"], json!({ "type": "TrueOrFalseRandom" }));
+        assert_eq!(migrated["rules"][0]["condition"]["condition"], json!({ "type": "TrueOrFalseRandom" }));
+        // 他のトークンはそのまま
+        assert_eq!(migrated["rules"][0]["then_action"], document["rules"][0]["then_action"]);
+    }
+
+    /// 文書中の全ての値のパス（CompileError と同じ形式）
+    fn value_paths(value: &Value, path: String, paths: &mut Vec<String>) {
+        match value {
+            Value::Object(object) => {
+                for (key, child) in object {
+                    value_paths(child, token_input::path::join(&path, key), paths);
+                }
+            }
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    value_paths(item, token_input::path::index(&path, i), paths);
+                }
+            }
+            _ => {}
+        }
+        paths.push(path);
+    }
+
+    #[test]
+    fn test_path_keeping_migrations_keep_every_path() {
+        let document = json!({
+            "definitions": [{ "name": "coin", "body": { "type": "TrueOrFalse" } }],
+            "rules": [{ "type": "FirstOf", "alternatives": [{ "type": "TrueOrFalse" }, { "type": "Heal", "target": { "type": "Hero" } }] }],
+        });
+        for (i, migration) in MIGRATIONS.iter().enumerate().filter(|(_, migration)| migration.keeps_paths) {
+            let mut migrated = document.clone();
+            (migration.upgrade)(&mut migrated);
+            let (mut before, mut after) = (Vec::new(), Vec::new());
+            value_paths(&document, String::new(), &mut before);
+            value_paths(&migrated, String::new(), &mut after);
+            assert_eq!(before, after, "migration from version {} moves values", i as u64 + UNVERSIONED);
+        }
+    }
+
+    #[test]
+    fn test_migrated_files_report_original_location() {
+        assert!(migration_keeps_paths(UNVERSIONED));
+        let legacy = r#"{
+  "rules": [
+    {
+      "type": "Check",
+      "condition": { "type": "TrueOrFalse" },
+      "then_action": { "type": "Heal", "target": { "type": "TrueOrFalse" } }
+    }
+  ]
+}"#;
+        let error = crate::parse_rules_from_json(legacy).unwrap_err();
+        assert_eq!(error, "Invalid rules: 6:50: rules[0].then_action.target: Type mismatch: expected Character, found Bool");
+    }
+
+    #[test]
+    fn test_migrate_upgrades_to_current_version() {
+        let legacy = json!({ "rules": [{ "type": "Check", "condition": { "type": "TrueOrFalse" }, "then_action": { "type": "Heal", "target": { "type": "ActingCharacter" } } }] });
+        let migrated = migrate(legacy).unwrap();
+        assert_eq!(migrated["version"], json!(CURRENT_VERSION));
+        assert_eq!(migrated["rules"][0]["condition"], json!({ "type": "TrueOrFalseRandom" }));
+
+        // 現在の版はそのまま
+        let current = json!({ "version": CURRENT_VERSION, "rules": [{ "type": "TrueOrFalse" }] });
+        assert_eq!(migrate(current.clone()).unwrap(), current);
+    }
+
+    #[test]
+    fn test_unsupported_versions() {
+        assert_eq!(document_version(&json!({ "rules": [] })), Ok(1));
+        assert_eq!(document_version(&json!({ "version": 0, "rules": [] })).unwrap_err(), "Invalid rule file version: 0");
+        assert_eq!(document_version(&json!({ "version": "2", "rules": [] })).unwrap_err(), "Invalid rule file version: \"2\"");
+        assert_eq!(document_version(&json!([])).unwrap_err(), "Rule file must be a JSON object");
+
+        let error = migrate(json!({ "version": CURRENT_VERSION + 1, "rules": [] })).unwrap_err();
+        assert_eq!(error, format!("Rule file version {} is newer than the supported version {}", CURRENT_VERSION + 1, CURRENT_VERSION));
+    }
+}
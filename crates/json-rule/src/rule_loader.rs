@@ -1,21 +1,104 @@
+use crate::formats::RuleFormat;
+use crate::migration::{document_version, migrate, migration_keeps_paths, CURRENT_VERSION};
+use crate::spans::JsonSpans;
+use serde::Serialize;
+use serde_json::Value;
 use std::fs;
 use std::path::Path;
-use token_input::RuleSet;
+use token_input::{parse_rule_text, CompileError, Compiler, RuleSet};
 
+// 拡張子（.json / .yaml / .yml / .toml / .ron）で形式を選んで読む
+// 型検査のエラーは JSON なら `ファイル:行:列: パス`、YAML / TOML / RON は行・列を記録しないので `ファイル: パス` で報告する
 pub fn load_rules_from_file<P: AsRef<Path>>(path: P) -> Result<RuleSet, String> {
+    let path = path.as_ref();
+    let format = RuleFormat::from_path(path)?;
     let content = fs::read_to_string(path)
         .map_err(|e| format!("Failed to read file: {}", e))?;
     
-    parse_rules_from_json(&content)
+    format.parse_source(&content, Some(path))
 }
 
+// 拡張子で選んだ形式で、現在の版として書き出す
+pub fn save_rules_to_file<P: AsRef<Path>>(path: P, rule_set: &RuleSet) -> Result<(), String> {
+    let content = RuleFormat::from_path(path.as_ref())?.write(rule_set)?;
+    fs::write(path, content)
+        .map_err(|e| format!("Failed to write file: {}", e))
+}
+
+// 古い版のファイルは現在の版に上げてから読む
 pub fn parse_rules_from_json(json_content: &str) -> Result<RuleSet, String> {
-    let rule_set: RuleSet = serde_json::from_str(json_content)
+    parse_json_source(json_content, None)
+}
+
+// 検査エラーには失敗したオブジェクトの行・列（`file` があればファイル名も）を添える
+pub(crate) fn parse_json_source(json_content: &str, file: Option<&Path>) -> Result<RuleSet, String> {
+    let document: Value = serde_json::from_str(json_content)
         .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    let version = document_version(&document)?;
+    let rule_set: RuleSet = if version == CURRENT_VERSION {
+        // 現在の版はそのまま読む（エラー位置を残すため）
+        serde_json::from_str(json_content)
+    } else {
+        serde_json::from_value(migrate(document)?)
+    }
+    .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    
+    // 値のパスを変えない移行なら元の JSON の位置がそのまま使える。変える移行ならパスだけで報告する
+    let spans = if migration_keeps_paths(version) { JsonSpans::new(json_content) } else { JsonSpans::default() };
+    validated_with(rule_set, |error| spans.describe(error, file))
+}
+
+// 現在の版を付けて JSON に書き出す
+pub fn rule_set_to_json(rule_set: &RuleSet) -> String {
+    serde_json::to_string_pretty(&VersionedRuleSet::new(rule_set)).expect("rule sets are always serializable")
+}
+
+/// 書き出し用。`version` を先頭に置く
+#[derive(Serialize)]
+pub(crate) struct VersionedRuleSet<'a> {
+    version: u64,
+    #[serde(flatten)]
+    rule_set: &'a RuleSet,
+}
+
+impl<'a> VersionedRuleSet<'a> {
+    pub(crate) fn new(rule_set: &'a RuleSet) -> Self {
+        Self { version: CURRENT_VERSION, rule_set }
+    }
+}
+
+// テキスト記法（token_input::text_syntax）のルールを読む
+pub fn parse_rules_from_text(text: &str) -> Result<RuleSet, String> {
+    let rule_set = parse_rule_text(text)
+        .map_err(|e| format!("Failed to parse rules: {}", e))?;
+    
+    validated(rule_set)
+}
+
+pub(crate) fn validated(rule_set: RuleSet) -> Result<RuleSet, String> {
+    validated_with(rule_set, |error| error.to_string())
+}
+
+// 位置を記録しない形式は `file` とパスだけを添える
+pub(crate) fn validated_in_file(rule_set: RuleSet, file: Option<&Path>) -> Result<RuleSet, String> {
+    let spans = JsonSpans::default();
+    validated_with(rule_set, |error| spans.describe(error, file))
+}
+
+fn validated_with(rule_set: RuleSet, describe: impl Fn(&CompileError) -> String) -> Result<RuleSet, String> {
+    validate_rule_set(&rule_set).map_err(|errors| {
+        let messages: Vec<_> = errors.iter().map(describe).collect();
+        format!("Invalid rules: {}", messages.join("; "))
+    })?;
     
     Ok(rule_set)
 }
 
+// 全ルールを型検査し、失敗したトークンのパス付きでエラーを返す
+pub fn validate_rule_set(rule_set: &RuleSet) -> Result<(), Vec<CompileError>> {
+    Compiler::shared().compile_rule_set(rule_set).map(|_| ())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,23 +146,168 @@ mod tests {
         assert_ne!(rule_set.rules.len(), 0);
     }
 
+    #[test]
+    fn test_text_and_json_round_trip() {
+        // JSON → テキスト → JSON
+        let rule_set = load_rules_from_file("../../rules/enemy_rules.json").unwrap();
+        let text = token_input::format_rule_set(&rule_set);
+        assert!(text.starts_with("def heroes = FilterList(AllCharacters, Eq(CharacterTeam(Element), Hero))\n"), "{}", text);
+        let from_text = parse_rules_from_text(&text).unwrap();
+        assert_eq!(from_text, rule_set);
+        assert_eq!(serde_json::to_value(&from_text).unwrap(), serde_json::to_value(&rule_set).unwrap());
+
+        // テキスト → JSON → テキスト
+        let text = "def heroes = FilterList(AllCharacters, Eq(CharacterTeam(Element), Hero))
+
+Check(
+    GreaterThan(CharacterToHp(ActingCharacter), 50),
+    Strike(RandomPick(Ref heroes)),
+)
+";
+        let json = serde_json::to_string(&parse_rules_from_text(text).unwrap()).unwrap();
+        assert_eq!(token_input::format_rule_set(&parse_rules_from_json(&json).unwrap()), text);
+
+        // テキスト記法では構文エラーは行・列、型エラーはパスで報告する
+        let error = parse_rules_from_text("Heal(ActingCharacter)\nStrike(RandomPick(AllCharacters)").unwrap_err();
+        assert_eq!(error, "Failed to parse rules: 2:33: Expected `,`, found end of input");
+        let error = parse_rules_from_text("Heal(ActingCharacter)\nStrike(RandomPick(AllTeamSides))").unwrap_err();
+        assert_eq!(error, "Invalid rules: rules[1].target: Type mismatch: expected Character, found TeamSide");
+    }
+
+    #[test]
+    fn test_definitions_are_expanded_at_each_use() {
+        let rule_json = r#"{
+            "definitions": [
+                {
+                    "name": "heroes",
+                    "body": {
+                        "type": "FilterList",
+                        "array": { "type": "AllCharacters" },
+                        "condition": {
+                            "type": "Eq",
+                            "left": { "type": "CharacterTeam", "character": { "type": "Element" } },
+                            "right": { "type": "Hero" }
+                        }
+                    }
+                },
+                { "name": "weakest_hero", "body": { "type": "MinBy", "array": { "type": "Ref", "name": "heroes" }, "key": { "type": "Ref", "name": "hp" } } },
+                { "name": "hp", "body": { "type": "CharacterToHp", "character": { "type": "Element" } } }
+            ],
+            "rules": [
+                { "type": "Strike", "target": { "type": "Ref", "name": "weakest_hero" } },
+                { "type": "Strike", "target": { "type": "RandomPick", "array": { "type": "Ref", "name": "heroes" } } }
+            ]
+        }"#;
+
+        let rule_set = parse_rules_from_json(rule_json).unwrap();
+        assert_eq!(rule_set.definitions.len(), 3);
+        let serialized = serde_json::to_string(&rule_set).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(rule_json).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&serialized).unwrap(), expected);
+
+        let rules = rule_set.expanded_rules().unwrap();
+        let StructuredTokenInput::Strike { target } = &rules[0] else { panic!("Expected Strike") };
+        let StructuredTokenInput::MinBy { array, key, .. } = target.as_ref() else { panic!("Expected MinBy") };
+        assert!(matches!(array.as_ref(), StructuredTokenInput::FilterList { .. }));
+        assert!(matches!(key.as_ref(), StructuredTokenInput::CharacterToHp { .. }));
+
+        // 型検査は使用箇所ごとに行われる
+        let misused = rule_json.replace(
+            r#"{ "type": "Strike", "target": { "type": "Ref", "name": "weakest_hero" } }"#,
+            r#"{ "type": "Strike", "target": { "type": "Ref", "name": "heroes" } }"#,
+        );
+        let error = parse_rules_from_json(&misused).unwrap_err();
+        assert_eq!(error, "Invalid rules: 19:47: rules[0].target: Type mismatch: expected Character, found Array<Character>");
+        let unbound = rule_json.replace(
+            r#"{ "type": "Strike", "target": { "type": "Ref", "name": "weakest_hero" } }"#,
+            r#"{ "type": "Check", "condition": { "type": "GreaterThan", "left": { "type": "Ref", "name": "hp" }, "right": { "type": "Number", "value": 0 } }, "then_action": { "type": "Heal", "target": { "type": "ActingCharacter" } } }"#,
+        );
+        let error = parse_rules_from_json(&unbound).unwrap_err();
+        assert_eq!(error, "Invalid rules: 19:82: rules[0].condition.left.character: Element is used outside of a scope that binds Element");
+    }
+
+    #[test]
+    fn test_definition_bodies_capture_use_site_bindings() {
+        // 定義の本体の Element / Var は使用箇所を囲む束縛を指す
+        let rule_json = |rule: &str| format!(r#"{{
+            "definitions": [
+                {{
+                    "name": "weaker_than_enemy",
+                    "body": {{
+                        "type": "LessThan",
+                        "left": {{ "type": "CharacterToHp", "character": {{ "type": "Element" }} }},
+                        "right": {{ "type": "CharacterToHp", "character": {{ "type": "Var", "name": "enemy" }} }}
+                    }}
+                }}
+            ],
+            "rules": [{}]
+        }}"#, rule);
+        let captured = r#"{ "type": "Strike", "target": { "type": "RandomPick", "array": {
+            "type": "FilterList", "name": "enemy", "array": { "type": "TeamMembers", "team_side": { "type": "Enemy" } },
+            "condition": { "type": "Any", "array": { "type": "TeamMembers", "team_side": { "type": "Hero" } }, "condition": { "type": "Ref", "name": "weaker_than_enemy" } }
+        } } }"#;
+        let rules = parse_rules_from_json(&rule_json(captured)).unwrap().expanded_rules().unwrap();
+        let StructuredTokenInput::Strike { target } = &rules[0] else { panic!("Expected Strike") };
+        let StructuredTokenInput::RandomPick { array } = target.as_ref() else { panic!("Expected RandomPick") };
+        let StructuredTokenInput::FilterList { condition, .. } = array.as_ref() else { panic!("Expected FilterList") };
+        let StructuredTokenInput::Any { condition, .. } = condition.as_ref() else { panic!("Expected Any") };
+        assert!(matches!(condition.as_ref(), StructuredTokenInput::LessThan { .. }));
+
+        // 使用箇所で enemy が束縛されていなければエラー
+        let uncaptured = r#"{ "type": "Strike", "target": { "type": "RandomPick", "array": {
+            "type": "FilterList", "array": { "type": "TeamMembers", "team_side": { "type": "Enemy" } },
+            "condition": { "type": "Ref", "name": "weaker_than_enemy" }
+        } } }"#;
+        let error = parse_rules_from_json(&rule_json(uncaptured)).unwrap_err();
+        assert_eq!(error, "Invalid rules: 14:26: rules[0].target.array.condition.right.character: Var is used outside of a scope that binds enemy");
+    }
+
+    #[test]
+    fn test_definition_errors() {
+        let rule_set = |definitions: &str, rule: &str| format!(r#"{{ "definitions": [{}], "rules": [{}] }}"#, definitions, rule);
+        let heal_self = r#"{ "type": "Heal", "target": { "type": "ActingCharacter" } }"#;
+        let strike_ref = |name: &str| format!(r#"{{ "type": "Strike", "target": {{ "type": "Ref", "name": "{}" }} }}"#, name);
+
+        let error = parse_rules_from_json(&rule_set("", &strike_ref("nobody"))).unwrap_err();
+        assert_eq!(error, "Invalid rules: 1:62: rules[0].target: Unknown definition: nobody");
+
+        // 使われていない定義の循環も検出する
+        let cycle = r#"
+            { "name": "a", "body": { "type": "First", "array": { "type": "Ref", "name": "b" } } },
+            { "name": "b", "body": { "type": "TeamMembers", "team_side": { "type": "CharacterTeam", "character": { "type": "Ref", "name": "a" } } } }
+        "#;
+        let error = parse_rules_from_json(&rule_set(cycle, heal_self)).unwrap_err();
+        assert_eq!(
+            error,
+            "Invalid rules: 2:64: definitions[0].body.array.team_side.character: Definition cycle: a -> b -> a; \
+             3:114: definitions[1].body.team_side.character.array: Definition cycle: b -> a -> b"
+        );
+
+        let duplicated = r#"{ "name": "me", "body": { "type": "ActingCharacter" } }, { "name": "me", "body": { "type": "ActingCharacter" } }"#;
+        let error = parse_rules_from_json(&rule_set(duplicated, &strike_ref("me"))).unwrap_err();
+        assert_eq!(error, "Invalid rules: 1:76: definitions[1]: Definition me is declared more than once");
+    }
+
     #[test]
     fn test_convert_simple_nodes() {
         let rule_set = RuleSet {
+            definitions: Vec::new(),
             rules: vec![
                 StructuredTokenInput::Strike { target: Box::new(StructuredTokenInput::ActingCharacter) },
             ],
         };
         
         let node_rules: Vec<_> = rule_set.rules.iter()
-            .filter_map(|token| convert_to_rule_node(token))
-            .collect();
+            .map(convert_to_rule_node)
+            .collect::<Result<_, _>>()
+            .unwrap();
         assert_eq!(node_rules.len(), 1);
     }
 
     #[test]
     fn test_convert_complex_nodes() {
         let rule_set = RuleSet {
+            definitions: Vec::new(),
             rules: vec![
                 StructuredTokenInput::Check {
                     condition: Box::new(StructuredTokenInput::GreaterThan {
@@ -92,8 +320,446 @@ mod tests {
         };
         
         let node_rules: Vec<_> = rule_set.rules.iter()
-            .filter_map(|token| convert_to_rule_node(token))
-            .collect();
+            .map(convert_to_rule_node)
+            .collect::<Result<_, _>>()
+            .unwrap();
         assert_eq!(node_rules.len(), 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_invalid_rule_reports_path() {
+        let rule_json = r#"{
+            "rules": [
+                { "type": "Heal", "target": { "type": "ActingCharacter" } },
+                {
+                    "type": "Strike",
+                    "target": {
+                        "type": "RandomPick",
+                        "array": { "type": "AllTeamSides" }
+                    }
+                }
+            ]
+        }"#;
+
+        let error = parse_rules_from_json(rule_json).unwrap_err();
+        assert_eq!(
+            error,
+            "Invalid rules: 6:31: rules[1].target: Type mismatch: expected Character, found TeamSide"
+        );
+
+        let rule_set: RuleSet = serde_json::from_str(rule_json).unwrap();
+        let errors = validate_rule_set(&rule_set).unwrap_err();
+        assert_eq!(errors[0].path, "rules[1].target");
+    }
+
+    #[test]
+    fn test_file_errors_report_location() {
+        // 版のない（移行される）ファイルでも元の位置を指す
+        let path = std::env::temp_dir().join(format!("json-rule-location-{}.json", std::process::id()));
+        let content = "{\n  \"rules\": [\n    { \"type\": \"Strike\", \"target\": { \"type\": \"ActingCharacter\" } },\n    {\n      \"type\": \"Heal\",\n      \"target\": { \"type\": \"Number\", \"value\": 50 }\n    }\n  ]\n}\n";
+        fs::write(&path, content).unwrap();
+        let error = load_rules_from_file(&path).unwrap_err();
+        assert_eq!(
+            error,
+            format!("Invalid rules: {}:6:17: rules[1].target: Type mismatch: expected Character, found I32", path.display())
+        );
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_json_fields_follow_token_registry() {
+        let missing_slot = r#"{ "rules": [ { "type": "Strike" } ] }"#;
+        let error = parse_rules_from_json(missing_slot).unwrap_err();
+        assert!(error.contains("missing field `target`"), "{}", error);
+
+        let unknown_token = r#"{ "rules": [ { "type": "Teleport", "target": { "type": "ActingCharacter" } } ] }"#;
+        let error = parse_rules_from_json(unknown_token).unwrap_err();
+        assert!(error.contains("unknown token type `Teleport`"), "{}", error);
+
+        let unknown_slot = r#"{ "rules": [ { "type": "Heal", "target": { "type": "ActingCharacter" }, "amount": { "type": "Number", "value": 3 } } ] }"#;
+        let error = parse_rules_from_json(unknown_slot).unwrap_err();
+        assert!(error.contains("unknown field `amount` for Heal"), "{}", error);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let rule_json = r#"{
+            "rules": [
+                {
+                    "type": "Check",
+                    "condition": {
+                        "type": "GreaterThan",
+                        "left": { "type": "Number", "value": 50 },
+                        "right": { "type": "CharacterToHp", "character": { "type": "ActingCharacter" } }
+                    },
+                    "then_action": { "type": "Heal", "target": { "type": "ActingCharacter" } }
+                }
+            ]
+        }"#;
+
+        let rule_set = parse_rules_from_json(rule_json).unwrap();
+        let serialized = serde_json::to_string(&rule_set).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(rule_json).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&serialized).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_let_rule_json_round_trip() {
+        // ランダムに選んだ1人のHPを確認し、同じキャラクターを攻撃する
+        let rule_json = r#"{
+            "rules": [
+                {
+                    "type": "Let",
+                    "name": "target",
+                    "value": { "type": "RandomPick", "array": { "type": "AllCharacters" } },
+                    "body": {
+                        "type": "Check",
+                        "condition": {
+                            "type": "GreaterThan",
+                            "left": { "type": "CharacterToHp", "character": { "type": "Var", "name": "target" } },
+                            "right": { "type": "Number", "value": 50 }
+                        },
+                        "then_action": { "type": "Strike", "target": { "type": "Var", "name": "target" } }
+                    }
+                }
+            ]
+        }"#;
+
+        let rule_set = parse_rules_from_json(rule_json).unwrap();
+        let serialized = serde_json::to_string(&rule_set).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(rule_json).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&serialized).unwrap(), expected);
+
+        let missing_name = r#"{ "rules": [ { "type": "Var" } ] }"#;
+        let error = parse_rules_from_json(missing_name).unwrap_err();
+        assert!(error.contains("missing field `name`"), "{}", error);
+
+        let unbound = rule_json.replace(
+            r#""target": { "type": "Var", "name": "target" }"#,
+            r#""target": { "type": "Var", "name": "other" }"#,
+        );
+        let error = parse_rules_from_json(&unbound).unwrap_err();
+        assert_eq!(error, "Invalid rules: 14:70: rules[0].body.then_action.target: Var is used outside of a scope that binds other");
+    }
+
+    #[test]
+    fn test_if_rule_json() {
+        // HPが30未満なら自分を、そうでなければ味方の誰かを回復
+        let rule_json = r#"{
+            "rules": [
+                {
+                    "type": "Heal",
+                    "target": {
+                        "type": "If",
+                        "condition": {
+                            "type": "LessThan",
+                            "left": { "type": "CharacterToHp", "character": { "type": "ActingCharacter" } },
+                            "right": { "type": "Number", "value": 30 }
+                        },
+                        "then": { "type": "ActingCharacter" },
+                        "else": { "type": "RandomPick", "array": { "type": "TeamMembers", "team_side": { "type": "Hero" } } }
+                    }
+                }
+            ]
+        }"#;
+
+        let rule_set = parse_rules_from_json(rule_json).unwrap();
+        let serialized = serde_json::to_string(&rule_set).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(rule_json).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&serialized).unwrap(), expected);
+
+        let mismatched = rule_json.replace(r#""then": { "type": "ActingCharacter" }"#, r#""then": { "type": "Enemy" }"#);
+        let error = parse_rules_from_json(&mismatched).unwrap_err();
+        assert_eq!(error, "Invalid rules: 13:33: rules[0].target.else: Type mismatch: expected TeamSide, found Character");
+    }
+
+    #[test]
+    fn test_first_of_rule_json() {
+        // HPが50未満なら回復、そうでなければHP30未満の敵、いなければランダムな敵を攻撃
+        let rule_json = r#"{
+            "rules": [
+                {
+                    "type": "FirstOf",
+                    "alternatives": [
+                        {
+                            "type": "Check",
+                            "condition": {
+                                "type": "LessThan",
+                                "left": { "type": "CharacterToHp", "character": { "type": "ActingCharacter" } },
+                                "right": { "type": "Number", "value": 50 }
+                            },
+                            "then_action": { "type": "Heal", "target": { "type": "ActingCharacter" } }
+                        },
+                        {
+                            "type": "Strike",
+                            "target": {
+                                "type": "FirstOf",
+                                "alternatives": [
+                                    {
+                                        "type": "First",
+                                        "array": {
+                                            "type": "FilterList",
+                                            "array": { "type": "TeamMembers", "team_side": { "type": "Enemy" } },
+                                            "condition": {
+                                                "type": "LessThan",
+                                                "left": { "type": "CharacterToHp", "character": { "type": "Element" } },
+                                                "right": { "type": "Number", "value": 30 }
+                                            }
+                                        }
+                                    },
+                                    { "type": "RandomPick", "array": { "type": "TeamMembers", "team_side": { "type": "Enemy" } } }
+                                ]
+                            }
+                        }
+                    ]
+                }
+            ]
+        }"#;
+
+        let rule_set = parse_rules_from_json(rule_json).unwrap();
+        let serialized = serde_json::to_string(&rule_set).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(rule_json).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&serialized).unwrap(), expected);
+
+        // 候補が1つでも配列で書く
+        let single = r#"{ "rules": [ { "type": "FirstOf", "alternatives": [ { "type": "Heal", "target": { "type": "ActingCharacter" } } ] } ] }"#;
+        let rule_set = parse_rules_from_json(single).unwrap();
+        let serialized: serde_json::Value = serde_json::from_str(&serde_json::to_string(&rule_set).unwrap()).unwrap();
+        assert_eq!(serialized, serde_json::from_str::<serde_json::Value>(single).unwrap());
+
+        let not_a_list = r#"{ "rules": [ { "type": "FirstOf", "alternatives": { "type": "Heal", "target": { "type": "ActingCharacter" } } } ] }"#;
+        let error = parse_rules_from_json(not_a_list).unwrap_err();
+        assert!(error.contains("`alternatives` of FirstOf must be a list of tokens"), "{}", error);
+
+        let empty = r#"{ "rules": [ { "type": "FirstOf", "alternatives": [] } ] }"#;
+        let error = parse_rules_from_json(empty).unwrap_err();
+        assert!(error.contains("FirstOf requires at least one alternative"), "{}", error);
+    }
+
+    #[test]
+    fn test_named_parameters_json() {
+        // 味方の誰かよりHPが低い敵を攻撃
+        let rule_json = r#"{
+            "rules": [
+                {
+                    "type": "Strike",
+                    "target": {
+                        "type": "RandomPick",
+                        "array": {
+                            "type": "FilterList",
+                            "name": "enemy",
+                            "array": { "type": "TeamMembers", "team_side": { "type": "Enemy" } },
+                            "condition": {
+                                "type": "Any",
+                                "name": "ally",
+                                "array": { "type": "TeamMembers", "team_side": { "type": "Hero" } },
+                                "condition": {
+                                    "type": "LessThan",
+                                    "left": { "type": "CharacterToHp", "character": { "type": "Var", "name": "enemy" } },
+                                    "right": { "type": "CharacterToHp", "character": { "type": "Var", "name": "ally" } }
+                                }
+                            }
+                        }
+                    }
+                }
+            ]
+        }"#;
+
+        let rule_set = parse_rules_from_json(rule_json).unwrap();
+        let serialized = serde_json::to_string(&rule_set).unwrap();
+        let expected: serde_json::Value = serde_json::from_str(rule_json).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&serialized).unwrap(), expected);
+
+        let invalid_json = r#"{ "rules": [ { "type": "Strike", "name": "x", "target": { "type": "ActingCharacter" } } ] }"#;
+        let error = parse_rules_from_json(invalid_json).unwrap_err();
+        assert!(error.contains("Strike does not take a name"), "{}", error);
+    }
+
+    #[test]
+    fn test_parse_map_rule_json() {
+        // 敵の最大HPが50より大きければ、そのキャラクターを攻撃
+        let rule_json = r#"{
+            "rules": [
+                {
+                    "type": "Check",
+                    "condition": {
+                        "type": "GreaterThan",
+                        "left": {
+                            "type": "Max",
+                            "array": {
+                                "type": "Map",
+                                "array": { "type": "TeamMembers", "team_side": { "type": "Enemy" } },
+                                "transform": { "type": "CharacterToHp", "character": { "type": "Element" } }
+                            }
+                        },
+                        "right": { "type": "Number", "value": 50 }
+                    },
+                    "then_action": {
+                        "type": "Strike",
+                        "target": {
+                            "type": "CharacterHpToCharacter",
+                            "character_hp": {
+                                "type": "Max",
+                                "array": {
+                                    "type": "Map",
+                                    "array": { "type": "TeamMembers", "team_side": { "type": "Enemy" } },
+                                    "transform": { "type": "CharacterToHp", "character": { "type": "Element" } }
+                                }
+                            }
+                        }
+                    }
+                }
+            ]
+        }"#;
+
+        let rule_set = parse_rules_from_json(rule_json).unwrap();
+        assert!(matches!(rule_set.rules[0], StructuredTokenInput::Check { .. }));
+        assert!(validate_rule_set(&rule_set).is_ok());
+    }
+
+    #[test]
+    fn test_parse_array_aggregate_rule_json() {
+        // 味方が全員HP50以上、かつ敵のHP合計が100より大きければ攻撃
+        let rule_json = r#"{
+            "rules": [
+                {
+                    "type": "Check",
+                    "condition": {
+                        "type": "And",
+                        "left": {
+                            "type": "All",
+                            "array": { "type": "TeamMembers", "team_side": { "type": "Hero" } },
+                            "condition": {
+                                "type": "GreaterOrEqual",
+                                "left": { "type": "CharacterToHp", "character": { "type": "Element" } },
+                                "right": { "type": "Number", "value": 50 }
+                            }
+                        },
+                        "right": {
+                            "type": "GreaterThan",
+                            "left": {
+                                "type": "Sum",
+                                "array": {
+                                    "type": "Map",
+                                    "array": { "type": "TeamMembers", "team_side": { "type": "Enemy" } },
+                                    "transform": { "type": "CharacterToHp", "character": { "type": "Element" } }
+                                }
+                            },
+                            "right": { "type": "Number", "value": 100 }
+                        }
+                    },
+                    "then_action": { "type": "Strike", "target": { "type": "ActingCharacter" } }
+                }
+            ]
+        }"#;
+
+        let rule_set = parse_rules_from_json(rule_json).unwrap();
+        assert!(validate_rule_set(&rule_set).is_ok());
+
+        // Sum は数値配列のみ（TeamSide の配列は不可）
+        let invalid_json = r#"{
+            "rules": [
+                {
+                    "type": "Check",
+                    "condition": {
+                        "type": "GreaterThan",
+                        "left": { "type": "Sum", "array": { "type": "AllTeamSides" } },
+                        "right": { "type": "Number", "value": 1 }
+                    },
+                    "then_action": { "type": "Strike", "target": { "type": "ActingCharacter" } }
+                }
+            ]
+        }"#;
+        let error = parse_rules_from_json(invalid_json).unwrap_err();
+        assert_eq!(error, "Invalid rules: 7:33: rules[0].condition.left: Sum requires Numeric, found TeamSide");
+    }
+
+    #[test]
+    fn test_parse_arithmetic_rule_json() {
+        // HPが最大HP(100)の3分の1未満なら回復
+        let rule_json = r#"{
+            "rules": [
+                {
+                    "type": "Check",
+                    "condition": {
+                        "type": "LessThan",
+                        "left": { "type": "CharacterToHp", "character": { "type": "ActingCharacter" } },
+                        "right": {
+                            "type": "Div",
+                            "left": { "type": "Number", "value": 100 },
+                            "right": { "type": "Number", "value": 3 }
+                        }
+                    },
+                    "then_action": { "type": "Heal", "target": { "type": "ActingCharacter" } }
+                },
+                {
+                    "type": "Check",
+                    "condition": {
+                        "type": "GreaterThan",
+                        "left": {
+                            "type": "Percent",
+                            "base": { "type": "CharacterToHp", "character": { "type": "ActingCharacter" } },
+                            "percent": { "type": "Number", "value": 50 }
+                        },
+                        "right": { "type": "Number", "value": 20 }
+                    },
+                    "then_action": { "type": "Strike", "target": { "type": "ActingCharacter" } }
+                }
+            ]
+        }"#;
+
+        let rule_set = parse_rules_from_json(rule_json).unwrap();
+        assert_eq!(rule_set.rules.len(), 2);
+        assert!(validate_rule_set(&rule_set).is_ok());
+
+        // 算術の結果は数値なので、キャラクターとしては使えない
+        let invalid_json = r#"{
+            "rules": [
+                {
+                    "type": "Strike",
+                    "target": {
+                        "type": "Add",
+                        "left": { "type": "Number", "value": 1 },
+                        "right": { "type": "Number", "value": 2 }
+                    }
+                }
+            ]
+        }"#;
+        let error = parse_rules_from_json(invalid_json).unwrap_err();
+        assert_eq!(error, "Invalid rules: 5:31: rules[0].target: Type mismatch: expected Character, found I32");
+    }
+
+    #[test]
+    fn test_legacy_rule_files_are_migrated() {
+        // version のないファイルは版1として読み、TrueOrFalse を TrueOrFalseRandom に読み替える
+        let legacy = r#"{
+            "rules": [{
+                "type": "Check",
+                "condition": { "type": "TrueOrFalse" },
+                "then_action": { "type": "Heal", "target": { "type": "ActingCharacter" } }
+            }]
+        }"#;
+        let rule_set = parse_rules_from_json(legacy).unwrap();
+        let StructuredTokenInput::Check { condition, .. } = &rule_set.rules[0] else {
+            panic!("Expected Check");
+        };
+        assert_eq!(**condition, StructuredTokenInput::TrueOrFalseRandom);
+
+        // 現在の版では読み替えない
+        let current = legacy.replacen("{", &format!("{{ \"version\": {},", CURRENT_VERSION), 1);
+        let error = parse_rules_from_json(&current).unwrap_err();
+        assert!(error.contains("unknown token type `TrueOrFalse`"), "{}", error);
+
+        let newer = r#"{ "version": 99, "rules": [] }"#;
+        let error = parse_rules_from_json(newer).unwrap_err();
+        assert_eq!(error, format!("Rule file version 99 is newer than the supported version {}", CURRENT_VERSION));
+    }
+
+    #[test]
+    fn test_rule_set_to_json_writes_current_version() {
+        let rule_set = load_rules_from_file("../../rules/enemy_rules.json").unwrap();
+        let json = rule_set_to_json(&rule_set);
+        assert!(json.starts_with(&format!("{{\n  \"version\": {},", CURRENT_VERSION)), "{}", json);
+        assert_eq!(parse_rules_from_json(&json).unwrap(), rule_set);
+    }
+}
@@ -0,0 +1,220 @@
+// JSON Schema 生成 - トークンレジストリから RuleSet / StructuredTokenInput のスキーマを作る
+//
+// 各トークンは `Token.<名前>`、スロットが要求する型は `Type.<型>` として $defs に置く。
+// 型の一致は型推論より緩く判定する（型パラメータは何にでも一致する）ので、
+// 正しいルールファイルをスキーマが拒否することはない
+
+use crate::migration::CURRENT_VERSION;
+use serde_json::{json, Map, Value};
+use token_input::game_type::{GameType, GameTypeSystem};
+use token_input::token_registry::{registry, NameField, TokenDefinition};
+use token_input::type_system::{Type, TypeSystem};
+
+pub const SCHEMA_DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+pub fn rule_set_schema() -> Value {
+    let action = GameType::Action.ty();
+    let any = Type::param(0);
+
+    // ルート・定義本体・各スロットで要求される型
+    let mut slot_types = vec![action.clone(), any.clone()];
+    let mut definitions = Map::new();
+    for definition in registry().iter() {
+        for argument in &definition.metadata.arguments {
+            let ty = with_bounds(&argument.argument_type, &definition.metadata.type_parameters);
+            if !slot_types.contains(&ty) {
+                slot_types.push(ty);
+            }
+        }
+        definitions.insert(format!("Token.{}", definition.name()), token_schema(definition));
+    }
+    for ty in &slot_types {
+        let tokens: Vec<Value> = registry()
+            .iter()
+            .filter(|definition| may_match(&definition.metadata.return_type, ty))
+            .map(|definition| reference(&format!("Token.{}", definition.name())))
+            .collect();
+        definitions.insert(format!("Type.{}", type_key(ty)), json!({ "anyOf": tokens }));
+    }
+
+    json!({
+        "$schema": SCHEMA_DRAFT,
+        "title": "RuleSet",
+        "type": "object",
+        "properties": {
+            "$schema": { "type": "string" },
+            "version": { "type": "integer", "minimum": 1, "maximum": CURRENT_VERSION },
+            "definitions": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string" },
+                        "body": type_reference(&any),
+                    },
+                    "required": ["name", "body"],
+                    "additionalProperties": false,
+                },
+            },
+            "rules": {
+                "type": "array",
+                "items": type_reference(&action),
+            },
+        },
+        "required": ["rules"],
+        "additionalProperties": false,
+        "$defs": definitions,
+    })
+}
+
+fn token_schema(definition: &TokenDefinition) -> Value {
+    let mut properties = Map::new();
+    let mut required = vec![json!("type")];
+    properties.insert("type".to_string(), json!({ "const": definition.name() }));
+
+    if definition.name_field != NameField::Absent {
+        properties.insert("name".to_string(), json!({ "type": "string" }));
+    }
+    if definition.name_field == NameField::Required {
+        required.push(json!("name"));
+    }
+    if definition.has_value {
+        properties.insert("value".to_string(), json!({ "type": "integer" }));
+        required.push(json!("value"));
+    }
+    for argument in &definition.metadata.arguments {
+        let ty = with_bounds(&argument.argument_type, &definition.metadata.type_parameters);
+        let schema = if argument.variadic {
+            json!({ "type": "array", "items": type_reference(&ty), "minItems": 1 })
+        } else {
+            type_reference(&ty)
+        };
+        properties.insert(argument.slot.to_string(), schema);
+        required.push(json!(argument.slot));
+    }
+
+    json!({
+        "description": definition.display_text,
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// 上限のある型パラメータを上限の型に置き換える（上限のないものは型変数のまま）
+fn with_bounds(ty: &Type<GameType>, bounds: &[Option<Type<GameType>>]) -> Type<GameType> {
+    match ty {
+        Type::Con(id, arguments) => Type::app(*id, arguments.iter().map(|a| with_bounds(a, bounds)).collect()),
+        Type::Var(index) => match bounds.get(*index as usize) {
+            Some(Some(bound)) => bound.clone(),
+            _ => Type::param(0),
+        },
+    }
+}
+
+/// `found` を返すトークンが `expected` のスロットに置ける可能性があるか
+fn may_match(found: &Type<GameType>, expected: &Type<GameType>) -> bool {
+    match (found, expected) {
+        (Type::Var(_), _) | (_, Type::Var(_)) => true,
+        (Type::Con(found_id, found_arguments), Type::Con(expected_id, expected_arguments))
+            if found_id == expected_id && found_arguments.len() == expected_arguments.len() =>
+        {
+            found_arguments.iter().zip(expected_arguments).all(|(f, e)| may_match(f, e))
+        }
+        _ => GameTypeSystem.is_subtype(found, expected),
+    }
+}
+
+/// $defs のキーに使う型名（`Array<Character>` → `ArrayOfCharacter`、型変数 → `Any`）
+fn type_key(ty: &Type<GameType>) -> String {
+    match ty {
+        Type::Con(id, arguments) => {
+            let mut key = id.to_string();
+            for argument in arguments {
+                key.push_str("Of");
+                key.push_str(&type_key(argument));
+            }
+            key
+        }
+        Type::Var(_) => "Any".to_string(),
+    }
+}
+
+fn type_reference(ty: &Type<GameType>) -> Value {
+    reference(&format!("Type.{}", type_key(ty)))
+}
+
+fn reference(key: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{}", key) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alternatives(schema: &Value, key: &str) -> Vec<String> {
+        schema["$defs"][key]["anyOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["$ref"].as_str().unwrap().trim_start_matches("#/$defs/Token.").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_slots_reference_their_types() {
+        let schema = rule_set_schema();
+        assert_eq!(schema["properties"]["rules"]["items"], json!({ "$ref": "#/$defs/Type.Action" }));
+
+        let strike = &schema["$defs"]["Token.Strike"];
+        assert_eq!(strike["properties"]["type"], json!({ "const": "Strike" }));
+        assert_eq!(strike["properties"]["target"], json!({ "$ref": "#/$defs/Type.Character" }));
+        assert_eq!(strike["required"], json!(["type", "target"]));
+        assert_eq!(strike["additionalProperties"], json!(false));
+
+        // 型の合うトークンと、型パラメータを返すトークンだけが並ぶ
+        let characters = alternatives(&schema, "Type.Character");
+        for token in ["ActingCharacter", "RandomPick", "CharacterHpToCharacter", "Element", "Var", "If", "Ref"] {
+            assert!(characters.contains(&token.to_string()), "{} should be a Character", token);
+        }
+        for token in ["Enemy", "Strike", "AllCharacters", "Number"] {
+            assert!(!characters.contains(&token.to_string()), "{} should not be a Character", token);
+        }
+
+        // 上限付きの型パラメータは上限で制約する（Max の配列は数値の配列）
+        let max = &schema["$defs"]["Token.Max"];
+        assert_eq!(max["properties"]["array"], json!({ "$ref": "#/$defs/Type.ArrayOfNumeric" }));
+        let numeric_arrays = alternatives(&schema, "Type.ArrayOfNumeric");
+        assert!(numeric_arrays.contains(&"AllCharacters".to_string()));
+        assert!(!numeric_arrays.contains(&"Number".to_string()));
+    }
+
+    #[test]
+    fn test_value_name_and_variadic_fields() {
+        let schema = rule_set_schema();
+        let number = &schema["$defs"]["Token.Number"];
+        assert_eq!(number["properties"]["value"], json!({ "type": "integer" }));
+        assert_eq!(number["required"], json!(["type", "value"]));
+
+        assert_eq!(schema["$defs"]["Token.Let"]["required"][1], json!("name"));
+        let filter = &schema["$defs"]["Token.FilterList"];
+        assert_eq!(filter["properties"]["name"], json!({ "type": "string" }));
+        assert!(!filter["required"].as_array().unwrap().contains(&json!("name")));
+
+        let first_of = &schema["$defs"]["Token.FirstOf"];
+        assert_eq!(
+            first_of["properties"]["alternatives"],
+            json!({ "type": "array", "items": { "$ref": "#/$defs/Type.Any" }, "minItems": 1 })
+        );
+        assert_eq!(alternatives(&schema, "Type.Any").len(), registry().iter().count());
+    }
+
+    #[test]
+    fn test_checked_in_schema_is_up_to_date() {
+        // 再生成: cargo run -p json-rule --bin rule_schema > rules/rule_set.schema.json
+        let checked_in = std::fs::read_to_string("../../rules/rule_set.schema.json").unwrap();
+        let checked_in: Value = serde_json::from_str(&checked_in).unwrap();
+        assert_eq!(checked_in, rule_set_schema());
+    }
+}
@@ -0,0 +1,244 @@
+// JSON の位置情報 - 各値の開始位置を CompileError と同じ形式のパスで引けるようにする
+//
+// serde_json は値の位置を残さないので、読み込みに成功した JSON をもう一度走査して
+// `rules[0].then_action.target` のようなパスごとに開始位置を記録する。
+// パスは CompileError と同じ token_input::path で組み立てる
+
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+use token_input::{path, CompileError};
+
+/// 1始まりの行・列（文字単位）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct JsonSpans {
+    spans: HashMap<String, Span>,
+}
+
+impl JsonSpans {
+    /// 壊れた JSON は読めたところまで記録する
+    pub fn new(source: &str) -> Self {
+        let mut scanner = Scanner {
+            chars: source.chars().peekable(),
+            position: Span { line: 1, column: 1 },
+            spans: HashMap::new(),
+        };
+        scanner.value(Some(String::new()));
+        Self { spans: scanner.spans }
+    }
+
+    /// パスの値の位置。JSON にないパス（Ref の展開先など）は、JSON にある最も深い祖先の位置
+    pub fn locate(&self, path: &str) -> Option<Span> {
+        std::iter::successors(Some(path), |path| path::parent(path)).find_map(|path| self.spans.get(path).copied())
+    }
+
+    /// `ファイル:行:列: パス: メッセージ` の形でエラーを書く（位置が分からなければ行・列を省く）
+    pub fn describe(&self, error: &CompileError, file: Option<&Path>) -> String {
+        match (self.locate(&error.path), file) {
+            (Some(span), Some(file)) => format!("{}:{}: {}", file.display(), span, error),
+            (Some(span), None) => format!("{}: {}", span, error),
+            (None, Some(file)) => format!("{}: {}", file.display(), error),
+            (None, None) => error.to_string(),
+        }
+    }
+}
+
+struct Scanner<'a> {
+    chars: Peekable<Chars<'a>>,
+    position: Span,
+    spans: HashMap<String, Span>,
+}
+
+impl Scanner<'_> {
+    fn advance(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.advance();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        self.skip_whitespace();
+        (self.advance()? == expected).then_some(())
+    }
+
+    /// `path` が None の値（パスで表せないキーの下）は位置を記録せずに読み飛ばす
+    fn value(&mut self, path: Option<String>) -> Option<()> {
+        self.skip_whitespace();
+        let c = *self.chars.peek()?;
+        if let Some(path) = &path {
+            self.spans.insert(path.clone(), self.position);
+        }
+        match c {
+            '{' => self.object(path.as_deref()),
+            '[' => self.array(path.as_deref()),
+            '"' => self.string().map(|_| ()),
+            _ => {
+                // 数値・true・false・null
+                while self.chars.peek().is_some_and(|c| !matches!(c, ',' | ']' | '}') && !c.is_whitespace()) {
+                    self.advance();
+                }
+                Some(())
+            }
+        }
+    }
+
+    fn object(&mut self, path: Option<&str>) -> Option<()> {
+        self.advance();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.advance();
+            return Some(());
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            // `.` や `[` を含むキーは CompileError のパスに現れず、記録すると別のパスと紛れる
+            let child = path.filter(|_| path::is_plain_key(&key)).map(|path| path::join(path, &key));
+            self.value(child)?;
+            self.skip_whitespace();
+            match self.advance()? {
+                ',' => continue,
+                '}' => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    fn array(&mut self, path: Option<&str>) -> Option<()> {
+        self.advance();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.advance();
+            return Some(());
+        }
+        for index in 0.. {
+            self.value(path.map(|path| path::index(path, index)))?;
+            self.skip_whitespace();
+            match self.advance()? {
+                ',' => continue,
+                ']' => break,
+                _ => return None,
+            }
+        }
+        Some(())
+    }
+
+    /// 文字列を読んでエスケープを解いた値を返す
+    fn string(&mut self) -> Option<String> {
+        if self.advance()? != '"' {
+            return None;
+        }
+        let mut raw = String::from('"');
+        loop {
+            let c = self.advance()?;
+            raw.push(c);
+            match c {
+                '"' => break,
+                '\\' => raw.push(self.advance()?),
+                _ => {}
+            }
+        }
+        serde_json::from_str(&raw).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"{
+  "rules": [
+    {
+      "type": "Heal",
+      "target": { "type": "Number", "value": 50 }
+    },
+    {
+      "type": "FirstOf",
+      "alternatives": [
+        { "type": "Strike", "target": { "name": "w\"eak", "type": "Ref" } },
+        { "type": "Heal", "target": { "type": "ActingCharacter" } }
+      ]
+    }
+  ]
+}"#;
+
+    #[test]
+    fn test_locate_values_by_path() {
+        let spans = JsonSpans::new(SOURCE);
+        assert_eq!(spans.locate(""), Some(Span { line: 1, column: 1 }));
+        assert_eq!(spans.locate("rules[0]"), Some(Span { line: 3, column: 5 }));
+        assert_eq!(spans.locate("rules[0].target"), Some(Span { line: 5, column: 17 }));
+        assert_eq!(spans.locate("rules[1].alternatives[1].target"), Some(Span { line: 11, column: 37 }));
+        // エスケープを含む文字列の後も位置がずれない
+        assert_eq!(spans.locate("rules[1].alternatives[0].target.type"), Some(Span { line: 10, column: 67 }));
+    }
+
+    #[test]
+    fn test_locate_falls_back_to_the_deepest_ancestor() {
+        let spans = JsonSpans::new(SOURCE);
+        // Ref の展開先のパスは Ref の位置になる
+        assert_eq!(spans.locate("rules[1].alternatives[0].target.array.condition"), Some(Span { line: 10, column: 39 }));
+        assert_eq!(spans.locate("rules[5].target"), Some(Span { line: 2, column: 12 }));
+        assert_eq!(JsonSpans::new("").locate("rules[0]"), None);
+    }
+
+    #[test]
+    fn test_keys_that_are_not_path_segments_are_skipped() {
+        // `.` や `[` を含むキーの値は、同じ文字列になる本物のパスの位置を上書きしない
+        let source = r#"{
+  "rules[0]": { "target": { "type": "Hero" } },
+  "notes.rules": [{ "type": "Heal" }],
+  "rules": [
+    { "type": "Heal", "target": { "type": "Number", "value": 50 }, "target.type": 1 }
+  ]
+}"#;
+        let spans = JsonSpans::new(source);
+        assert_eq!(spans.locate("rules[0]"), Some(Span { line: 5, column: 5 }));
+        assert_eq!(spans.locate("rules[0].target"), Some(Span { line: 5, column: 33 }));
+        assert_eq!(spans.locate("rules[0].target.type"), Some(Span { line: 5, column: 43 }));
+        // 読み飛ばしたキーの下は記録されない
+        assert_eq!(spans.locate("notes.rules[0]"), Some(Span { line: 1, column: 1 }));
+    }
+
+    #[test]
+    fn test_describe_errors() {
+        let spans = JsonSpans::new(SOURCE);
+        let error = CompileError::new("rules[0].target", "Type mismatch: expected Character, found Number");
+        assert_eq!(
+            spans.describe(&error, Some(Path::new("rules/enemy_rules.json"))),
+            "rules/enemy_rules.json:5:17: rules[0].target: Type mismatch: expected Character, found Number"
+        );
+        assert_eq!(spans.describe(&error, None), "5:17: rules[0].target: Type mismatch: expected Character, found Number");
+        assert_eq!(JsonSpans::default().describe(&error, None), error.to_string());
+        assert_eq!(
+            JsonSpans::default().describe(&error, Some(Path::new("enemy.yaml"))),
+            "enemy.yaml: rules[0].target: Type mismatch: expected Character, found Number"
+        );
+    }
+}
@@ -0,0 +1,69 @@
+// CompileError - トークン木のどこで変換に失敗したかを表すエラー
+
+use crate::game_type::GameType;
+use crate::path;
+use crate::type_system::{Type, TypeError};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompileError {
+    /// 失敗したトークンまでのパス（例: `rules[0].then_action.target.array`）
+    pub path: String,
+    pub expected: Option<Type<GameType>>,
+    pub found: Option<Type<GameType>>,
+    pub message: String,
+}
+
+impl CompileError {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            expected: None,
+            found: None,
+            message: message.into(),
+        }
+    }
+
+    /// パスの先頭に `prefix` を付ける
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.path = path::join(prefix, &self.path);
+        self
+    }
+}
+
+impl From<TypeError<GameType>> for CompileError {
+    fn from(error: TypeError<GameType>) -> Self {
+        Self {
+            path: path::from_segments(&error.path),
+            expected: error.expected().cloned(),
+            found: error.found().cloned(),
+            message: error.kind.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_prefix() {
+        let error = CompileError::new("", "Unknown token: Foo").with_prefix("rules[2]");
+        assert_eq!(error.to_string(), "rules[2]: Unknown token: Foo");
+
+        let error = CompileError::new("target.array", "Type mismatch").with_prefix("rules[0]");
+        assert_eq!(error.path, "rules[0].target.array");
+    }
+}
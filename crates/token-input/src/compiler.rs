@@ -0,0 +1,838 @@
+// Compiler - StructuredTokenInput → 型推論 → TypedAST → action_system::Node
+
+use crate::compile_error::CompileError;
+use crate::path;
+use crate::game_type::{GameType, GameTypeSystem};
+use crate::token_registry::{registry, NodeBuilder};
+use crate::type_system::{Type, TypeHole, TypeInferable, TypeInferenceEngine, TypedAST};
+use crate::{RuleSet, StructuredTokenInput};
+use action_system::{BoxedNode, EvaluationContext, Node, NumericNode, RuleNode};
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+pub type GameTypedAST = TypedAST<GameType, i32>;
+
+/// 型消去されたノード。生成時の型でのみ取り出せる
+pub struct CompiledNode(Box<dyn Any + Send + Sync>);
+
+impl CompiledNode {
+    pub fn new<T: 'static>(node: BoxedNode<T>) -> Self {
+        Self(Box::new(node))
+    }
+
+    pub fn from_node<T: 'static, N>(node: N) -> Self
+    where
+        N: for<'a> Node<T, EvaluationContext<'a>> + Send + Sync + 'static,
+    {
+        Self::new::<T>(Box::new(node))
+    }
+
+    pub fn into_node<T: 'static>(self) -> Result<BoxedNode<T>, String> {
+        self.0
+            .downcast::<BoxedNode<T>>()
+            .map(|node| *node)
+            .map_err(|_| format!("Compiled node is not a {} node", std::any::type_name::<T>()))
+    }
+}
+
+/// トークンの引数を宣言順に取り出す
+pub struct BuildArguments {
+    nodes: std::vec::IntoIter<CompiledNode>,
+}
+
+impl BuildArguments {
+    pub fn take<T: 'static>(&mut self) -> Result<BoxedNode<T>, String> {
+        self.nodes
+            .next()
+            .ok_or_else(|| "Missing compiled argument".to_string())?
+            .into_node()
+    }
+
+    /// 残りの引数を全て取り出す（可変長スロット用）
+    pub fn take_rest<T: 'static>(&mut self) -> Result<Vec<BoxedNode<T>>, String> {
+        self.nodes.by_ref().map(CompiledNode::into_node).collect()
+    }
+}
+
+/// GameType に対応する Rust の型 `$T` で `$body` を展開する
+macro_rules! dispatch_game_type {
+    ($ty:expr, |$T:ident| $body:expr, [$($variant:ident => $rust:ty),* $(,)?]) => {{
+        let ty: &$crate::type_system::Type<$crate::game_type::GameType> = $ty;
+        match ty.constructor() {
+            $(Some($crate::game_type::GameType::$variant) if ty.arguments().is_empty() => {
+                type $T = $rust;
+                $body
+            })*
+            _ => Err(format!("Unsupported type: {}", ty)),
+        }
+    }};
+}
+
+/// 配列要素になれる値型で展開する
+macro_rules! with_value_type {
+    ($ty:expr, |$T:ident| $body:expr) => {
+        $crate::compiler::dispatch_game_type!($ty, |$T| $body, [
+            Character => action_system::Character,
+            CharacterHP => action_system::CharacterHP,
+            CharacterMP => action_system::CharacterMP,
+            I32 => i32,
+            TeamSide => action_system::TeamSide,
+        ])
+    };
+}
+
+/// Numeric を実装する型で展開する
+macro_rules! with_numeric_type {
+    ($ty:expr, |$T:ident| $body:expr) => {
+        $crate::compiler::dispatch_game_type!($ty, |$T| $body, [
+            Character => action_system::Character,
+            CharacterHP => action_system::CharacterHP,
+            CharacterMP => action_system::CharacterMP,
+            I32 => i32,
+        ])
+    };
+}
+
+/// 式の型すべて（アクション・真偽値・値型・値型の配列）で展開する
+macro_rules! with_any_type {
+    ($ty:expr, |$T:ident| $body:expr) => {{
+        let ty: &$crate::type_system::Type<$crate::game_type::GameType> = $ty;
+        match (ty.constructor(), ty.arguments()) {
+            (Some($crate::game_type::GameType::Array), [element]) => {
+                $crate::compiler::with_value_type!(element, |E| {
+                    type $T = Vec<E>;
+                    $body
+                })
+            }
+            _ => $crate::compiler::dispatch_game_type!(ty, |$T| $body, [
+                Action => Box<dyn action_system::Action>,
+                Bool => bool,
+                Character => action_system::Character,
+                CharacterHP => action_system::CharacterHP,
+                CharacterMP => action_system::CharacterMP,
+                I32 => i32,
+                TeamSide => action_system::TeamSide,
+            ]),
+        }
+    }};
+}
+
+pub(crate) use {dispatch_game_type, with_any_type, with_numeric_type, with_value_type};
+
+pub struct Compiler {
+    engine: TypeInferenceEngine<GameTypeSystem>,
+    builders: HashMap<&'static str, NodeBuilder>,
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compiler {
+    pub fn new() -> Self {
+        let mut engine = TypeInferenceEngine::new(GameTypeSystem);
+        let mut builders = HashMap::new();
+        for definition in registry().iter() {
+            builders.insert(definition.name(), definition.build);
+            engine.register(definition.metadata.clone());
+        }
+        Self { engine, builders }
+    }
+
+    /// プロセスで共有するコンパイラ。型推論エンジンを呼び出しごとに作り直さない
+    pub fn shared() -> &'static Compiler {
+        static COMPILER: OnceLock<Compiler> = OnceLock::new();
+        COMPILER.get_or_init(Compiler::new)
+    }
+
+    /// ルール（Action を返す式）として型推論する
+    pub fn infer(&self, token: &StructuredTokenInput) -> Result<GameTypedAST, CompileError> {
+        self.infer_as(token, &GameType::Action.ty())
+    }
+
+    pub fn infer_as(&self, token: &StructuredTokenInput, expected: &Type<GameType>) -> Result<GameTypedAST, CompileError> {
+        Ok(self.engine.infer(token, expected)?)
+    }
+
+    /// 穴を含む式をルールとして型推論し、穴を出現順に返す
+    pub fn infer_holes<AST: TypeInferable>(&self, token: &AST) -> Result<Vec<TypeHole<GameType>>, CompileError> {
+        Ok(self.engine.infer_partial(token, &GameType::Action.ty())?)
+    }
+
+    pub fn compile(&self, token: &StructuredTokenInput) -> Result<RuleNode, CompileError> {
+        let typed = self.infer(token)?;
+        self.generate(&typed)?
+            .into_node()
+            .map_err(|message| CompileError::new("", message))
+    }
+
+    /// ルール一覧を変換する。失敗したルールは `rules[i]` から始まるパスで報告する
+    pub fn compile_rules(&self, rules: &[StructuredTokenInput]) -> Result<Vec<RuleNode>, Vec<CompileError>> {
+        let mut nodes = Vec::with_capacity(rules.len());
+        let mut errors = Vec::new();
+        for (i, rule) in rules.iter().enumerate() {
+            match self.compile(rule) {
+                Ok(node) => nodes.push(node),
+                Err(error) => errors.push(error.with_prefix(&path::index("rules", i))),
+            }
+        }
+        if errors.is_empty() {
+            Ok(nodes)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// definitions を展開してからルール一覧を変換する
+    pub fn compile_rule_set(&self, rule_set: &RuleSet) -> Result<Vec<RuleNode>, Vec<CompileError>> {
+        self.compile_rules(&rule_set.expanded_rules()?)
+    }
+
+    /// 型付きASTからノードを生成する
+    pub fn generate(&self, typed: &GameTypedAST) -> Result<CompiledNode, CompileError> {
+        self.generate_at(typed, &mut Vec::new())
+    }
+
+    fn generate_at(&self, typed: &GameTypedAST, path: &mut Vec<String>) -> Result<CompiledNode, CompileError> {
+        let mut nodes = Vec::with_capacity(typed.arguments.len());
+        for argument in &typed.arguments {
+            path.push(argument.slot.clone());
+            let node = self.generate_at(argument, path)?;
+            let node = coerce(node, &argument.node_type, &argument.expected_type)
+                .map_err(|message| CompileError::new(path::from_segments(path), message))?;
+            path.pop();
+            nodes.push(node);
+        }
+        let builder = self
+            .builders
+            .get(typed.token_name.as_str())
+            .ok_or_else(|| CompileError::new(path::from_segments(path), format!("Unknown token: {}", typed.token_name)))?;
+        builder(typed, &mut BuildArguments { nodes: nodes.into_iter() })
+            .map_err(|message| CompileError::new(path::from_segments(path), message))
+    }
+}
+
+/// 部分型として渡されたノードを要求された型に変換する
+fn coerce(node: CompiledNode, from: &Type<GameType>, to: &Type<GameType>) -> Result<CompiledNode, String> {
+    if from == to {
+        return Ok(node);
+    }
+    match to.constructor() {
+        Some(GameType::Numeric) => with_numeric_type!(from, |T| {
+            Ok(CompiledNode::from_node(NumericNode::<T>::new(node.into_node::<T>()?)))
+        }),
+        _ => Err(format!("Cannot convert {} to {}", from, to)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use action_system::{BattleContext, Character, Team, TeamSide};
+    use rand::SeedableRng;
+
+    fn number(value: i32) -> Box<StructuredTokenInput> {
+        Box::new(StructuredTokenInput::Number { value })
+    }
+
+    #[test]
+    fn test_infer_types_of_generic_tokens() {
+        let compiler = Compiler::new();
+        let rule = StructuredTokenInput::Strike {
+            target: Box::new(StructuredTokenInput::RandomPick {
+                array: Box::new(StructuredTokenInput::FilterList {
+                    name: None,
+                    array: Box::new(StructuredTokenInput::AllCharacters),
+                    condition: Box::new(StructuredTokenInput::Eq {
+                        left: Box::new(StructuredTokenInput::CharacterTeam {
+                            character: Box::new(StructuredTokenInput::Element),
+                        }),
+                        right: Box::new(StructuredTokenInput::Enemy),
+                    }),
+                }),
+            }),
+        };
+
+        let typed = compiler.infer(&rule).unwrap();
+        let random_pick = &typed.arguments[0];
+        assert_eq!(random_pick.node_type, GameType::Character.ty());
+        let filter_list = &random_pick.arguments[0];
+        assert_eq!(filter_list.node_type, GameType::array_of(GameType::Character.ty()));
+        let element = &filter_list.arguments[1].arguments[0].arguments[0];
+        assert_eq!(element.token_name, "Element");
+        assert_eq!(element.node_type, GameType::Character.ty());
+    }
+
+    #[test]
+    fn test_numeric_arguments_record_coercion() {
+        let compiler = Compiler::new();
+        let condition = StructuredTokenInput::GreaterThan {
+            left: Box::new(StructuredTokenInput::CharacterToHp {
+                character: Box::new(StructuredTokenInput::ActingCharacter),
+            }),
+            right: number(50),
+        };
+
+        let typed = compiler.infer_as(&condition, &GameType::Bool.ty()).unwrap();
+        assert_eq!(typed.arguments[0].node_type, GameType::CharacterHP.ty());
+        assert_eq!(typed.arguments[0].expected_type, GameType::Numeric.ty());
+        assert_eq!(typed.arguments[1].value, Some(50));
+    }
+
+    #[test]
+    fn test_type_errors() {
+        let compiler = Compiler::new();
+
+        // Strikeの対象にTeamSideは渡せない
+        let rule = StructuredTokenInput::Strike {
+            target: Box::new(StructuredTokenInput::Enemy),
+        };
+        let error = compiler.compile(&rule).err().unwrap();
+        assert_eq!(error.path, "target");
+        assert_eq!(error.expected, Some(GameType::Character.ty()));
+        assert_eq!(error.found, Some(GameType::TeamSide.ty()));
+        assert_eq!(error.to_string(), "target: Type mismatch: expected Character, found TeamSide");
+
+        // Eqの左右は同じ型
+        let rule = StructuredTokenInput::Check {
+            condition: Box::new(StructuredTokenInput::Eq {
+                left: number(1),
+                right: Box::new(StructuredTokenInput::Hero),
+            }),
+            then_action: Box::new(StructuredTokenInput::Heal {
+                target: Box::new(StructuredTokenInput::ActingCharacter),
+            }),
+        };
+        assert!(compiler.compile(&rule).is_err());
+
+        // 大小比較は数値のみ。TeamSide は NotEq で比較する
+        let rule = StructuredTokenInput::Check {
+            condition: Box::new(StructuredTokenInput::GreaterOrEqual {
+                left: Box::new(StructuredTokenInput::Enemy),
+                right: Box::new(StructuredTokenInput::Hero),
+            }),
+            then_action: Box::new(StructuredTokenInput::Heal {
+                target: Box::new(StructuredTokenInput::ActingCharacter),
+            }),
+        };
+        assert_eq!(compiler.compile(&rule).err().unwrap().path, "condition.left");
+        let rule = StructuredTokenInput::Check {
+            condition: Box::new(StructuredTokenInput::NotEq {
+                left: Box::new(StructuredTokenInput::Enemy),
+                right: Box::new(StructuredTokenInput::Hero),
+            }),
+            then_action: Box::new(StructuredTokenInput::Heal {
+                target: Box::new(StructuredTokenInput::ActingCharacter),
+            }),
+        };
+        assert!(compiler.compile(&rule).is_ok());
+
+        // ElementはFilterListの外では使えない
+        let rule = StructuredTokenInput::Strike {
+            target: Box::new(StructuredTokenInput::Element),
+        };
+        assert!(compiler.compile(&rule).is_err());
+
+        // ルートはActionでなければならない
+        assert!(compiler.compile(&StructuredTokenInput::ActingCharacter).is_err());
+    }
+
+    #[test]
+    fn test_compile_rules_reports_path_of_failing_token() {
+        let compiler = Compiler::new();
+        let rules = vec![
+            StructuredTokenInput::Heal {
+                target: Box::new(StructuredTokenInput::ActingCharacter),
+            },
+            StructuredTokenInput::Check {
+                condition: Box::new(StructuredTokenInput::TrueOrFalseRandom),
+                then_action: Box::new(StructuredTokenInput::Strike {
+                    target: Box::new(StructuredTokenInput::RandomPick {
+                        array: Box::new(StructuredTokenInput::ActingCharacter),
+                    }),
+                }),
+            },
+        ];
+
+        let errors = compiler.compile_rules(&rules).err().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "rules[1].then_action.target.array");
+        assert_eq!(errors[0].found, Some(GameType::Character.ty()));
+        assert!(errors[0].expected.as_ref().unwrap().to_string().starts_with("Array<"));
+
+        assert_eq!(compiler.compile_rules(&rules[..1]).map(|nodes| nodes.len()).ok(), Some(1));
+    }
+
+    #[test]
+    fn test_compile_and_evaluate() {
+        let compiler = Compiler::new();
+        let rule = StructuredTokenInput::Check {
+            condition: Box::new(StructuredTokenInput::LessThan {
+                left: Box::new(StructuredTokenInput::Max {
+                    array: Box::new(StructuredTokenInput::TeamMembers {
+                        team_side: Box::new(StructuredTokenInput::CharacterTeam {
+                            character: Box::new(StructuredTokenInput::ActingCharacter),
+                        }),
+                    }),
+                }),
+                right: number(90),
+            }),
+            then_action: Box::new(StructuredTokenInput::Heal {
+                target: Box::new(StructuredTokenInput::RandomPick {
+                    array: Box::new(StructuredTokenInput::AllTeamSides),
+                }),
+            }),
+        };
+        // RandomPick(AllTeamSides)はTeamSideなのでHealの対象にならない
+        assert!(compiler.compile(&rule).is_err());
+
+        let rule = StructuredTokenInput::Check {
+            condition: Box::new(StructuredTokenInput::LessThan {
+                left: Box::new(StructuredTokenInput::Max {
+                    array: Box::new(StructuredTokenInput::TeamMembers {
+                        team_side: Box::new(StructuredTokenInput::CharacterTeam {
+                            character: Box::new(StructuredTokenInput::ActingCharacter),
+                        }),
+                    }),
+                }),
+                right: number(90),
+            }),
+            then_action: Box::new(StructuredTokenInput::Heal {
+                target: Box::new(StructuredTokenInput::ActingCharacter),
+            }),
+        };
+        let node = compiler.compile(&rule).unwrap();
+
+        let mut hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        hero.hp = 60;
+        let player_team = Team::new("Player".to_string(), vec![hero.clone()]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let action = node.evaluate(&mut eval_context).unwrap();
+        assert_eq!(action.get_action_name(), "Heal");
+    }
+
+    fn map(array: StructuredTokenInput, transform: StructuredTokenInput) -> StructuredTokenInput {
+        StructuredTokenInput::Map {
+            name: None,
+            array: Box::new(array),
+            transform: Box::new(transform),
+        }
+    }
+
+    fn character_to_hp_of_element() -> StructuredTokenInput {
+        StructuredTokenInput::CharacterToHp {
+            character: Box::new(StructuredTokenInput::Element),
+        }
+    }
+
+    #[test]
+    fn test_map_types_element_by_input_array() {
+        let compiler = Compiler::new();
+        let array_of = |t: GameType| GameType::array_of(t.ty());
+        let characters = || StructuredTokenInput::AllCharacters;
+
+        let cases = vec![
+            // Character → CharacterHP
+            (map(characters(), character_to_hp_of_element()), array_of(GameType::CharacterHP), GameType::Character),
+            // Character → TeamSide
+            (
+                map(characters(), StructuredTokenInput::CharacterTeam {
+                    character: Box::new(StructuredTokenInput::Element),
+                }),
+                array_of(GameType::TeamSide),
+                GameType::Character,
+            ),
+            // CharacterHP → Character
+            (
+                map(map(characters(), character_to_hp_of_element()), StructuredTokenInput::CharacterHpToCharacter {
+                    character_hp: Box::new(StructuredTokenInput::Element),
+                }),
+                array_of(GameType::Character),
+                GameType::CharacterHP,
+            ),
+            // I32 → I32
+            (
+                map(map(characters(), StructuredTokenInput::Number { value: 5 }), StructuredTokenInput::Element),
+                array_of(GameType::I32),
+                GameType::I32,
+            ),
+        ];
+
+        for (token, expected, element) in cases {
+            let typed = compiler.infer_as(&token, &expected).unwrap();
+            assert_eq!(typed.node_type, expected);
+            let transform = &typed.arguments[1];
+            let element_node = std::iter::successors(Some(transform), |t| t.arguments.first())
+                .find(|t| t.token_name == "Element")
+                .unwrap();
+            assert_eq!(element_node.node_type, element.ty());
+            assert!(compiler.generate(&typed).is_ok());
+        }
+
+        // 変換先の要素に Element を使うと入力と異なる型は受け付けない
+        let token = map(characters(), StructuredTokenInput::CharacterHpToCharacter {
+            character_hp: Box::new(StructuredTokenInput::Element),
+        });
+        let error = compiler.infer_as(&token, &array_of(GameType::Character)).unwrap_err();
+        assert_eq!(error.path, "transform.character_hp");
+    }
+
+    #[test]
+    fn test_max_of_mapped_hp() {
+        let compiler = Compiler::new();
+        let token = StructuredTokenInput::Max {
+            array: Box::new(map(
+                StructuredTokenInput::TeamMembers {
+                    team_side: Box::new(StructuredTokenInput::Enemy),
+                },
+                character_to_hp_of_element(),
+            )),
+        };
+        let typed = compiler.infer_as(&token, &GameType::CharacterHP.ty()).unwrap();
+        let node = compiler.generate(&typed).unwrap().into_node::<action_system::CharacterHP>().unwrap();
+
+        let hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        let mut slime = Character::new(2, "Slime".to_string(), 100, 0, 5);
+        slime.hp = 30;
+        let mut goblin = Character::new(3, "Goblin".to_string(), 100, 0, 5);
+        goblin.hp = 70;
+        let player_team = Team::new("Player".to_string(), vec![hero.clone()]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![slime, goblin]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let max_hp = node.evaluate(&mut eval_context).unwrap();
+        assert_eq!(max_hp.get_hp(), 70);
+        assert_eq!(max_hp.get_character().id, 3);
+    }
+
+    #[test]
+    fn test_max_of_mapped_mp_returns_owner() {
+        let compiler = Compiler::new();
+        let token = StructuredTokenInput::CharacterMpToCharacter {
+            character_mp: Box::new(StructuredTokenInput::Max {
+                array: Box::new(map(
+                    StructuredTokenInput::AllCharacters,
+                    StructuredTokenInput::CharacterToMp {
+                        character: Box::new(StructuredTokenInput::Element),
+                    },
+                )),
+            }),
+        };
+        let typed = compiler.infer_as(&token, &GameType::Character.ty()).unwrap();
+        assert_eq!(typed.arguments[0].node_type, GameType::CharacterMP.ty());
+        let node = compiler.generate(&typed).unwrap().into_node::<Character>().unwrap();
+
+        let mut hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        hero.mp = 5;
+        let mut mage = Character::new(2, "Mage".to_string(), 80, 90, 5);
+        mage.mp = 60;
+        let slime = Character::new(3, "Slime".to_string(), 50, 10, 5);
+        let player_team = Team::new("Player".to_string(), vec![hero.clone(), mage]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![slime]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        assert_eq!(node.evaluate(&mut eval_context).unwrap().id, 2);
+    }
+
+    #[test]
+    fn test_max_by_and_min_by_over_character_hp_and_team_side_arrays() {
+        let compiler = Compiler::new();
+        let element = || Box::new(StructuredTokenInput::Element);
+        let enemies = || Box::new(StructuredTokenInput::TeamMembers {
+            team_side: Box::new(StructuredTokenInput::Enemy),
+        });
+
+        // 攻撃力が最大の敵（キャラクター配列）
+        let highest_attack = StructuredTokenInput::MaxBy {
+            name: None,
+            array: enemies(),
+            key: Box::new(StructuredTokenInput::CharacterAttack { character: element() }),
+        };
+        // HPが最小の敵のHP（CharacterHP配列。キーは要素そのもの）
+        let lowest_hp = StructuredTokenInput::MinBy {
+            name: None,
+            array: Box::new(map(*enemies(), character_to_hp_of_element())),
+            key: element(),
+        };
+        // 人数が多い陣営（TeamSide配列）
+        let larger_side = StructuredTokenInput::MaxBy {
+            name: None,
+            array: Box::new(StructuredTokenInput::AllTeamSides),
+            key: Box::new(StructuredTokenInput::Count {
+                array: Box::new(StructuredTokenInput::TeamMembers { team_side: element() }),
+            }),
+        };
+
+        let hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        let mut orc = Character::new(2, "Orc".to_string(), 100, 0, 30);
+        orc.hp = 80;
+        let mut goblin = Character::new(3, "Goblin".to_string(), 100, 0, 12);
+        goblin.hp = 25;
+        let player_team = Team::new("Player".to_string(), vec![hero.clone()]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![orc, goblin]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let typed = compiler.infer_as(&highest_attack, &GameType::Character.ty()).unwrap();
+        let node = compiler.generate(&typed).unwrap().into_node::<Character>().unwrap();
+        assert_eq!(node.evaluate(&mut eval_context).unwrap().id, 2);
+
+        let typed = compiler.infer_as(&lowest_hp, &GameType::CharacterHP.ty()).unwrap();
+        let node = compiler.generate(&typed).unwrap().into_node::<action_system::CharacterHP>().unwrap();
+        assert_eq!(node.evaluate(&mut eval_context).unwrap().get_character().id, 3);
+
+        let typed = compiler.infer_as(&larger_side, &GameType::TeamSide.ty()).unwrap();
+        let node = compiler.generate(&typed).unwrap().into_node::<TeamSide>().unwrap();
+        assert_eq!(node.evaluate(&mut eval_context).unwrap(), TeamSide::Enemy);
+
+        // キーは数値でなければならない
+        let invalid = StructuredTokenInput::MaxBy {
+            name: None,
+            array: enemies(),
+            key: Box::new(StructuredTokenInput::CharacterTeam { character: element() }),
+        };
+        let error = compiler.infer_as(&invalid, &GameType::Character.ty()).unwrap_err();
+        assert_eq!(error.path, "key");
+    }
+
+    #[test]
+    fn test_let_type_checks_variable_uses() {
+        let compiler = Compiler::new();
+        let var = |name: &str| Box::new(StructuredTokenInput::Var { name: name.to_string() });
+        let let_in = |name: &str, value: StructuredTokenInput, body: StructuredTokenInput| StructuredTokenInput::Let {
+            name: name.to_string(),
+            value: Box::new(value),
+            body: Box::new(body),
+        };
+        let random_character = || StructuredTokenInput::RandomPick {
+            array: Box::new(StructuredTokenInput::AllCharacters),
+        };
+
+        // Let target = RandomPick(AllCharacters) in Check(HP(target) > 0, Strike(target))
+        let rule = let_in("target", random_character(), StructuredTokenInput::Check {
+            condition: Box::new(StructuredTokenInput::GreaterThan {
+                left: Box::new(StructuredTokenInput::CharacterToHp { character: var("target") }),
+                right: number(0),
+            }),
+            then_action: Box::new(StructuredTokenInput::Strike { target: var("target") }),
+        });
+        let typed = compiler.infer(&rule).unwrap();
+        assert_eq!(typed.arguments[0].node_type, GameType::Character.ty());
+        assert!(compiler.compile(&rule).is_ok());
+
+        // 内側の束縛が外側を隠す
+        let shadowed = let_in("x", random_character(), let_in("x", StructuredTokenInput::Number { value: 3 }, *var("x")));
+        let typed = compiler.infer_as(&shadowed, &GameType::I32.ty()).unwrap();
+        assert_eq!(typed.node_type, GameType::I32.ty());
+
+        // 変数の型に合わない使い方
+        let rule = let_in("side", StructuredTokenInput::Enemy, StructuredTokenInput::Strike { target: var("side") });
+        let error = compiler.infer(&rule).unwrap_err();
+        assert_eq!(error.to_string(), "body.target: Type mismatch: expected Character, found TeamSide");
+
+        // スコープ外の変数
+        let rule = StructuredTokenInput::Heal {
+            target: Box::new(let_in("target", random_character(), *var("target"))),
+        };
+        assert!(compiler.infer(&rule).is_ok());
+        let rule = let_in("target", random_character(), StructuredTokenInput::Heal { target: var("other") });
+        let error = compiler.infer(&rule).unwrap_err();
+        assert_eq!(error.to_string(), "body.target: Var is used outside of a scope that binds other");
+    }
+
+    #[test]
+    fn test_nested_filter_with_named_parameters() {
+        let compiler = Compiler::new();
+        let var = |name: &str| Box::new(StructuredTokenInput::Var { name: name.to_string() });
+        let members = |side: StructuredTokenInput| Box::new(StructuredTokenInput::TeamMembers { team_side: Box::new(side) });
+
+        // 味方の誰かよりHPが低い敵: FilterList(enemy: 敵, Any(ally: 味方, HP(enemy) < HP(ally)))
+        let token = StructuredTokenInput::FilterList {
+            name: Some("enemy".to_string()),
+            array: members(StructuredTokenInput::Enemy),
+            condition: Box::new(StructuredTokenInput::Any {
+                name: Some("ally".to_string()),
+                array: members(StructuredTokenInput::Hero),
+                condition: Box::new(StructuredTokenInput::LessThan {
+                    left: Box::new(StructuredTokenInput::CharacterToHp { character: var("enemy") }),
+                    // Element は最も内側の配列操作（ally）を指す
+                    right: Box::new(character_to_hp_of_element()),
+                }),
+            }),
+        };
+        let typed = compiler.infer_as(&token, &GameType::array_of(GameType::Character.ty())).unwrap();
+        let less_than = &typed.arguments[1].arguments[1];
+        assert_eq!(less_than.arguments[0].arguments[0].binding_depth, Some(1));
+        assert_eq!(less_than.arguments[1].arguments[0].binding_depth, Some(0));
+        let node = compiler.generate(&typed).unwrap().into_node::<Vec<Character>>().unwrap();
+
+        let mut hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        hero.hp = 60;
+        let mut mage = Character::new(2, "Mage".to_string(), 100, 50, 10);
+        mage.hp = 30;
+        let mut orc = Character::new(3, "Orc".to_string(), 100, 0, 10);
+        orc.hp = 50;
+        let mut goblin = Character::new(4, "Goblin".to_string(), 100, 0, 10);
+        goblin.hp = 70;
+        let player_team = Team::new("Player".to_string(), vec![hero.clone(), mage]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![orc, goblin]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let ids: Vec<_> = node.evaluate(&mut eval_context).unwrap().into_iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![3]);
+
+        // 名前はその配列操作の内側でのみ参照できる
+        let token = StructuredTokenInput::FilterList {
+            name: Some("enemy".to_string()),
+            array: members(StructuredTokenInput::Enemy),
+            condition: Box::new(StructuredTokenInput::GreaterThan {
+                left: Box::new(StructuredTokenInput::CharacterToHp { character: var("ally") }),
+                right: number(0),
+            }),
+        };
+        let error = compiler.infer_as(&token, &GameType::array_of(GameType::Character.ty())).unwrap_err();
+        assert_eq!(error.to_string(), "condition.left.character: Var is used outside of a scope that binds ally");
+    }
+
+    #[test]
+    fn test_if_branches_must_share_a_type() {
+        let compiler = Compiler::new();
+        let acting_hp_below = |value: i32| Box::new(StructuredTokenInput::LessThan {
+            left: Box::new(StructuredTokenInput::CharacterToHp { character: Box::new(StructuredTokenInput::ActingCharacter) }),
+            right: number(value),
+        });
+        let members = |side: StructuredTokenInput| Box::new(StructuredTokenInput::TeamMembers { team_side: Box::new(side) });
+
+        // target = If(HP < 30, ActingCharacter, RandomPick(味方))
+        let target = |threshold: i32| StructuredTokenInput::If {
+            condition: acting_hp_below(threshold),
+            then: Box::new(StructuredTokenInput::ActingCharacter),
+            otherwise: Box::new(StructuredTokenInput::RandomPick { array: members(StructuredTokenInput::Hero) }),
+        };
+        assert!(compiler.compile(&StructuredTokenInput::Strike { target: Box::new(target(30)) }).is_ok());
+
+        let mut hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        hero.hp = 20;
+        let mage = Character::new(2, "Mage".to_string(), 100, 50, 10);
+        let player_team = Team::new("Player".to_string(), vec![hero.clone(), mage]);
+        let enemy_team = Team::new("Enemy".to_string(), vec![Character::new(3, "Orc".to_string(), 100, 0, 10)]);
+        let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+
+        let build = |token: &StructuredTokenInput, ty: Type<GameType>| {
+            compiler.generate(&compiler.infer_as(token, &ty).unwrap()).unwrap()
+        };
+        let node = build(&target(30), GameType::Character.ty()).into_node::<Character>().unwrap();
+        assert_eq!(node.evaluate(&mut eval_context).unwrap().id, 1);
+        let node = build(&target(10), GameType::Character.ty()).into_node::<Character>().unwrap();
+        assert!([1, 2].contains(&node.evaluate(&mut eval_context).unwrap().id));
+
+        // 配列も分岐できる
+        let token = StructuredTokenInput::If {
+            condition: acting_hp_below(30),
+            then: members(StructuredTokenInput::Enemy),
+            otherwise: members(StructuredTokenInput::Hero),
+        };
+        let node = build(&token, GameType::array_of(GameType::Character.ty())).into_node::<Vec<Character>>().unwrap();
+        assert_eq!(node.evaluate(&mut eval_context).unwrap()[0].id, 3);
+
+        // 分岐の型が異なるとエラー
+        let token = StructuredTokenInput::If {
+            condition: acting_hp_below(30),
+            then: Box::new(StructuredTokenInput::ActingCharacter),
+            otherwise: number(1),
+        };
+        let error = compiler.infer_as(&token, &GameType::Character.ty()).unwrap_err();
+        assert_eq!(error.to_string(), "else: Type mismatch: expected Character, found I32");
+
+        // 数値どうしでも型は揃える必要がある（I32 と CharacterHP は別の型）
+        let token = StructuredTokenInput::GreaterThan {
+            left: Box::new(StructuredTokenInput::If {
+                condition: acting_hp_below(30),
+                then: number(1),
+                otherwise: Box::new(StructuredTokenInput::CharacterToHp { character: Box::new(StructuredTokenInput::ActingCharacter) }),
+            }),
+            right: number(0),
+        };
+        let error = compiler.infer_as(&token, &GameType::Bool.ty()).unwrap_err();
+        assert_eq!(error.to_string(), "left.else: Type mismatch: expected I32, found CharacterHP");
+    }
+
+    #[test]
+    fn test_first_of_tries_alternatives_in_order() {
+        let compiler = Compiler::new();
+        let acting = || Box::new(StructuredTokenInput::ActingCharacter);
+        let enemies = || Box::new(StructuredTokenInput::TeamMembers { team_side: Box::new(StructuredTokenInput::Enemy) });
+        let hp_below = |character: Box<StructuredTokenInput>, value: i32| Box::new(StructuredTokenInput::LessThan {
+            left: Box::new(StructuredTokenInput::CharacterToHp { character }),
+            right: number(value),
+        });
+
+        // FirstOf([Check(HP(自分) < 50, Heal(自分)), Strike(First(敵))])
+        let rule = StructuredTokenInput::FirstOf {
+            alternatives: vec![
+                StructuredTokenInput::Check {
+                    condition: hp_below(acting(), 50),
+                    then_action: Box::new(StructuredTokenInput::Heal { target: acting() }),
+                },
+                StructuredTokenInput::Strike { target: Box::new(StructuredTokenInput::First { array: enemies() }) },
+            ],
+        };
+        // 対象: HP30未満の敵がいればその先頭、いなければ敵の末尾
+        let target = StructuredTokenInput::FirstOf {
+            alternatives: vec![
+                StructuredTokenInput::First {
+                    array: Box::new(StructuredTokenInput::FilterList {
+                        name: None,
+                        array: enemies(),
+                        condition: hp_below(Box::new(StructuredTokenInput::Element), 30),
+                    }),
+                },
+                StructuredTokenInput::Last { array: enemies() },
+            ],
+        };
+        let target_node = compiler
+            .generate(&compiler.infer_as(&target, &GameType::Character.ty()).unwrap())
+            .unwrap()
+            .into_node::<Character>()
+            .unwrap();
+        let rule_node = compiler.compile(&rule).unwrap();
+
+        let mut hero = Character::new(1, "Hero".to_string(), 100, 50, 10);
+        let mut orc = Character::new(2, "Orc".to_string(), 100, 0, 10);
+        let goblin = Character::new(3, "Goblin".to_string(), 100, 0, 10);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        for (hero_hp, orc_hp, action, target_id) in [(100, 100, "Strike", 3), (40, 20, "Heal", 2)] {
+            hero.hp = hero_hp;
+            orc.hp = orc_hp;
+            let player_team = Team::new("Player".to_string(), vec![hero.clone()]);
+            let enemy_team = Team::new("Enemy".to_string(), vec![orc.clone(), goblin.clone()]);
+            let battle_context = BattleContext::new(&hero, TeamSide::Player, &player_team, &enemy_team);
+            let mut eval_context = EvaluationContext::new(&battle_context, &mut rng);
+            assert_eq!(rule_node.evaluate(&mut eval_context).unwrap().get_action_name(), action);
+            assert_eq!(target_node.evaluate(&mut eval_context).unwrap().id, target_id);
+        }
+
+        // 候補は全て同じ型でなければならない
+        let token = StructuredTokenInput::FirstOf {
+            alternatives: vec![StructuredTokenInput::ActingCharacter, StructuredTokenInput::Number { value: 1 }],
+        };
+        let error = compiler.infer_as(&token, &GameType::Character.ty()).unwrap_err();
+        assert_eq!(error.to_string(), "alternatives[1]: Type mismatch: expected Character, found I32");
+    }
+}
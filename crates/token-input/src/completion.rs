@@ -0,0 +1,203 @@
+// 入力途中の平坦トークン列の補完 - 次の穴の型と、そこに置けるトークン
+
+use crate::compile_error::CompileError;
+use crate::compiler::Compiler;
+use crate::path;
+use crate::flat_to_structured::surplus_error;
+use crate::game_type::GameType;
+use crate::token_registry::registry;
+use crate::type_system::{Type, TypeHole, TypeInferable};
+use crate::FlatTokenInput;
+
+/// 入力途中の行の状態
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowCompletion {
+    /// 次に埋める穴のパス（ルートは空文字）。行が完成していれば None
+    pub hole: Option<String>,
+    /// 次の穴に要求される型。まだ決まっていなければ None
+    pub expected_type: Option<Type<GameType>>,
+    /// 候補のうち次の穴に置けるトークン
+    pub valid_tokens: Vec<FlatTokenInput>,
+}
+
+impl RowCompletion {
+    /// 穴が残っていないか
+    pub fn is_complete(&self) -> bool {
+        self.hole.is_none()
+    }
+
+    pub fn accepts(&self, token: &FlatTokenInput) -> bool {
+        self.valid_tokens.contains(token)
+    }
+}
+
+// 行の続きを調べる。`candidates` はそれぞれ次の穴に置いてみて型が通るものだけ残す
+// 既に型が合わない行・余りのある行はエラー
+pub fn complete_flat_row(
+    flat_tokens: &[FlatTokenInput],
+    candidates: &[FlatTokenInput],
+) -> Result<RowCompletion, CompileError> {
+    let compiler = Compiler::shared();
+    let holes = infer_row(compiler, flat_tokens)?;
+    let Some(hole) = holes.into_iter().next() else {
+        return Ok(RowCompletion { hole: None, expected_type: None, valid_tokens: Vec::new() });
+    };
+
+    let mut row = flat_tokens.to_vec();
+    let mut valid_tokens = Vec::new();
+    for candidate in candidates {
+        row.push(candidate.clone());
+        if infer_row(compiler, &row).is_ok() {
+            valid_tokens.push(candidate.clone());
+        }
+        row.pop();
+    }
+
+    Ok(RowCompletion {
+        hole: Some(path::from_segments(&hole.path)),
+        expected_type: hole.expected.is_resolved().then_some(hole.expected),
+        valid_tokens,
+    })
+}
+
+fn infer_row(compiler: &Compiler, flat_tokens: &[FlatTokenInput]) -> Result<Vec<TypeHole<GameType>>, CompileError> {
+    let mut index = 0;
+    let tree = parse_partial(flat_tokens, &mut index)?;
+    if index < flat_tokens.len() {
+        return Err(surplus_error(flat_tokens, index));
+    }
+    compiler.infer_holes(&tree)
+}
+
+/// 足りない引数を穴で埋めた前置記法の木
+enum PartialToken<'a> {
+    Token {
+        token: &'a FlatTokenInput,
+        name: &'static str,
+        arguments: Vec<(&'static str, PartialToken<'a>)>,
+    },
+    Hole,
+}
+
+fn parse_partial<'a>(tokens: &'a [FlatTokenInput], index: &mut usize) -> Result<PartialToken<'a>, CompileError> {
+    let Some(token) = tokens.get(*index) else {
+        return Ok(PartialToken::Hole);
+    };
+    let definition = registry()
+        .find_flat(token)
+        .ok_or_else(|| CompileError::new("", format!("Unknown token: {:?}", token)))?;
+    *index += 1;
+
+    let mut arguments = Vec::new();
+    for slot in definition.flat_slots(token) {
+        arguments.push((slot, parse_partial(tokens, index)?));
+    }
+    Ok(PartialToken::Token { token, name: definition.name(), arguments })
+}
+
+impl TypeInferable for PartialToken<'_> {
+    type Value = i32;
+
+    fn token_name(&self) -> &str {
+        match self {
+            PartialToken::Token { name, .. } => name,
+            PartialToken::Hole => "",
+        }
+    }
+
+    fn arguments(&self) -> Vec<(&'static str, &Self)> {
+        match self {
+            PartialToken::Token { arguments, .. } => arguments.iter().map(|(slot, argument)| (*slot, argument)).collect(),
+            PartialToken::Hole => Vec::new(),
+        }
+    }
+
+    fn value(&self) -> Option<i32> {
+        match self {
+            // 範囲外の数は変換時にエラーになるので、ここでは値なしとして型だけを見る
+            PartialToken::Token { token, .. } => token.value().ok().flatten(),
+            PartialToken::Hole => None,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        match self {
+            PartialToken::Token { token: FlatTokenInput::Let(name) | FlatTokenInput::Var(name), .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    fn is_hole(&self) -> bool {
+        matches!(self, PartialToken::Hole)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complete(flat_tokens: &[FlatTokenInput]) -> Result<RowCompletion, CompileError> {
+        complete_flat_row(flat_tokens, &registry().flat_tokens())
+    }
+
+    #[test]
+    fn test_empty_row_expects_an_action() {
+        let completion = complete(&[]).unwrap();
+        assert_eq!(completion.hole.as_deref(), Some(""));
+        assert_eq!(completion.expected_type, Some(GameType::Action.ty()));
+        assert!(!completion.is_complete());
+        for token in [FlatTokenInput::Strike, FlatTokenInput::Heal, FlatTokenInput::Check, FlatTokenInput::FirstOf(2)] {
+            assert!(completion.accepts(&token), "{:?} should start a rule", token);
+        }
+        for token in [FlatTokenInput::Number(50), FlatTokenInput::AllCharacters, FlatTokenInput::Element] {
+            assert!(!completion.accepts(&token), "{:?} should not start a rule", token);
+        }
+    }
+
+    #[test]
+    fn test_hole_type_follows_the_open_slot() {
+        let completion = complete(&[FlatTokenInput::Strike]).unwrap();
+        assert_eq!(completion.hole.as_deref(), Some("target"));
+        assert_eq!(completion.expected_type, Some(GameType::Character.ty()));
+        assert!(completion.accepts(&FlatTokenInput::ActingCharacter));
+        assert!(completion.accepts(&FlatTokenInput::RandomPick));
+        assert!(!completion.accepts(&FlatTokenInput::Enemy));
+
+        // FilterList の条件の中では Element が使える
+        let completion = complete(&[
+            FlatTokenInput::Strike,
+            FlatTokenInput::RandomPick,
+            FlatTokenInput::FilterList,
+            FlatTokenInput::AllCharacters,
+            FlatTokenInput::Eq,
+        ])
+        .unwrap();
+        assert_eq!(completion.hole.as_deref(), Some("target.array.condition.left"));
+        assert_eq!(completion.expected_type, None);
+        assert!(completion.accepts(&FlatTokenInput::Element));
+        assert!(completion.accepts(&FlatTokenInput::Enemy));
+
+        // Let x の本体では Var x だけが参照できる
+        let completion = complete(&[FlatTokenInput::Let("x".to_string()), FlatTokenInput::ActingCharacter]).unwrap();
+        assert_eq!(completion.hole.as_deref(), Some("body"));
+        let completion = complete_flat_row(
+            &[FlatTokenInput::Let("x".to_string()), FlatTokenInput::ActingCharacter, FlatTokenInput::Strike],
+            &[FlatTokenInput::Var("x".to_string()), FlatTokenInput::Var("y".to_string())],
+        )
+        .unwrap();
+        assert_eq!(completion.valid_tokens, vec![FlatTokenInput::Var("x".to_string())]);
+    }
+
+    #[test]
+    fn test_complete_and_invalid_rows() {
+        let completion = complete(&[FlatTokenInput::Strike, FlatTokenInput::ActingCharacter]).unwrap();
+        assert!(completion.is_complete());
+        assert!(completion.valid_tokens.is_empty());
+
+        let error = complete(&[FlatTokenInput::Strike, FlatTokenInput::Enemy]).unwrap_err();
+        assert_eq!(error.to_string(), "target: Type mismatch: expected Character, found TeamSide");
+
+        let error = complete(&[FlatTokenInput::Strike, FlatTokenInput::ActingCharacter, FlatTokenInput::Heal]).unwrap_err();
+        assert_eq!(error.to_string(), "Unexpected token Heal at index 2: the rule already ended");
+    }
+}
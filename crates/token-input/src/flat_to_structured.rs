@@ -1,6 +1,9 @@
 // FlatTokenInput → StructuredTokenInput 変換
 
+use crate::compile_error::CompileError;
+use crate::token_registry::registry;
 use crate::{FlatTokenInput, StructuredTokenInput};
+use std::ops::Range;
 
 // FlatTokenInput → StructuredTokenInput 変換
 pub fn convert_flat_to_structured(flat_tokens: &[FlatTokenInput]) -> Result<Vec<StructuredTokenInput>, String> {
@@ -16,151 +19,52 @@ pub fn convert_flat_to_structured(flat_tokens: &[FlatTokenInput]) -> Result<Vec<
     Ok(result)
 }
 
-fn parse_flat_token(tokens: &[FlatTokenInput], index: usize) -> Result<(StructuredTokenInput, usize), String> {
-    if index >= tokens.len() {
-        return Err("No tokens to parse".to_string());
+// 1行分の平坦トークンを1つのルールとして読む
+// 式の後に残ったトークンは捨てずに、その位置を付けたエラーにする
+pub fn convert_flat_rule(flat_tokens: &[FlatTokenInput]) -> Result<StructuredTokenInput, CompileError> {
+    let (rule, consumed) = parse_flat_token(flat_tokens, 0).map_err(|message| CompileError::new("", message))?;
+    if consumed < flat_tokens.len() {
+        return Err(surplus_error(flat_tokens, consumed));
     }
-    
-    match &tokens[index] {
-        FlatTokenInput::Strike => {
-            if index + 1 >= tokens.len() {
-                return Err("Strike requires a target".to_string());
-            }
-            let (target, consumed) = parse_flat_token(tokens, index + 1)?;
-            Ok((StructuredTokenInput::Strike { target: Box::new(target) }, 1 + consumed))
-        }
-        FlatTokenInput::Heal => {
-            if index + 1 >= tokens.len() {
-                return Err("Heal requires a target".to_string());
-            }
-            let (target, consumed) = parse_flat_token(tokens, index + 1)?;
-            Ok((StructuredTokenInput::Heal { target: Box::new(target) }, 1 + consumed))
-        }
-        FlatTokenInput::Check => {
-            if index + 2 >= tokens.len() {
-                return Err("Check requires a condition and action".to_string());
-            }
-            let (condition, cond_consumed) = parse_flat_token(tokens, index + 1)?;
-            let (action, action_consumed) = parse_flat_token(tokens, index + 1 + cond_consumed)?;
-            Ok((StructuredTokenInput::Check { 
-                condition: Box::new(condition), 
-                then_action: Box::new(action) 
-            }, 1 + cond_consumed + action_consumed))
-        }
-        FlatTokenInput::GreaterThan => {
-            if index + 2 >= tokens.len() {
-                return Err("GreaterThan requires two operands".to_string());
-            }
-            let (left, left_consumed) = parse_flat_token(tokens, index + 1)?;
-            let (right, right_consumed) = parse_flat_token(tokens, index + 1 + left_consumed)?;
-            Ok((StructuredTokenInput::GreaterThan { 
-                left: Box::new(left), 
-                right: Box::new(right) 
-            }, 1 + left_consumed + right_consumed))
-        }
-        FlatTokenInput::LessThan => {
-            if index + 2 >= tokens.len() {
-                return Err("LessThan requires two operands".to_string());
-            }
-            let (left, left_consumed) = parse_flat_token(tokens, index + 1)?;
-            let (right, right_consumed) = parse_flat_token(tokens, index + 1 + left_consumed)?;
-            Ok((StructuredTokenInput::LessThan { 
-                left: Box::new(left), 
-                right: Box::new(right) 
-            }, 1 + left_consumed + right_consumed))
-        }
-        FlatTokenInput::CharacterToHp => {
-            if index + 1 >= tokens.len() {
-                return Err("HP requires a character".to_string());
-            }
-            let (character, consumed) = parse_flat_token(tokens, index + 1)?;
-            Ok((StructuredTokenInput::CharacterToHp { character: Box::new(character) }, 1 + consumed))
-        }
-        FlatTokenInput::CharacterHpToCharacter => {
-            if index + 1 >= tokens.len() {
-                return Err("HpCharacter requires a character HP".to_string());
-            }
-            let (character_hp, consumed) = parse_flat_token(tokens, index + 1)?;
-            Ok((StructuredTokenInput::CharacterHpToCharacter { character_hp: Box::new(character_hp) }, 1 + consumed))
-        }
-        FlatTokenInput::Number(n) => Ok((StructuredTokenInput::Number { value: *n as i32 }, 1)),
-        FlatTokenInput::ActingCharacter => Ok((StructuredTokenInput::ActingCharacter, 1)),
-        FlatTokenInput::AllCharacters => Ok((StructuredTokenInput::AllCharacters, 1)),
-        FlatTokenInput::TeamMembers => {
-            if index + 1 >= tokens.len() {
-                return Err("TeamMembers requires a team side".to_string());
-            }
-            let (team_side, consumed) = parse_flat_token(tokens, index + 1)?;
-            Ok((StructuredTokenInput::TeamMembers { team_side: Box::new(team_side) }, 1 + consumed))
-        }
-        FlatTokenInput::RandomPick => {
-            if index + 1 >= tokens.len() {
-                return Err("RandomPick requires an array argument".to_string());
-            }
-            let (array_token, array_consumed) = parse_flat_token(tokens, index + 1)?;
-            Ok((StructuredTokenInput::RandomPick { array: Box::new(array_token) }, 1 + array_consumed))
-        }
-        FlatTokenInput::TrueOrFalse => Ok((StructuredTokenInput::TrueOrFalseRandom, 1)),
-        FlatTokenInput::FilterList => {
-            if index + 2 >= tokens.len() {
-                return Err("FilterList requires an array and a condition".to_string());
-            }
-            let (array, array_consumed) = parse_flat_token(tokens, index + 1)?;
-            let (condition, condition_consumed) = parse_flat_token(tokens, index + 1 + array_consumed)?;
-            Ok((StructuredTokenInput::FilterList {
-                array: Box::new(array),
-                condition: Box::new(condition),
-            }, 1 + array_consumed + condition_consumed))
-        }
-        FlatTokenInput::Map => {
-            if index + 2 >= tokens.len() {
-                return Err("Map requires an array and a transform function".to_string());
-            }
-            let (array, array_consumed) = parse_flat_token(tokens, index + 1)?;
-            let (transform, transform_consumed) = parse_flat_token(tokens, index + 1 + array_consumed)?;
-            Ok((StructuredTokenInput::Map {
-                array: Box::new(array),
-                transform: Box::new(transform),
-            }, 1 + array_consumed + transform_consumed))
-        }
-        FlatTokenInput::Eq => {
-            if index + 2 >= tokens.len() {
-                return Err("Eq requires two operands".to_string());
-            }
-            let (left, left_consumed) = parse_flat_token(tokens, index + 1)?;
-            let (right, right_consumed) = parse_flat_token(tokens, index + 1 + left_consumed)?;
-            Ok((StructuredTokenInput::Eq {
-                left: Box::new(left),
-                right: Box::new(right),
-            }, 1 + left_consumed + right_consumed))
-        }
-        FlatTokenInput::CharacterTeam => {
-            if index + 1 >= tokens.len() {
-                return Err("CharacterTeam requires a character".to_string());
-            }
-            let (character, consumed) = parse_flat_token(tokens, index + 1)?;
-            Ok((StructuredTokenInput::CharacterTeam {
-                character: Box::new(character),
-            }, 1 + consumed))
-        }
-        FlatTokenInput::Element => Ok((StructuredTokenInput::Element, 1)),
-        FlatTokenInput::Enemy => Ok((StructuredTokenInput::Enemy, 1)),
-        FlatTokenInput::Hero => Ok((StructuredTokenInput::Hero, 1)),
-        FlatTokenInput::Max => {
-            if index + 1 >= tokens.len() {
-                return Err("Max requires an array argument".to_string());
-            }
-            let (array_token, array_consumed) = parse_flat_token(tokens, index + 1)?;
-            Ok((StructuredTokenInput::Max { array: Box::new(array_token) }, 1 + array_consumed))
-        }
-        FlatTokenInput::Min => {
-            if index + 1 >= tokens.len() {
-                return Err("Min requires an array argument".to_string());
-            }
-            let (array_token, array_consumed) = parse_flat_token(tokens, index + 1)?;
-            Ok((StructuredTokenInput::Min { array: Box::new(array_token) }, 1 + array_consumed))
+    Ok(rule)
+}
+
+pub(crate) fn surplus_error(flat_tokens: &[FlatTokenInput], index: usize) -> CompileError {
+    let message = format!(
+        "Unexpected token {} at index {}: the rule already ended",
+        flat_tokens[index].display_text(),
+        index
+    );
+    CompileError::new("", message)
+}
+
+// 1つ目の式の後に残ったトークンの範囲（UIで印を付ける）
+pub fn surplus_tokens(flat_tokens: &[FlatTokenInput]) -> Option<Range<usize>> {
+    let (_, consumed) = parse_flat_token(flat_tokens, 0).ok()?;
+    (consumed < flat_tokens.len()).then_some(consumed..flat_tokens.len())
+}
+
+// 前置記法：レジストリに宣言された引数スロットの数だけ後続トークンを読む
+fn parse_flat_token(tokens: &[FlatTokenInput], index: usize) -> Result<(StructuredTokenInput, usize), String> {
+    let token = tokens.get(index).ok_or_else(|| "No tokens to parse".to_string())?;
+    let definition = registry()
+        .find_flat(token)
+        .ok_or_else(|| format!("Unknown token: {:?}", token))?;
+
+    let slots = definition.flat_slots(token);
+    let mut arguments = Vec::with_capacity(slots.len());
+    let mut consumed = 1;
+    for slot in slots {
+        if index + consumed >= tokens.len() {
+            return Err(format!("{} requires {}", definition.name(), slot));
         }
+        let (argument, argument_consumed) = parse_flat_token(tokens, index + consumed)?;
+        arguments.push(argument);
+        consumed += argument_consumed;
     }
+
+    let structured = StructuredTokenInput::from_parts(definition.name(), arguments, token.value()?, token.variable_name())?;
+    Ok((structured, consumed))
 }
 
 #[cfg(test)]
@@ -249,7 +153,7 @@ mod tests {
         
         assert_eq!(structured.len(), 1);
         match &structured[0] {
-            StructuredTokenInput::FilterList { array, condition } => {
+            StructuredTokenInput::FilterList { name: None, array, condition } => {
                 match array.as_ref() {
                     StructuredTokenInput::AllCharacters => (),
                     _ => panic!("Expected AllCharacters array"),
@@ -365,4 +269,56 @@ mod tests {
         }
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_boolean_combinators_flat_to_structured() {
+        // Not(And(50/50, Or(50/50, Eq(Enemy, Hero))))
+        let flat = vec![
+            FlatTokenInput::Not,
+            FlatTokenInput::And,
+            FlatTokenInput::TrueOrFalse,
+            FlatTokenInput::Or,
+            FlatTokenInput::TrueOrFalse,
+            FlatTokenInput::Eq,
+            FlatTokenInput::Enemy,
+            FlatTokenInput::Hero,
+        ];
+        let structured = convert_flat_to_structured(&flat).unwrap();
+
+        assert_eq!(structured.len(), 1);
+        let StructuredTokenInput::Not { condition } = &structured[0] else {
+            panic!("Expected Not");
+        };
+        let StructuredTokenInput::And { left, right } = condition.as_ref() else {
+            panic!("Expected And");
+        };
+        assert!(matches!(left.as_ref(), StructuredTokenInput::TrueOrFalseRandom));
+        let StructuredTokenInput::Or { left, right } = right.as_ref() else {
+            panic!("Expected Or");
+        };
+        assert!(matches!(left.as_ref(), StructuredTokenInput::TrueOrFalseRandom));
+        assert!(matches!(right.as_ref(), StructuredTokenInput::Eq { .. }));
+    }
+
+    #[test]
+    fn test_surplus_tokens_are_reported() {
+        // Strike(ActingCharacter) の後に続く Heal ActingCharacter は捨てずにエラー
+        let flat = vec![
+            FlatTokenInput::Strike,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::Heal,
+            FlatTokenInput::ActingCharacter,
+        ];
+        let error = convert_flat_rule(&flat).unwrap_err();
+        assert_eq!(error.message, "Unexpected token Heal at index 2: the rule already ended");
+        assert_eq!(surplus_tokens(&flat), Some(2..4));
+
+        let rule = convert_flat_rule(&flat[..2]).unwrap();
+        assert!(matches!(rule, StructuredTokenInput::Strike { .. }));
+        assert_eq!(surplus_tokens(&flat[..2]), None);
+
+        // 不完全な行は従来どおりのエラーで、余りのトークンはない
+        let error = convert_flat_rule(&flat[..1]).unwrap_err();
+        assert_eq!(error.message, "Strike requires target");
+        assert_eq!(surplus_tokens(&flat[..1]), None);
+    }
+}
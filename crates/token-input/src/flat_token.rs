@@ -1,6 +1,10 @@
 // FlatTokenInput - UI入力用の平坦なトークン定義（ui-coreから移行）
 
-#[derive(Clone, Debug, PartialEq)]
+use crate::token_registry::{registry, TokenDefinition};
+use serde::{Deserialize, Serialize};
+
+// 保存形式: 引数のないトークンは "Strike"、値を持つトークンは {"Number": 50} / {"Let": "x"}
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum FlatTokenInput {
     Check,
     Strike,
@@ -12,49 +16,101 @@ pub enum FlatTokenInput {
     RandomPick,       // ランダム選択
     CharacterToHp, // CharacterからCharacterHP型の値を取得
     CharacterHpToCharacter, // CharacterHPからCharacterを取得
+    CharacterToMp, // CharacterからCharacterMP型の値を取得
+    CharacterMpToCharacter, // CharacterMPからCharacterを取得
+    CharacterAttack,  // 攻撃力
+    MaxHp,            // 最大HP
+    MaxMp,            // 最大MP
+    HpPercent,        // HP割合（0〜100）
     GreaterThan,
     LessThan,
+    GreaterOrEqual,   // 以上
+    LessOrEqual,      // 以下
     TrueOrFalse,
+    And,              // 論理積
+    Or,               // 論理和
+    Not,              // 否定
+    If,               // 値の条件分岐
+    FirstOf(usize),   // 候補を順に試す（候補の数）
+    Add,              // 加算
+    Sub,              // 減算
+    Mul,              // 乗算
+    Div,              // 除算（0除算はエラー）
+    Percent,          // 割合（base の percent%）
     FilterList,       // リストフィルタリング
     Map,              // 配列マッピング
     Eq,               // 等価比較
+    NotEq,            // 非等価比較
     CharacterTeam,    // キャラクターのチーム取得
     Element,          // 現在の要素（フィルタリング中のキャラクター）
     Enemy,            // 敵チーム定数
     Hero,             // 味方チーム定数
     Max,              // 配列の最大値
     Min,              // 配列の最小値
+    MaxBy,            // キーが最大の要素
+    MinBy,            // キーが最小の要素
+    Count,            // 配列の要素数
+    Sum,              // 配列の合計
+    Average,          // 配列の平均（切り捨て）
+    Any,              // いずれかの要素が条件を満たす
+    All,              // すべての要素が条件を満たす
+    IsEmpty,          // 配列が空か
+    SortBy,           // キーの昇順に並べ替え
+    Take,             // 先頭からN個
+    First,            // 先頭の要素
+    Last,             // 末尾の要素
+    Reverse,          // 逆順
+    Let(String),      // 変数束縛（値を1回だけ評価して名前を付ける）
+    Var(String),      // 変数参照
 }
 
-// 表示テキストは元のUITokenTypeと同じ
+// 名前・表示テキストはトークンレジストリから引く
 impl FlatTokenInput {
-    pub fn display_text(&self) -> String {
+    pub fn definition(&self) -> Option<&'static TokenDefinition> {
+        registry().find_flat(self)
+    }
+
+    /// レジストリ上のトークン名（未登録なら None）
+    pub fn token_name(&self) -> Option<&'static str> {
+        self.definition().map(TokenDefinition::name)
+    }
+
+    /// 式の値として持つ数。i32 に収まらない Number はエラー
+    pub fn value(&self) -> Result<Option<i32>, String> {
         match self {
-            FlatTokenInput::Check => "Check".to_string(),
-            FlatTokenInput::Strike => "Strike".to_string(),
-            FlatTokenInput::Heal => "Heal".to_string(),
-            FlatTokenInput::Number(n) => match n {
-                50 => "50".to_string(),
-                _ => "Num".to_string(),
-            },
-            FlatTokenInput::ActingCharacter => "ActingChar".to_string(),
-            FlatTokenInput::AllCharacters => "AllChars".to_string(),
-            FlatTokenInput::TeamMembers => "TeamMembers".to_string(),
-            FlatTokenInput::RandomPick => "RandomPick".to_string(),
-            FlatTokenInput::CharacterToHp => "CharToHp".to_string(),
-            FlatTokenInput::CharacterHpToCharacter => "CharHpToChar".to_string(),
-            FlatTokenInput::GreaterThan => "L-gt-R".to_string(),
-            FlatTokenInput::LessThan => "L-lt-R".to_string(),
-            FlatTokenInput::TrueOrFalse => "50/50".to_string(),
-            FlatTokenInput::FilterList => "FilterList".to_string(),
-            FlatTokenInput::Map => "Map".to_string(),
-            FlatTokenInput::Eq => "Eq".to_string(),
-            FlatTokenInput::CharacterTeam => "CharTeam".to_string(),
-            FlatTokenInput::Element => "Element".to_string(),
-            FlatTokenInput::Enemy => "Enemy".to_string(),
-            FlatTokenInput::Hero => "Hero".to_string(),
-            FlatTokenInput::Max => "Max".to_string(),
-            FlatTokenInput::Min => "Min".to_string(),
+            FlatTokenInput::Number(n) => i32::try_from(*n)
+                .map(Some)
+                .map_err(|_| format!("Number {} is larger than {}", n, i32::MAX)),
+            _ => Ok(None),
         }
     }
-}
\ No newline at end of file
+
+    pub fn variable_name(&self) -> Option<String> {
+        match self {
+            FlatTokenInput::Let(name) | FlatTokenInput::Var(name) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// FirstOf が後続に取る候補の数
+    pub fn alternative_count(&self) -> Option<usize> {
+        match self {
+            FlatTokenInput::FirstOf(count) => Some(*count),
+            _ => None,
+        }
+    }
+
+    pub fn display_text(&self) -> String {
+        if let (Some(count), Some(definition)) = (self.alternative_count(), self.definition()) {
+            return format!("{} {}", definition.display_text, count);
+        }
+        if let FlatTokenInput::Number(n) = self {
+            return n.to_string();
+        }
+        match (self.variable_name(), self.definition()) {
+            (Some(name), Some(definition)) => format!("{} {}", definition.display_text, name),
+            (None, Some(definition)) => definition.display_text.to_string(),
+            (_, None) => format!("{:?}", self),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+// GameType - ゲームドメインの型定義と部分型関係
+
+use crate::type_system::{Type, TypeSystem};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GameType {
+    Action,
+    Bool,
+    I32,
+    Character,
+    CharacterHP,
+    CharacterMP,
+    TeamSide,
+    /// 要素型を1つ取る型コンストラクタ
+    Array,
+    /// 数値として比較できる型の抽象型（I32, CharacterHP, CharacterMP, Character）
+    Numeric,
+    /// 配列要素・比較対象になれる値型の抽象型
+    Value,
+}
+
+impl fmt::Display for GameType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl GameType {
+    pub fn ty(self) -> Type<GameType> {
+        Type::con(self)
+    }
+
+    pub fn array_of(element: Type<GameType>) -> Type<GameType> {
+        Type::app(GameType::Array, vec![element])
+    }
+}
+
+pub struct GameTypeSystem;
+
+impl TypeSystem for GameTypeSystem {
+    type TypeId = GameType;
+
+    fn is_subtype(&self, sub: &Type<GameType>, super_: &Type<GameType>) -> bool {
+        if !sub.arguments().is_empty() {
+            return false;
+        }
+        match (sub.constructor(), super_.constructor()) {
+            (Some(sub), Some(GameType::Numeric)) => matches!(
+                sub,
+                GameType::I32 | GameType::CharacterHP | GameType::CharacterMP | GameType::Character
+            ),
+            (Some(sub), Some(GameType::Value)) => matches!(
+                sub,
+                GameType::I32
+                    | GameType::CharacterHP
+                    | GameType::CharacterMP
+                    | GameType::Character
+                    | GameType::TeamSide
+            ),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric_subtypes() {
+        let system = GameTypeSystem;
+        let numeric = GameType::Numeric.ty();
+        assert!(system.is_subtype(&GameType::I32.ty(), &numeric));
+        assert!(system.is_subtype(&GameType::CharacterHP.ty(), &numeric));
+        assert!(system.is_subtype(&GameType::CharacterMP.ty(), &numeric));
+        assert!(system.is_subtype(&GameType::Character.ty(), &numeric));
+        assert!(!system.is_subtype(&GameType::TeamSide.ty(), &numeric));
+        assert!(!system.is_subtype(&GameType::array_of(GameType::I32.ty()), &numeric));
+    }
+
+    #[test]
+    fn test_type_display() {
+        assert_eq!(GameType::array_of(GameType::Character.ty()).to_string(), "Array<Character>");
+    }
+}
@@ -1,9 +1,23 @@
 pub mod flat_token;
 pub mod structured_token;
 pub mod flat_to_structured;
+pub mod structured_to_flat;
+pub mod type_system;
+pub mod game_type;
+pub mod token_registry;
+pub mod compile_error;
+pub mod path;
+pub mod compiler;
 pub mod converter;
+pub mod text_syntax;
+pub mod completion;
 
 pub use flat_token::*;
 pub use structured_token::*;
-pub use flat_to_structured::convert_flat_to_structured;
+pub use flat_to_structured::{convert_flat_rule, convert_flat_to_structured, surplus_tokens};
+pub use structured_to_flat::convert_structured_to_flat;
 pub use converter::convert_to_rule_node;
+pub use compiler::Compiler;
+pub use compile_error::CompileError;
+pub use completion::{complete_flat_row, RowCompletion};
+pub use text_syntax::{format_rule_set, format_token, parse_rule_text, parse_token_text, TextSyntaxError};
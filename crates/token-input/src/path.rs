@@ -0,0 +1,60 @@
+// パスの組み立て - `rules[0].then_action.target` の形
+//
+// CompileError のパスと、読み込んだファイルの位置（json_rule::spans）の両方がこの関数で
+// パスを作るので、形式がずれない。キーは `.` で、配列の添字は `[i]` で繋ぐ
+
+/// `prefix.path`（どちらかが空ならもう一方）
+pub fn join(prefix: &str, path: &str) -> String {
+    match (prefix.is_empty(), path.is_empty()) {
+        (true, _) => path.to_string(),
+        (false, true) => prefix.to_string(),
+        (false, false) => format!("{}.{}", prefix, path),
+    }
+}
+
+/// キーの列を繋いだパス
+pub fn from_segments(segments: &[String]) -> String {
+    segments.iter().fold(String::new(), |path, segment| join(&path, segment))
+}
+
+/// `parent[index]`
+pub fn index(parent: &str, index: usize) -> String {
+    format!("{}[{}]", parent, index)
+}
+
+/// 1段上のパス（`a.b[0]` → `a.b` → `a` → 空）。空のパスなら None
+pub fn parent(path: &str) -> Option<&str> {
+    if path.is_empty() {
+        return None;
+    }
+    Some(&path[..path.rfind(['.', '[']).unwrap_or(0)])
+}
+
+/// キーをそのままパスの1段として書けるか。`.` や `[` を含むキーは区切りと区別できない
+pub fn is_plain_key(key: &str) -> bool {
+    !key.is_empty() && !key.contains(['.', '[', ']'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_and_walk_up_paths() {
+        let path = join(&index("rules", 0), &from_segments(&["then_action".to_string(), index("alternatives", 1)]));
+        assert_eq!(path, "rules[0].then_action.alternatives[1]");
+        assert_eq!(join("", "target"), "target");
+        assert_eq!(join("rules[2]", ""), "rules[2]");
+
+        let ancestors: Vec<_> = std::iter::successors(Some(path.as_str()), |path| parent(path)).collect();
+        assert_eq!(
+            ancestors,
+            ["rules[0].then_action.alternatives[1]", "rules[0].then_action.alternatives", "rules[0].then_action", "rules[0]", "rules", ""]
+        );
+
+        assert!(is_plain_key("then_action"));
+        assert!(!is_plain_key("a.b"));
+        assert!(!is_plain_key("rules[0]"));
+        assert!(!is_plain_key(""));
+    }
+}
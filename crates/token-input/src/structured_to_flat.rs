@@ -0,0 +1,167 @@
+// StructuredTokenInput → FlatTokenInput 変換（convert_flat_to_structured の逆変換）
+
+use crate::compile_error::CompileError;
+use crate::path;
+use crate::token_registry::registry;
+use crate::type_system::TypeInferable;
+use crate::{FlatTokenInput, StructuredTokenInput};
+
+// 前置記法で書き出す。平坦トークンで表せないもの（負の数・引数名・Ref など）は
+// 情報を落とさずにエラーにする
+pub fn convert_structured_to_flat(token: &StructuredTokenInput) -> Result<Vec<FlatTokenInput>, CompileError> {
+    let mut flat_tokens = Vec::new();
+    write_flat_token(token, &mut Vec::new(), &mut flat_tokens)?;
+    Ok(flat_tokens)
+}
+
+fn write_flat_token(
+    token: &StructuredTokenInput,
+    path: &mut Vec<String>,
+    flat_tokens: &mut Vec<FlatTokenInput>,
+) -> Result<(), CompileError> {
+    let error = |path: &[String], message: String| CompileError::new(path::from_segments(path), message);
+    let name = token.token_name();
+    let definition = registry().get(name).filter(|definition| definition.flat_token.is_some());
+    let definition = definition.ok_or_else(|| error(path, format!("{} cannot be written as a flat token", name)))?;
+    let template = definition.flat_token.as_ref().unwrap();
+    let arguments = token.arguments();
+
+    let flat_token = match template {
+        FlatTokenInput::Number(_) => {
+            let value = token.value().unwrap_or_default();
+            let value = u32::try_from(value)
+                .map_err(|_| error(path, format!("Number {} cannot be written as a flat token", value)))?;
+            FlatTokenInput::Number(value)
+        }
+        FlatTokenInput::Let(_) => FlatTokenInput::Let(TypeInferable::name(token).unwrap_or_default().to_string()),
+        FlatTokenInput::Var(_) => FlatTokenInput::Var(TypeInferable::name(token).unwrap_or_default().to_string()),
+        FlatTokenInput::FirstOf(_) => FlatTokenInput::FirstOf(arguments.len()),
+        // 配列操作の引数名は平坦トークンに持てない
+        _ if TypeInferable::name(token).is_some() => {
+            let message = format!("{} with a parameter name cannot be written as a flat token", name);
+            return Err(error(path, message));
+        }
+        template => template.clone(),
+    };
+    flat_tokens.push(flat_token);
+
+    for (i, (slot, argument)) in arguments.iter().enumerate() {
+        // 可変長スロットは `slot[i]` で区別する
+        if definition.is_variadic(slot) {
+            let index = arguments[..i].iter().filter(|(s, _)| s == slot).count();
+            path.push(path::index(slot, index));
+        } else {
+            path.push(slot.to_string());
+        }
+        write_flat_token(argument, path, flat_tokens)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::convert_flat_to_structured;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// ランダムな平坦トークン列（1つの式）。引数は型を問わず任意のトークンで埋める
+    fn random_row(rng: &mut StdRng, root: &FlatTokenInput, depth: usize) -> Vec<FlatTokenInput> {
+        let flat_tokens = registry().flat_tokens();
+        let root = match root {
+            FlatTokenInput::Number(_) => FlatTokenInput::Number(rng.gen_range(0..=100)),
+            FlatTokenInput::Let(_) => FlatTokenInput::Let(["x", "target", "ally"][rng.gen_range(0..3)].to_string()),
+            FlatTokenInput::Var(_) => FlatTokenInput::Var(["x", "target", "ally"][rng.gen_range(0..3)].to_string()),
+            FlatTokenInput::FirstOf(_) => FlatTokenInput::FirstOf(rng.gen_range(1..=3)),
+            root => root.clone(),
+        };
+        let slots = root.definition().unwrap().flat_slots(&root);
+        let mut row = vec![root];
+        for _ in slots {
+            let child = if depth == 0 {
+                FlatTokenInput::Number(rng.gen_range(0..=100))
+            } else {
+                flat_tokens[rng.gen_range(0..flat_tokens.len())].clone()
+            };
+            row.extend(random_row(rng, &child, depth.saturating_sub(1)));
+        }
+        row
+    }
+
+    #[test]
+    fn test_flat_round_trip_for_every_token() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for root in registry().flat_tokens() {
+            for _ in 0..50 {
+                let row = random_row(&mut rng, &root, 3);
+                let structured = convert_flat_to_structured(&row).unwrap();
+                assert_eq!(structured.len(), 1);
+                let flat = convert_structured_to_flat(&structured[0]).unwrap();
+                assert_eq!(flat, row, "round trip of {:?}", root);
+            }
+        }
+
+        // i32 に収まる境界値は往復し、収まらない値は折り返さずにエラー
+        for n in [0, i32::MAX as u32] {
+            let row = vec![FlatTokenInput::Heal, FlatTokenInput::CharacterHpToCharacter, FlatTokenInput::Number(n)];
+            let structured = convert_flat_to_structured(&row).unwrap();
+            assert_eq!(convert_structured_to_flat(&structured[0]).unwrap(), row);
+        }
+        for n in [i32::MAX as u32 + 1, u32::MAX] {
+            let row = vec![FlatTokenInput::Heal, FlatTokenInput::CharacterHpToCharacter, FlatTokenInput::Number(n)];
+            assert_eq!(convert_flat_to_structured(&row).unwrap_err(), format!("Number {} is larger than 2147483647", n));
+        }
+    }
+
+    #[test]
+    fn test_structured_to_flat() {
+        let token = StructuredTokenInput::FirstOf {
+            alternatives: vec![
+                StructuredTokenInput::Heal { target: Box::new(StructuredTokenInput::Var { name: "me".to_string() }) },
+                StructuredTokenInput::Strike { target: Box::new(StructuredTokenInput::ActingCharacter) },
+            ],
+        };
+        assert_eq!(
+            convert_structured_to_flat(&token).unwrap(),
+            vec![
+                FlatTokenInput::FirstOf(2),
+                FlatTokenInput::Heal,
+                FlatTokenInput::Var("me".to_string()),
+                FlatTokenInput::Strike,
+                FlatTokenInput::ActingCharacter,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokens_without_flat_form_are_rejected() {
+        let negative = StructuredTokenInput::Strike {
+            target: Box::new(StructuredTokenInput::First {
+                array: Box::new(StructuredTokenInput::Take {
+                    array: Box::new(StructuredTokenInput::AllCharacters),
+                    count: Box::new(StructuredTokenInput::Number { value: -1 }),
+                }),
+            }),
+        };
+        let error = convert_structured_to_flat(&negative).unwrap_err();
+        assert_eq!(error.to_string(), "target.array.count: Number -1 cannot be written as a flat token");
+
+        let named = StructuredTokenInput::FilterList {
+            name: Some("enemy".to_string()),
+            array: Box::new(StructuredTokenInput::AllCharacters),
+            condition: Box::new(StructuredTokenInput::TrueOrFalseRandom),
+        };
+        let error = convert_structured_to_flat(&named).unwrap_err();
+        assert_eq!(error.to_string(), "FilterList with a parameter name cannot be written as a flat token");
+
+        let reference = StructuredTokenInput::FirstOf {
+            alternatives: vec![
+                StructuredTokenInput::Heal { target: Box::new(StructuredTokenInput::ActingCharacter) },
+                StructuredTokenInput::Strike { target: Box::new(StructuredTokenInput::Ref { name: "weakest".to_string() }) },
+            ],
+        };
+        let error = convert_structured_to_flat(&reference).unwrap_err();
+        assert_eq!(error.to_string(), "alternatives[1].target: Ref cannot be written as a flat token");
+    }
+}
@@ -1,14 +1,123 @@
 // StructuredTokenInput - JSON入力用の構造化されたトークン定義（rule-parserから移行）
 
-use serde::{Deserialize, Serialize};
+use crate::compile_error::CompileError;
+use crate::path;
+use crate::token_registry::{registry, NameField};
+use crate::type_system::TypeInferable;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub struct RuleSet {
+    /// ルールから `Ref` で参照できる名前付きの式
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub definitions: Vec<Definition>,
     pub rules: Vec<StructuredTokenInput>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[serde(tag = "type")]
+/// 名前付きの式。使用箇所（Ref）に展開され、そこで型検査される。
+/// 本体の Element / Var は定義側ではなく使用箇所を囲む配列操作・Let の束縛を指す
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Definition {
+    pub name: String,
+    pub body: StructuredTokenInput,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<StructuredTokenInput>) -> Self {
+        Self { definitions: Vec::new(), rules }
+    }
+
+    /// Ref を定義の式に置き換えたルール一覧。
+    /// 未定義の名前・循環参照・重複した定義は `rules[i]` / `definitions[i]` から始まるパスで報告する
+    pub fn expanded_rules(&self) -> Result<Vec<StructuredTokenInput>, Vec<CompileError>> {
+        let mut errors = Vec::new();
+        let mut expander = RefExpander { definitions: HashMap::new(), expanded: HashMap::new() };
+        for (i, definition) in self.definitions.iter().enumerate() {
+            if expander.definitions.insert(definition.name.as_str(), &definition.body).is_some() {
+                let message = format!("Definition {} is declared more than once", definition.name);
+                errors.push(CompileError::new(path::index("definitions", i), message));
+            }
+        }
+
+        // 使われていない定義も循環を検査する
+        for (i, definition) in self.definitions.iter().enumerate() {
+            let mut stack = vec![definition.name.clone()];
+            if let Err(error) = expander.expand(&definition.body, &mut stack, &mut Vec::new()) {
+                errors.push(error.with_prefix(&path::join(&path::index("definitions", i), "body")));
+            }
+        }
+
+        let mut rules = Vec::with_capacity(self.rules.len());
+        for (i, rule) in self.rules.iter().enumerate() {
+            match expander.expand(rule, &mut Vec::new(), &mut Vec::new()) {
+                Ok(rule) => rules.push(rule),
+                Err(error) => errors.push(error.with_prefix(&path::index("rules", i))),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(rules)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+struct RefExpander<'a> {
+    definitions: HashMap<&'a str, &'a StructuredTokenInput>,
+    /// 展開に成功した定義の本体（Ref を含まない）。共有された定義を使用箇所ごとに展開し直さない
+    expanded: HashMap<&'a str, StructuredTokenInput>,
+}
+
+impl<'a> RefExpander<'a> {
+    /// `stack` は展開中の定義名（外側から順）
+    fn expand(
+        &mut self,
+        token: &StructuredTokenInput,
+        stack: &mut Vec<String>,
+        path: &mut Vec<String>,
+    ) -> Result<StructuredTokenInput, CompileError> {
+        if let StructuredTokenInput::Ref { name } = token {
+            let (&name, &body) = self
+                .definitions
+                .get_key_value(name.as_str())
+                .ok_or_else(|| CompileError::new(path::from_segments(path), format!("Unknown definition: {}", name)))?;
+            // 展開済みの定義は循環を含まない
+            if let Some(expanded) = self.expanded.get(name) {
+                return Ok(expanded.clone());
+            }
+            if stack.iter().any(|outer| outer == name) {
+                let message = format!("Definition cycle: {} -> {}", stack.join(" -> "), name);
+                return Err(CompileError::new(path::from_segments(path), message));
+            }
+            stack.push(name.to_string());
+            let expanded = self.expand(body, stack, path);
+            stack.pop();
+            let expanded = expanded?;
+            self.expanded.insert(name, expanded.clone());
+            return Ok(expanded);
+        }
+
+        let mut arguments = Vec::new();
+        for (slot, argument) in token.arguments() {
+            path.push(slot.to_string());
+            let expanded = self.expand(argument, stack, path);
+            path.pop();
+            arguments.push(expanded?);
+        }
+        let name = TypeInferable::name(token).map(str::to_string);
+        StructuredTokenInput::from_parts(token.token_name(), arguments, token.value(), name)
+            .map_err(|message| CompileError::new(path::from_segments(path), message))
+    }
+}
+
+/// JSON では `{"type": <トークン名>, <スロット名>: <子トークン>, ...}` の形を取る。
+/// (デ)シリアライズはトークンレジストリのスロット宣言に従う
+#[derive(Debug, Clone, PartialEq)]
 pub enum StructuredTokenInput {
     Strike {
         target: Box<StructuredTokenInput>,
@@ -29,6 +138,56 @@ pub enum StructuredTokenInput {
         left: Box<StructuredTokenInput>,
         right: Box<StructuredTokenInput>,
     },
+    GreaterOrEqual {
+        left: Box<StructuredTokenInput>,
+        right: Box<StructuredTokenInput>,
+    },
+    LessOrEqual {
+        left: Box<StructuredTokenInput>,
+        right: Box<StructuredTokenInput>,
+    },
+    And {
+        left: Box<StructuredTokenInput>,
+        right: Box<StructuredTokenInput>,
+    },
+    Or {
+        left: Box<StructuredTokenInput>,
+        right: Box<StructuredTokenInput>,
+    },
+    Not {
+        condition: Box<StructuredTokenInput>,
+    },
+    /// 候補を順に試し、Break したら次の候補へ（JSON では "alternatives" に配列で並べる）
+    FirstOf {
+        alternatives: Vec<StructuredTokenInput>,
+    },
+    /// 値を返す条件分岐（スロット名は condition / then / else）
+    If {
+        condition: Box<StructuredTokenInput>,
+        then: Box<StructuredTokenInput>,
+        otherwise: Box<StructuredTokenInput>,
+    },
+    // Arithmetic
+    Add {
+        left: Box<StructuredTokenInput>,
+        right: Box<StructuredTokenInput>,
+    },
+    Sub {
+        left: Box<StructuredTokenInput>,
+        right: Box<StructuredTokenInput>,
+    },
+    Mul {
+        left: Box<StructuredTokenInput>,
+        right: Box<StructuredTokenInput>,
+    },
+    Div {
+        left: Box<StructuredTokenInput>,
+        right: Box<StructuredTokenInput>,
+    },
+    Percent {
+        base: Box<StructuredTokenInput>,
+        percent: Box<StructuredTokenInput>,
+    },
     Number {
         value: i32,
     },
@@ -38,6 +197,24 @@ pub enum StructuredTokenInput {
     CharacterHpToCharacter {
         character_hp: Box<StructuredTokenInput>,
     },
+    CharacterToMp {
+        character: Box<StructuredTokenInput>,
+    },
+    CharacterMpToCharacter {
+        character_mp: Box<StructuredTokenInput>,
+    },
+    CharacterAttack {
+        character: Box<StructuredTokenInput>,
+    },
+    MaxHp {
+        character: Box<StructuredTokenInput>,
+    },
+    MaxMp {
+        character: Box<StructuredTokenInput>,
+    },
+    HpPercent {
+        character: Box<StructuredTokenInput>,
+    },
     // Character types
     ActingCharacter,
     // Array types
@@ -50,10 +227,14 @@ pub enum StructuredTokenInput {
         array: Box<StructuredTokenInput>,
     },
     FilterList {
+        /// 要素を束縛する名前（省略時は Element でのみ参照できる）
+        name: Option<String>,
         array: Box<StructuredTokenInput>,
         condition: Box<StructuredTokenInput>,
     },
     Map {
+        /// 要素を束縛する名前（省略時は Element でのみ参照できる）
+        name: Option<String>,
         array: Box<StructuredTokenInput>,
         transform: Box<StructuredTokenInput>,
     },
@@ -61,6 +242,10 @@ pub enum StructuredTokenInput {
         left: Box<StructuredTokenInput>,
         right: Box<StructuredTokenInput>,
     },
+    NotEq {
+        left: Box<StructuredTokenInput>,
+        right: Box<StructuredTokenInput>,
+    },
     CharacterTeam {
         character: Box<StructuredTokenInput>,
     },
@@ -70,8 +255,486 @@ pub enum StructuredTokenInput {
     Max {
         array: Box<StructuredTokenInput>,
     },
+    MaxBy {
+        /// 要素を束縛する名前（省略時は Element でのみ参照できる）
+        name: Option<String>,
+        array: Box<StructuredTokenInput>,
+        key: Box<StructuredTokenInput>,
+    },
+    MinBy {
+        /// 要素を束縛する名前（省略時は Element でのみ参照できる）
+        name: Option<String>,
+        array: Box<StructuredTokenInput>,
+        key: Box<StructuredTokenInput>,
+    },
+    Count {
+        array: Box<StructuredTokenInput>,
+    },
+    Sum {
+        array: Box<StructuredTokenInput>,
+    },
+    Average {
+        array: Box<StructuredTokenInput>,
+    },
+    Any {
+        /// 要素を束縛する名前（省略時は Element でのみ参照できる）
+        name: Option<String>,
+        array: Box<StructuredTokenInput>,
+        condition: Box<StructuredTokenInput>,
+    },
+    All {
+        /// 要素を束縛する名前（省略時は Element でのみ参照できる）
+        name: Option<String>,
+        array: Box<StructuredTokenInput>,
+        condition: Box<StructuredTokenInput>,
+    },
+    IsEmpty {
+        array: Box<StructuredTokenInput>,
+    },
+    SortBy {
+        /// 要素を束縛する名前（省略時は Element でのみ参照できる）
+        name: Option<String>,
+        array: Box<StructuredTokenInput>,
+        key: Box<StructuredTokenInput>,
+    },
+    Take {
+        array: Box<StructuredTokenInput>,
+        count: Box<StructuredTokenInput>,
+    },
+    First {
+        array: Box<StructuredTokenInput>,
+    },
+    Last {
+        array: Box<StructuredTokenInput>,
+    },
+    Reverse {
+        array: Box<StructuredTokenInput>,
+    },
+    // Variables
+    Let {
+        name: String,
+        value: Box<StructuredTokenInput>,
+        body: Box<StructuredTokenInput>,
+    },
+    Var {
+        name: String,
+    },
+    /// RuleSet の definitions に宣言された式を名前で参照する
+    Ref {
+        name: String,
+    },
     Min {
         array: Box<StructuredTokenInput>,
     },
 }
 
+impl StructuredTokenInput {
+    /// トークン名と宣言順の引数から組み立てる
+    pub fn from_parts(
+        name: &str,
+        arguments: Vec<StructuredTokenInput>,
+        value: Option<i32>,
+        variable_name: Option<String>,
+    ) -> Result<Self, String> {
+        use StructuredTokenInput::*;
+
+        let variable = || variable_name.clone().ok_or_else(|| format!("{} requires a name", name));
+        let parameter = || variable_name.clone();
+
+        let mut arguments = arguments.into_iter().map(Box::new);
+        let mut arg = || arguments.next().ok_or_else(|| format!("{} is missing an argument", name));
+        let token = match name {
+            "Strike" => Strike { target: arg()? },
+            "Heal" => Heal { target: arg()? },
+            "TrueOrFalseRandom" => TrueOrFalseRandom,
+            "Check" => Check { condition: arg()?, then_action: arg()? },
+            "GreaterThan" => GreaterThan { left: arg()?, right: arg()? },
+            "LessThan" => LessThan { left: arg()?, right: arg()? },
+            "GreaterOrEqual" => GreaterOrEqual { left: arg()?, right: arg()? },
+            "LessOrEqual" => LessOrEqual { left: arg()?, right: arg()? },
+            "And" => And { left: arg()?, right: arg()? },
+            "Or" => Or { left: arg()?, right: arg()? },
+            "Not" => Not { condition: arg()? },
+            "FirstOf" => {
+                let alternatives: Vec<_> = arguments.by_ref().map(|argument| *argument).collect();
+                if alternatives.is_empty() {
+                    return Err("FirstOf requires at least one alternative".to_string());
+                }
+                FirstOf { alternatives }
+            }
+            "If" => If { condition: arg()?, then: arg()?, otherwise: arg()? },
+            "Add" => Add { left: arg()?, right: arg()? },
+            "Sub" => Sub { left: arg()?, right: arg()? },
+            "Mul" => Mul { left: arg()?, right: arg()? },
+            "Div" => Div { left: arg()?, right: arg()? },
+            "Percent" => Percent { base: arg()?, percent: arg()? },
+            "Number" => Number {
+                value: value.ok_or_else(|| "Number requires a value".to_string())?,
+            },
+            "CharacterToHp" => CharacterToHp { character: arg()? },
+            "CharacterHpToCharacter" => CharacterHpToCharacter { character_hp: arg()? },
+            "CharacterToMp" => CharacterToMp { character: arg()? },
+            "CharacterMpToCharacter" => CharacterMpToCharacter { character_mp: arg()? },
+            "CharacterAttack" => CharacterAttack { character: arg()? },
+            "MaxHp" => MaxHp { character: arg()? },
+            "MaxMp" => MaxMp { character: arg()? },
+            "HpPercent" => HpPercent { character: arg()? },
+            "ActingCharacter" => ActingCharacter,
+            "AllCharacters" => AllCharacters,
+            "TeamMembers" => TeamMembers { team_side: arg()? },
+            "AllTeamSides" => AllTeamSides,
+            "RandomPick" => RandomPick { array: arg()? },
+            "FilterList" => FilterList { name: parameter(), array: arg()?, condition: arg()? },
+            "Map" => Map { name: parameter(), array: arg()?, transform: arg()? },
+            "Eq" => Eq { left: arg()?, right: arg()? },
+            "NotEq" => NotEq { left: arg()?, right: arg()? },
+            "CharacterTeam" => CharacterTeam { character: arg()? },
+            "Element" => Element,
+            "Enemy" => Enemy,
+            "Hero" => Hero,
+            "Max" => Max { array: arg()? },
+            "Min" => Min { array: arg()? },
+            "MaxBy" => MaxBy { name: parameter(), array: arg()?, key: arg()? },
+            "MinBy" => MinBy { name: parameter(), array: arg()?, key: arg()? },
+            "Count" => Count { array: arg()? },
+            "Sum" => Sum { array: arg()? },
+            "Average" => Average { array: arg()? },
+            "Any" => Any { name: parameter(), array: arg()?, condition: arg()? },
+            "All" => All { name: parameter(), array: arg()?, condition: arg()? },
+            "IsEmpty" => IsEmpty { array: arg()? },
+            "SortBy" => SortBy { name: parameter(), array: arg()?, key: arg()? },
+            "Take" => Take { array: arg()?, count: arg()? },
+            "First" => First { array: arg()? },
+            "Last" => Last { array: arg()? },
+            "Reverse" => Reverse { array: arg()? },
+            "Let" => Let { name: variable()?, value: arg()?, body: arg()? },
+            "Var" => Var { name: variable()? },
+            "Ref" => Ref { name: variable()? },
+            _ => return Err(format!("Unknown token: {}", name)),
+        };
+        if arguments.next().is_some() {
+            return Err(format!("{} was given too many arguments", name));
+        }
+        Ok(token)
+    }
+}
+
+impl Serialize for StructuredTokenInput {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let arguments = self.arguments();
+        let definition = registry().get(self.token_name());
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", self.token_name())?;
+        if let Some(name) = TypeInferable::name(self) {
+            map.serialize_entry("name", name)?;
+        }
+        let mut serialized_slots: Vec<&str> = Vec::new();
+        for (slot, argument) in &arguments {
+            if !definition.is_some_and(|definition| definition.is_variadic(slot)) {
+                map.serialize_entry(slot, argument)?;
+            } else if !serialized_slots.contains(slot) {
+                // 可変長スロットは子トークンの配列にまとめる
+                let list: Vec<_> = arguments.iter().filter(|(s, _)| s == slot).map(|(_, a)| a).collect();
+                map.serialize_entry(slot, &list)?;
+                serialized_slots.push(slot);
+            }
+        }
+        if let Some(value) = self.value() {
+            map.serialize_entry("value", &value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for StructuredTokenInput {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(StructuredTokenVisitor)
+    }
+}
+
+struct StructuredTokenVisitor;
+
+impl<'de> Visitor<'de> for StructuredTokenVisitor {
+    type Value = StructuredTokenInput;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a token object with a \"type\" field")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut name: Option<String> = None;
+        let mut value: Option<i32> = None;
+        let mut variable_name: Option<String> = None;
+        let mut slots: Vec<(String, FieldValue)> = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "type" => name = Some(map.next_value()?),
+                "name" => variable_name = Some(map.next_value()?),
+                // "value" の数値リテラル、またはスロットの子トークン（配列なら可変長スロット）
+                _ => match map.next_value()? {
+                    FieldValue::Number(number) if key == "value" => value = Some(number),
+                    FieldValue::Number(_) => {
+                        return Err(de::Error::custom(format!("`{}` must be a token object", key)));
+                    }
+                    field => slots.push((key, field)),
+                },
+            }
+        }
+
+        let name = name.ok_or_else(|| de::Error::missing_field("type"))?;
+        let definition = registry()
+            .get(&name)
+            .ok_or_else(|| de::Error::custom(format!("unknown token type `{}`", name)))?;
+        if definition.has_value && value.is_none() {
+            return Err(de::Error::missing_field("value"));
+        }
+        if !definition.has_value && value.is_some() {
+            return Err(de::Error::custom(format!("{} does not take a value", name)));
+        }
+        if definition.name_field == NameField::Required && variable_name.is_none() {
+            return Err(de::Error::missing_field("name"));
+        }
+        if definition.name_field == NameField::Absent && variable_name.is_some() {
+            return Err(de::Error::custom(format!("{} does not take a name", name)));
+        }
+
+        let mut arguments = Vec::with_capacity(definition.arity());
+        for slot in definition.slots() {
+            let position = slots
+                .iter()
+                .position(|(key, _)| key == slot)
+                .ok_or_else(|| de::Error::missing_field(slot))?;
+            match (slots.remove(position).1, definition.is_variadic(slot)) {
+                (FieldValue::Token(token), false) => arguments.push(token),
+                (FieldValue::List(tokens), true) => arguments.extend(tokens),
+                (_, false) => return Err(de::Error::custom(format!("`{}` of {} must be a token object", slot, name))),
+                (_, true) => return Err(de::Error::custom(format!("`{}` of {} must be a list of tokens", slot, name))),
+            }
+        }
+        if let Some((key, _)) = slots.first() {
+            return Err(de::Error::custom(format!("unknown field `{}` for {}", key, name)));
+        }
+
+        StructuredTokenInput::from_parts(&name, arguments, value, variable_name).map_err(de::Error::custom)
+    }
+}
+
+/// "type" / "name" 以外のキーの中身。数値は "value" のリテラル、
+/// オブジェクトはスロットの子トークン、配列は可変長スロットの子トークン列
+enum FieldValue {
+    Number(i32),
+    Token(StructuredTokenInput),
+    List(Vec<StructuredTokenInput>),
+}
+
+impl<'de> Deserialize<'de> for FieldValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(FieldValueVisitor)
+    }
+}
+
+struct FieldValueVisitor;
+
+impl<'de> Visitor<'de> for FieldValueVisitor {
+    type Value = FieldValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an integer, a token object or a list of token objects")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        i32::try_from(v)
+            .map(FieldValue::Number)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        i32::try_from(v)
+            .map(FieldValue::Number)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        StructuredTokenVisitor.visit_map(map).map(FieldValue::Token)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut tokens = Vec::new();
+        while let Some(token) = seq.next_element()? {
+            tokens.push(token);
+        }
+        Ok(FieldValue::List(tokens))
+    }
+}
+
+impl TypeInferable for StructuredTokenInput {
+    type Value = i32;
+
+    fn token_name(&self) -> &str {
+        match self {
+            StructuredTokenInput::Strike { .. } => "Strike",
+            StructuredTokenInput::Heal { .. } => "Heal",
+            StructuredTokenInput::TrueOrFalseRandom => "TrueOrFalseRandom",
+            StructuredTokenInput::Check { .. } => "Check",
+            StructuredTokenInput::GreaterThan { .. } => "GreaterThan",
+            StructuredTokenInput::LessThan { .. } => "LessThan",
+            StructuredTokenInput::GreaterOrEqual { .. } => "GreaterOrEqual",
+            StructuredTokenInput::LessOrEqual { .. } => "LessOrEqual",
+            StructuredTokenInput::And { .. } => "And",
+            StructuredTokenInput::Or { .. } => "Or",
+            StructuredTokenInput::Not { .. } => "Not",
+            StructuredTokenInput::FirstOf { .. } => "FirstOf",
+            StructuredTokenInput::If { .. } => "If",
+            StructuredTokenInput::Add { .. } => "Add",
+            StructuredTokenInput::Sub { .. } => "Sub",
+            StructuredTokenInput::Mul { .. } => "Mul",
+            StructuredTokenInput::Div { .. } => "Div",
+            StructuredTokenInput::Percent { .. } => "Percent",
+            StructuredTokenInput::Number { .. } => "Number",
+            StructuredTokenInput::CharacterToHp { .. } => "CharacterToHp",
+            StructuredTokenInput::CharacterHpToCharacter { .. } => "CharacterHpToCharacter",
+            StructuredTokenInput::CharacterToMp { .. } => "CharacterToMp",
+            StructuredTokenInput::CharacterMpToCharacter { .. } => "CharacterMpToCharacter",
+            StructuredTokenInput::CharacterAttack { .. } => "CharacterAttack",
+            StructuredTokenInput::MaxHp { .. } => "MaxHp",
+            StructuredTokenInput::MaxMp { .. } => "MaxMp",
+            StructuredTokenInput::HpPercent { .. } => "HpPercent",
+            StructuredTokenInput::ActingCharacter => "ActingCharacter",
+            StructuredTokenInput::AllCharacters => "AllCharacters",
+            StructuredTokenInput::TeamMembers { .. } => "TeamMembers",
+            StructuredTokenInput::AllTeamSides => "AllTeamSides",
+            StructuredTokenInput::RandomPick { .. } => "RandomPick",
+            StructuredTokenInput::FilterList { .. } => "FilterList",
+            StructuredTokenInput::Map { .. } => "Map",
+            StructuredTokenInput::Eq { .. } => "Eq",
+            StructuredTokenInput::NotEq { .. } => "NotEq",
+            StructuredTokenInput::CharacterTeam { .. } => "CharacterTeam",
+            StructuredTokenInput::Element => "Element",
+            StructuredTokenInput::Enemy => "Enemy",
+            StructuredTokenInput::Hero => "Hero",
+            StructuredTokenInput::Max { .. } => "Max",
+            StructuredTokenInput::Min { .. } => "Min",
+            StructuredTokenInput::MaxBy { .. } => "MaxBy",
+            StructuredTokenInput::MinBy { .. } => "MinBy",
+            StructuredTokenInput::Count { .. } => "Count",
+            StructuredTokenInput::Sum { .. } => "Sum",
+            StructuredTokenInput::Average { .. } => "Average",
+            StructuredTokenInput::Any { .. } => "Any",
+            StructuredTokenInput::All { .. } => "All",
+            StructuredTokenInput::IsEmpty { .. } => "IsEmpty",
+            StructuredTokenInput::SortBy { .. } => "SortBy",
+            StructuredTokenInput::Take { .. } => "Take",
+            StructuredTokenInput::First { .. } => "First",
+            StructuredTokenInput::Last { .. } => "Last",
+            StructuredTokenInput::Reverse { .. } => "Reverse",
+            StructuredTokenInput::Let { .. } => "Let",
+            StructuredTokenInput::Var { .. } => "Var",
+            StructuredTokenInput::Ref { .. } => "Ref",
+        }
+    }
+
+    fn arguments(&self) -> Vec<(&'static str, &Self)> {
+        if let StructuredTokenInput::FirstOf { alternatives } = self {
+            return alternatives.iter().map(|alternative| ("alternatives", alternative)).collect();
+        }
+        match self {
+            StructuredTokenInput::Strike { target } | StructuredTokenInput::Heal { target } => {
+                vec![("target", target)]
+            }
+            StructuredTokenInput::Check { condition, then_action } => {
+                vec![("condition", condition), ("then_action", then_action)]
+            }
+            StructuredTokenInput::GreaterThan { left, right }
+            | StructuredTokenInput::LessThan { left, right }
+            | StructuredTokenInput::GreaterOrEqual { left, right }
+            | StructuredTokenInput::LessOrEqual { left, right }
+            | StructuredTokenInput::Eq { left, right }
+            | StructuredTokenInput::NotEq { left, right }
+            | StructuredTokenInput::And { left, right }
+            | StructuredTokenInput::Or { left, right }
+            | StructuredTokenInput::Add { left, right }
+            | StructuredTokenInput::Sub { left, right }
+            | StructuredTokenInput::Mul { left, right }
+            | StructuredTokenInput::Div { left, right } => vec![("left", left), ("right", right)],
+            StructuredTokenInput::Percent { base, percent } => vec![("base", base), ("percent", percent)],
+            StructuredTokenInput::Not { condition } => vec![("condition", condition)],
+            StructuredTokenInput::If { condition, then, otherwise } => {
+                vec![("condition", condition), ("then", then), ("else", otherwise)]
+            }
+            StructuredTokenInput::FirstOf { .. } => unreachable!("FirstOf is handled above"),
+            StructuredTokenInput::CharacterToHp { character }
+            | StructuredTokenInput::CharacterTeam { character }
+            | StructuredTokenInput::CharacterToMp { character }
+            | StructuredTokenInput::CharacterAttack { character }
+            | StructuredTokenInput::MaxHp { character }
+            | StructuredTokenInput::MaxMp { character }
+            | StructuredTokenInput::HpPercent { character } => vec![("character", character)],
+            StructuredTokenInput::CharacterMpToCharacter { character_mp } => {
+                vec![("character_mp", character_mp)]
+            }
+            StructuredTokenInput::CharacterHpToCharacter { character_hp } => {
+                vec![("character_hp", character_hp)]
+            }
+            StructuredTokenInput::TeamMembers { team_side } => vec![("team_side", team_side)],
+            StructuredTokenInput::RandomPick { array }
+            | StructuredTokenInput::Max { array }
+            | StructuredTokenInput::Min { array }
+            | StructuredTokenInput::Count { array }
+            | StructuredTokenInput::Sum { array }
+            | StructuredTokenInput::Average { array }
+            | StructuredTokenInput::IsEmpty { array }
+            | StructuredTokenInput::First { array }
+            | StructuredTokenInput::Last { array }
+            | StructuredTokenInput::Reverse { array } => vec![("array", array)],
+            StructuredTokenInput::SortBy { array, key, .. }
+            | StructuredTokenInput::MaxBy { array, key, .. }
+            | StructuredTokenInput::MinBy { array, key, .. } => vec![("array", array), ("key", key)],
+            StructuredTokenInput::Take { array, count } => vec![("array", array), ("count", count)],
+            StructuredTokenInput::Let { value, body, .. } => vec![("value", value), ("body", body)],
+            StructuredTokenInput::FilterList { array, condition, .. }
+            | StructuredTokenInput::Any { array, condition, .. }
+            | StructuredTokenInput::All { array, condition, .. } => {
+                vec![("array", array), ("condition", condition)]
+            }
+            StructuredTokenInput::Map { array, transform, .. } => {
+                vec![("array", array), ("transform", transform)]
+            }
+            StructuredTokenInput::TrueOrFalseRandom
+            | StructuredTokenInput::Number { .. }
+            | StructuredTokenInput::ActingCharacter
+            | StructuredTokenInput::AllCharacters
+            | StructuredTokenInput::AllTeamSides
+            | StructuredTokenInput::Element
+            | StructuredTokenInput::Enemy
+            | StructuredTokenInput::Hero
+            | StructuredTokenInput::Var { .. }
+            | StructuredTokenInput::Ref { .. } => vec![],
+        }
+        .into_iter()
+        .map(|(slot, token)| (slot, token.as_ref()))
+        .collect()
+    }
+
+    fn value(&self) -> Option<i32> {
+        match self {
+            StructuredTokenInput::Number { value } => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        match self {
+            StructuredTokenInput::Let { name, .. }
+            | StructuredTokenInput::Var { name }
+            | StructuredTokenInput::Ref { name } => Some(name),
+            StructuredTokenInput::FilterList { name, .. }
+            | StructuredTokenInput::Map { name, .. }
+            | StructuredTokenInput::Any { name, .. }
+            | StructuredTokenInput::All { name, .. }
+            | StructuredTokenInput::SortBy { name, .. }
+            | StructuredTokenInput::MaxBy { name, .. }
+            | StructuredTokenInput::MinBy { name, .. } => name.as_deref(),
+            _ => None,
+        }
+    }
+}
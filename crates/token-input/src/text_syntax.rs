@@ -0,0 +1,429 @@
+// テキスト記法 - StructuredTokenInput をテキストで読み書きする（UI・JSON に次ぐ3つ目の入力）
+//
+//   // コメント
+//   def heroes = FilterList(AllCharacters, Eq(CharacterTeam(Element), Hero))
+//   Check(LessThan(CharacterToHp(ActingCharacter), 50), Heal(ActingCharacter))
+//   Strike(RandomPick(Ref heroes))
+//
+// - 引数はトークンレジストリのスロット宣言順に並べる。引数を取らないトークンは括弧を付けない
+// - Number は整数リテラルで書く
+// - 名前を持つトークンは名前をトークン名の直後に書く（`Var x`, `Let x(value, body)`, `FilterList enemy(array, condition)`）
+// - `def 名前 = 式` は RuleSet の definitions になる
+
+use crate::token_registry::{registry, NameField};
+use crate::type_system::TypeInferable;
+use crate::{Definition, RuleSet, StructuredTokenInput};
+use std::fmt;
+
+/// 1行に収まらない式は引数ごとに改行する
+const MAX_LINE_WIDTH: usize = 80;
+const INDENT: &str = "    ";
+
+/// 定義を宣言するキーワード
+const DEFINITION_KEYWORD: &str = "def";
+
+/// テキストの読み取りエラー。位置は1始まりの行・列（文字単位）
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextSyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TextSyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for TextSyntaxError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Position {
+    fn error(self, message: impl Into<String>) -> TextSyntaxError {
+        TextSyntaxError { line: self.line, column: self.column, message: message.into() }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Lexeme {
+    Identifier(String),
+    Integer(i32),
+    OpenParen,
+    CloseParen,
+    Comma,
+    Equals,
+    End,
+}
+
+impl fmt::Display for Lexeme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lexeme::Identifier(name) => write!(f, "`{}`", name),
+            Lexeme::Integer(value) => write!(f, "`{}`", value),
+            Lexeme::OpenParen => write!(f, "`(`"),
+            Lexeme::CloseParen => write!(f, "`)`"),
+            Lexeme::Comma => write!(f, "`,`"),
+            Lexeme::Equals => write!(f, "`=`"),
+            Lexeme::End => write!(f, "end of input"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Position, Lexeme)>, TextSyntaxError> {
+    let mut lexemes = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut position = Position { line: 1, column: 1 };
+
+    while let Some(&c) = chars.peek() {
+        let start = position;
+        let mut advance = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+            let c = chars.next();
+            if c == Some('\n') {
+                position.line += 1;
+                position.column = 1;
+            } else {
+                position.column += 1;
+            }
+            c
+        };
+
+        match c {
+            c if c.is_whitespace() => {
+                advance(&mut chars);
+            }
+            '/' => {
+                advance(&mut chars);
+                if chars.peek() != Some(&'/') {
+                    return Err(start.error("Unexpected character `/`"));
+                }
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    advance(&mut chars);
+                }
+            }
+            '(' | ')' | ',' | '=' => {
+                advance(&mut chars);
+                let lexeme = match c {
+                    '(' => Lexeme::OpenParen,
+                    ')' => Lexeme::CloseParen,
+                    ',' => Lexeme::Comma,
+                    _ => Lexeme::Equals,
+                };
+                lexemes.push((start, lexeme));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut text = String::new();
+                text.extend(advance(&mut chars));
+                while chars.peek().is_some_and(char::is_ascii_digit) {
+                    text.extend(advance(&mut chars));
+                }
+                let value = text
+                    .parse()
+                    .map_err(|_| start.error(format!("Invalid integer `{}`", text)))?;
+                lexemes.push((start, Lexeme::Integer(value)));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut text = String::new();
+                while chars.peek().is_some_and(|&c| c.is_alphanumeric() || c == '_') {
+                    text.extend(advance(&mut chars));
+                }
+                lexemes.push((start, Lexeme::Identifier(text)));
+            }
+            c => return Err(start.error(format!("Unexpected character `{}`", c))),
+        }
+    }
+
+    lexemes.push((position, Lexeme::End));
+    Ok(lexemes)
+}
+
+struct Parser {
+    lexemes: Vec<(Position, Lexeme)>,
+    index: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Self, TextSyntaxError> {
+        Ok(Self { lexemes: tokenize(source)?, index: 0 })
+    }
+
+    fn peek(&self) -> &(Position, Lexeme) {
+        &self.lexemes[self.index]
+    }
+
+    fn next(&mut self) -> (Position, Lexeme) {
+        let lexeme = self.lexemes[self.index].clone();
+        if lexeme.1 != Lexeme::End {
+            self.index += 1;
+        }
+        lexeme
+    }
+
+    fn expect(&mut self, expected: Lexeme) -> Result<Position, TextSyntaxError> {
+        match self.next() {
+            (position, lexeme) if lexeme == expected => Ok(position),
+            (position, lexeme) => Err(position.error(format!("Expected {}, found {}", expected, lexeme))),
+        }
+    }
+
+    fn expect_name(&mut self, token: &str) -> Result<String, TextSyntaxError> {
+        match self.next() {
+            (_, Lexeme::Identifier(name)) => Ok(name),
+            (position, lexeme) => Err(position.error(format!("{} requires a name, found {}", token, lexeme))),
+        }
+    }
+
+    fn rule_set(&mut self) -> Result<RuleSet, TextSyntaxError> {
+        let mut rule_set = RuleSet::default();
+        loop {
+            match self.peek() {
+                (_, Lexeme::End) => return Ok(rule_set),
+                (_, Lexeme::Identifier(keyword)) if keyword == DEFINITION_KEYWORD => {
+                    self.next();
+                    let name = self.expect_name(DEFINITION_KEYWORD)?;
+                    self.expect(Lexeme::Equals)?;
+                    let body = self.expression()?;
+                    rule_set.definitions.push(Definition { name, body });
+                }
+                _ => rule_set.rules.push(self.expression()?),
+            }
+        }
+    }
+
+    fn expression(&mut self) -> Result<StructuredTokenInput, TextSyntaxError> {
+        let (position, lexeme) = self.next();
+        let name = match lexeme {
+            Lexeme::Integer(value) => {
+                return StructuredTokenInput::from_parts("Number", Vec::new(), Some(value), None)
+                    .map_err(|message| position.error(message));
+            }
+            Lexeme::Identifier(name) => name,
+            lexeme => return Err(position.error(format!("Expected a token, found {}", lexeme))),
+        };
+        let definition = registry()
+            .get(&name)
+            .ok_or_else(|| position.error(format!("Unknown token: {}", name)))?;
+        if definition.has_value {
+            return Err(position.error(format!("{} is written as an integer literal", name)));
+        }
+
+        let variable_name = match definition.name_field {
+            NameField::Absent => None,
+            NameField::Required => Some(self.expect_name(&name)?),
+            NameField::Optional => match self.peek() {
+                (_, Lexeme::Identifier(_)) => Some(self.expect_name(&name)?),
+                _ => None,
+            },
+        };
+
+        let mut arguments = Vec::new();
+        if definition.arity() > 0 {
+            let open = self.expect(Lexeme::OpenParen)?;
+            while self.peek().1 != Lexeme::CloseParen {
+                arguments.push(self.expression()?);
+                if self.peek().1 != Lexeme::CloseParen {
+                    self.expect(Lexeme::Comma)?;
+                }
+            }
+            self.next();
+
+            let variadic = definition.metadata.arguments.iter().any(|argument| argument.variadic);
+            let arity = definition.arity();
+            if arguments.len() < arity || (!variadic && arguments.len() > arity) {
+                let slots: Vec<_> = definition.slots().collect();
+                return Err(open.error(format!(
+                    "{} takes {}{} ({}), found {}",
+                    name,
+                    if variadic { "at least " } else { "" },
+                    plural(arity, "argument"),
+                    slots.join(", "),
+                    arguments.len()
+                )));
+            }
+        }
+
+        StructuredTokenInput::from_parts(&name, arguments, None, variable_name).map_err(|message| position.error(message))
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, word)
+    } else {
+        format!("{} {}s", count, word)
+    }
+}
+
+/// テキストからルールセットを読む
+pub fn parse_rule_text(source: &str) -> Result<RuleSet, TextSyntaxError> {
+    Parser::new(source)?.rule_set()
+}
+
+/// テキストから式を1つ読む
+pub fn parse_token_text(source: &str) -> Result<StructuredTokenInput, TextSyntaxError> {
+    let mut parser = Parser::new(source)?;
+    let token = parser.expression()?;
+    match parser.next() {
+        (_, Lexeme::End) => Ok(token),
+        (position, lexeme) => Err(position.error(format!("Expected end of input, found {}", lexeme))),
+    }
+}
+
+/// ルールセットをテキストに書き出す。`parse_rule_text` で読み戻せる
+pub fn format_rule_set(rule_set: &RuleSet) -> String {
+    let mut text = String::new();
+    for definition in &rule_set.definitions {
+        let head = format!("{} {} = ", DEFINITION_KEYWORD, definition.name);
+        text.push_str(&head);
+        write_token(&definition.body, 0, head.len(), &mut text);
+        text.push('\n');
+    }
+    if !rule_set.definitions.is_empty() && !rule_set.rules.is_empty() {
+        text.push('\n');
+    }
+    for rule in &rule_set.rules {
+        write_token(rule, 0, 0, &mut text);
+        text.push('\n');
+    }
+    text
+}
+
+/// 式を1行でテキストにする
+pub fn format_token(token: &StructuredTokenInput) -> String {
+    let head = token_head(token);
+    let arguments = token.arguments();
+    if arguments.is_empty() {
+        return head;
+    }
+    let arguments: Vec<_> = arguments.into_iter().map(|(_, argument)| format_token(argument)).collect();
+    format!("{}({})", head, arguments.join(", "))
+}
+
+fn token_head(token: &StructuredTokenInput) -> String {
+    if let Some(value) = token.value() {
+        return value.to_string();
+    }
+    match TypeInferable::name(token) {
+        Some(name) => format!("{} {}", token.token_name(), name),
+        None => token.token_name().to_string(),
+    }
+}
+
+/// `offset` は同じ行で式の前に書かれている文字数
+fn write_token(token: &StructuredTokenInput, depth: usize, offset: usize, text: &mut String) {
+    let inline = format_token(token);
+    let arguments = token.arguments();
+    if arguments.is_empty() || INDENT.len() * depth + offset + inline.len() <= MAX_LINE_WIDTH {
+        text.push_str(&inline);
+        return;
+    }
+
+    text.push_str(&token_head(token));
+    text.push_str("(\n");
+    for (_, argument) in arguments {
+        text.push_str(&INDENT.repeat(depth + 1));
+        write_token(argument, depth + 1, 0, text);
+        text.push_str(",\n");
+    }
+    text.push_str(&INDENT.repeat(depth));
+    text.push(')');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules_and_definitions() {
+        let source = "
+            // 味方チーム
+            def heroes = FilterList(AllCharacters, Eq(CharacterTeam(Element), Hero))
+
+            Check(LessThan(CharacterToHp(ActingCharacter), 50), Heal(ActingCharacter))
+            Strike(RandomPick(Ref heroes))
+        ";
+        let rule_set = parse_rule_text(source).unwrap();
+        assert_eq!(rule_set.definitions.len(), 1);
+        assert_eq!(rule_set.definitions[0].name, "heroes");
+        assert_eq!(rule_set.rules.len(), 2);
+
+        let StructuredTokenInput::Check { condition, .. } = &rule_set.rules[0] else { panic!("Expected Check") };
+        let StructuredTokenInput::LessThan { right, .. } = condition.as_ref() else { panic!("Expected LessThan") };
+        assert_eq!(right.as_ref(), &StructuredTokenInput::Number { value: 50 });
+    }
+
+    #[test]
+    fn test_parse_names_and_variadic_arguments() {
+        let token = parse_token_text("Let target(RandomPick(AllCharacters), Strike(Var target))").unwrap();
+        assert!(matches!(&token, StructuredTokenInput::Let { name, .. } if name == "target"));
+
+        let token = parse_token_text("FilterList enemy(TeamMembers(Enemy), Any(TeamMembers(Hero), LessThan(CharacterToHp(Var enemy), CharacterToHp(Element))))").unwrap();
+        let StructuredTokenInput::FilterList { name, condition, .. } = &token else { panic!("Expected FilterList") };
+        assert_eq!(name.as_deref(), Some("enemy"));
+        assert!(matches!(condition.as_ref(), StructuredTokenInput::Any { name: None, .. }));
+
+        let token = parse_token_text("FirstOf(Heal(ActingCharacter), Strike(ActingCharacter), Strike(ActingCharacter),)").unwrap();
+        assert!(matches!(&token, StructuredTokenInput::FirstOf { alternatives } if alternatives.len() == 3));
+
+        assert_eq!(parse_token_text("Sub(0, -5)").unwrap().arguments()[1].1, &StructuredTokenInput::Number { value: -5 });
+    }
+
+    #[test]
+    fn test_errors_report_line_and_column() {
+        let error = parse_rule_text("Heal(ActingCharacter)\nStrike(Foo)").unwrap_err();
+        assert_eq!(error.to_string(), "2:8: Unknown token: Foo");
+
+        let error = parse_rule_text("Check(TrueOrFalseRandom)").unwrap_err();
+        assert_eq!(error.to_string(), "1:6: Check takes 2 arguments (condition, then_action), found 1");
+
+        let error = parse_rule_text("Heal(ActingCharacter, ActingCharacter)").unwrap_err();
+        assert_eq!(error.to_string(), "1:5: Heal takes 1 argument (target), found 2");
+
+        let error = parse_rule_text("FirstOf()").unwrap_err();
+        assert_eq!(error.to_string(), "1:8: FirstOf takes at least 1 argument (alternatives), found 0");
+
+        let error = parse_rule_text("Strike(Var)").unwrap_err();
+        assert_eq!(error.to_string(), "1:11: Var requires a name, found `)`");
+
+        let error = parse_rule_text("Heal(\n  ActingCharacter").unwrap_err();
+        assert_eq!(error.to_string(), "2:18: Expected `,`, found end of input");
+
+        let error = parse_rule_text("Heal(ActingCharacter) $").unwrap_err();
+        assert_eq!(error.to_string(), "1:23: Unexpected character `$`");
+
+        let error = parse_rule_text("def = Hero").unwrap_err();
+        assert_eq!(error.to_string(), "1:5: def requires a name, found `=`");
+
+        let error = parse_token_text("Hero Enemy").unwrap_err();
+        assert_eq!(error.to_string(), "1:6: Expected end of input, found `Enemy`");
+    }
+
+    #[test]
+    fn test_format_round_trips() {
+        let source = "def heroes = FilterList(AllCharacters, Eq(CharacterTeam(Element), Hero))
+
+Check(LessThan(CharacterToHp(ActingCharacter), 50), Heal(ActingCharacter))
+FirstOf(
+    Check(
+        GreaterThan(
+            Count(
+                FilterList ally(Ref heroes, LessThan(HpPercent(Var ally), 30)),
+            ),
+            1,
+        ),
+        Heal(MinBy(Ref heroes, CharacterToHp(Element))),
+    ),
+    Strike(Let target(RandomPick(TeamMembers(Enemy)), Var target)),
+)
+";
+        let rule_set = parse_rule_text(source).unwrap();
+        let formatted = format_rule_set(&rule_set);
+        assert_eq!(formatted, source);
+        assert_eq!(parse_rule_text(&formatted).unwrap(), rule_set);
+    }
+}
@@ -0,0 +1,826 @@
+// トークン定義 - 型シグネチャとノード生成をトークン毎に1か所で宣言する
+
+use crate::compiler::{with_any_type, with_numeric_type, with_value_type, BuildArguments, CompiledNode, GameTypedAST};
+use crate::flat_token::FlatTokenInput;
+use crate::game_type::GameType::{self, *};
+use crate::type_system::{Type, TokenMetadata};
+use std::mem::discriminant;
+use std::sync::OnceLock;
+use action_system::{
+    ActingCharacterNode, AllCharactersNode, AllNode, AllTeamSidesNode, AndNode, AnyNode,
+    ArithmeticNode, ArithmeticOperator, AverageNode, CharacterHpToCharacterNode,
+    CharacterMpToCharacterNode, CharacterStat, CharacterStatNode, CharacterTeamNode,
+    CharacterToHpNode, CharacterToMpNode, ComparisonNode, ComparisonOperator, ConditionCheckNode,
+    ConstantValueNode, CountArrayNode, ElementNode, EnemyNode, ExtremeByNode, FilterListNode,
+    FirstNode, FirstOfNode, HealActionNode, HeroNode, IfNode, IsEmptyNode, LastNode, LetNode,
+    MappingNode, MaxNode, MinNode, NotNode, OrNode, RandomConditionNode, RandomPickNode,
+    ReverseNode, SortByNode, StrikeActionNode, SumNode, TakeNode, TeamMembersNode,
+    nodes::condition::{EqConditionNode, NotEqConditionNode},
+};
+
+pub type NodeBuilder = fn(&GameTypedAST, &mut BuildArguments) -> Result<CompiledNode, String>;
+
+/// トークンが名前（JSON の "name" フィールド）を取るか
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameField {
+    Absent,
+    /// 配列操作の引数名（省略可）
+    Optional,
+    /// Let / Var の変数名
+    Required,
+}
+
+pub struct TokenDefinition {
+    /// トークン名・引数スロット・型シグネチャ
+    pub metadata: TokenMetadata<GameType>,
+    /// UI表示用テキスト
+    pub display_text: &'static str,
+    /// 数値リテラルを持つか（JSON の "value" フィールド）
+    pub has_value: bool,
+    /// 変数名・引数名を持つか（JSON の "name" フィールド）
+    pub name_field: NameField,
+    /// UIのトークン一覧に並べる平坦トークン（UIから使えない場合は None）
+    pub flat_token: Option<FlatTokenInput>,
+    pub build: NodeBuilder,
+}
+
+impl TokenDefinition {
+    pub fn new(metadata: TokenMetadata<GameType>, display_text: &'static str, build: NodeBuilder) -> Self {
+        Self {
+            metadata,
+            display_text,
+            has_value: false,
+            name_field: NameField::Absent,
+            flat_token: None,
+            build,
+        }
+    }
+
+    pub fn with_value(mut self) -> Self {
+        self.has_value = true;
+        self
+    }
+
+    pub fn with_name(mut self) -> Self {
+        self.name_field = NameField::Required;
+        self
+    }
+
+    pub fn with_optional_name(mut self) -> Self {
+        self.name_field = NameField::Optional;
+        self
+    }
+
+    pub fn flat(mut self, flat_token: FlatTokenInput) -> Self {
+        self.flat_token = Some(flat_token);
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.metadata.name
+    }
+
+    /// 引数スロット名（宣言順）
+    pub fn slots(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.metadata.arguments.iter().map(|argument| argument.slot)
+    }
+
+    /// 平坦トークンが後続に取る引数のスロット名。可変長スロットはトークンの持つ個数だけ繰り返す
+    pub fn flat_slots(&self, token: &FlatTokenInput) -> Vec<&'static str> {
+        self.metadata
+            .arguments
+            .iter()
+            .flat_map(|argument| {
+                let count = if argument.variadic { token.alternative_count().unwrap_or(1) } else { 1 };
+                std::iter::repeat_n(argument.slot, count)
+            })
+            .collect()
+    }
+
+    pub fn is_variadic(&self, slot: &str) -> bool {
+        self.metadata.arguments.iter().any(|argument| argument.slot == slot && argument.variadic)
+    }
+
+    pub fn arity(&self) -> usize {
+        self.metadata.arguments.len()
+    }
+}
+
+/// 全トークン定義。パーサ・JSON・コンパイラ・UIはここを参照する
+pub struct TokenRegistry {
+    definitions: Vec<TokenDefinition>,
+}
+
+impl TokenRegistry {
+    pub fn get(&self, name: &str) -> Option<&TokenDefinition> {
+        self.definitions.iter().find(|definition| definition.name() == name)
+    }
+
+    /// 平坦トークンに対応する定義（Number の値は区別しない）
+    pub fn find_flat(&self, token: &FlatTokenInput) -> Option<&TokenDefinition> {
+        self.definitions.iter().find(|definition| {
+            definition
+                .flat_token
+                .as_ref()
+                .is_some_and(|flat| discriminant(flat) == discriminant(token))
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TokenDefinition> {
+        self.definitions.iter()
+    }
+
+    /// UIで選択できるトークン一覧（登録順）
+    pub fn flat_tokens(&self) -> Vec<FlatTokenInput> {
+        self.definitions
+            .iter()
+            .filter_map(|definition| definition.flat_token.clone())
+            .collect()
+    }
+}
+
+pub fn registry() -> &'static TokenRegistry {
+    static REGISTRY: OnceLock<TokenRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| TokenRegistry {
+        definitions: token_definitions(),
+    })
+}
+
+/// UIのトークン一覧に並べる Let / Var の変数名
+const DEFAULT_VARIABLE_NAME: &str = "x";
+
+/// UIのトークン一覧に並べる FirstOf の候補数
+const DEFAULT_ALTERNATIVE_COUNT: usize = 2;
+
+/// Element / Var が参照する束縛の深さ（型推論で解決済み）
+fn binding_depth(ast: &GameTypedAST) -> Result<usize, String> {
+    ast.binding_depth.ok_or_else(|| format!("{} is not resolved to a binding", ast.token_name))
+}
+
+fn t() -> Type<GameType> {
+    Type::param(0)
+}
+
+fn u() -> Type<GameType> {
+    Type::param(1)
+}
+
+fn array_of(element: Type<GameType>) -> Type<GameType> {
+    GameType::array_of(element)
+}
+
+/// 2つの Numeric から I32 を計算するトークン
+fn arithmetic(name: &'static str, left: &'static str, right: &'static str) -> TokenMetadata<GameType> {
+    TokenMetadata::new(name, I32.ty())
+        .argument(left, Numeric.ty())
+        .argument(right, Numeric.ty())
+}
+
+/// 配列型の要素型
+fn element_type(array: &Type<GameType>) -> Result<&Type<GameType>, String> {
+    array
+        .arguments()
+        .first()
+        .ok_or_else(|| format!("Expected an array type, found {}", array))
+}
+
+fn token_definitions() -> Vec<TokenDefinition> {
+    vec![
+        // Actions
+        TokenDefinition::new(
+            TokenMetadata::new("Check", Action.ty())
+                .argument("condition", Bool.ty())
+                .argument("then_action", Action.ty()),
+            "Check",
+            |_, args| {
+                let condition = args.take::<bool>()?;
+                let then_action = args.take::<Box<dyn action_system::Action>>()?;
+                Ok(CompiledNode::from_node(ConditionCheckNode::new(condition, then_action)))
+            },
+        )
+        .flat(FlatTokenInput::Check),
+        TokenDefinition::new(
+            TokenMetadata::new("Strike", Action.ty()).argument("target", Character.ty()),
+            "Strike",
+            |_, args| Ok(CompiledNode::from_node(StrikeActionNode::new(args.take()?))),
+        )
+        .flat(FlatTokenInput::Strike),
+        TokenDefinition::new(
+            TokenMetadata::new("Heal", Action.ty()).argument("target", Character.ty()),
+            "Heal",
+            |_, args| Ok(CompiledNode::from_node(HealActionNode::new(args.take()?))),
+        )
+        .flat(FlatTokenInput::Heal),
+        // Conditions
+        TokenDefinition::new(
+            TokenMetadata::new("TrueOrFalseRandom", Bool.ty()),
+            "50/50",
+            |_, _| Ok(CompiledNode::from_node(RandomConditionNode)),
+        )
+        .flat(FlatTokenInput::TrueOrFalse),
+        TokenDefinition::new(
+            TokenMetadata::new("GreaterThan", Bool.ty())
+                .argument("left", Numeric.ty())
+                .argument("right", Numeric.ty()),
+            "L-gt-R",
+            |_, args| Ok(CompiledNode::from_node(ComparisonNode::new(ComparisonOperator::GreaterThan, args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::GreaterThan),
+        TokenDefinition::new(
+            TokenMetadata::new("LessThan", Bool.ty())
+                .argument("left", Numeric.ty())
+                .argument("right", Numeric.ty()),
+            "L-lt-R",
+            |_, args| Ok(CompiledNode::from_node(ComparisonNode::new(ComparisonOperator::LessThan, args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::LessThan),
+        TokenDefinition::new(
+            TokenMetadata::new("GreaterOrEqual", Bool.ty())
+                .argument("left", Numeric.ty())
+                .argument("right", Numeric.ty()),
+            "L-ge-R",
+            |_, args| Ok(CompiledNode::from_node(ComparisonNode::new(ComparisonOperator::GreaterOrEqual, args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::GreaterOrEqual),
+        TokenDefinition::new(
+            TokenMetadata::new("LessOrEqual", Bool.ty())
+                .argument("left", Numeric.ty())
+                .argument("right", Numeric.ty()),
+            "L-le-R",
+            |_, args| Ok(CompiledNode::from_node(ComparisonNode::new(ComparisonOperator::LessOrEqual, args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::LessOrEqual),
+        TokenDefinition::new(
+            TokenMetadata::new("And", Bool.ty())
+                .argument("left", Bool.ty())
+                .argument("right", Bool.ty()),
+            "And",
+            |_, args| Ok(CompiledNode::from_node(AndNode::new(args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::And),
+        TokenDefinition::new(
+            TokenMetadata::new("Or", Bool.ty())
+                .argument("left", Bool.ty())
+                .argument("right", Bool.ty()),
+            "Or",
+            |_, args| Ok(CompiledNode::from_node(OrNode::new(args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::Or),
+        TokenDefinition::new(
+            TokenMetadata::new("Not", Bool.ty()).argument("condition", Bool.ty()),
+            "Not",
+            |_, args| Ok(CompiledNode::from_node(NotNode::new(args.take()?))),
+        )
+        .flat(FlatTokenInput::Not),
+        // 候補を順に試し、Break したら次の候補へ（候補は全て同じ型）
+        TokenDefinition::new(
+            TokenMetadata::new("FirstOf", t())
+                .type_parameter(None)
+                .variadic_argument("alternatives", t()),
+            "FirstOf",
+            |ast, args| {
+                with_any_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(FirstOfNode::<T>::new(args.take_rest()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::FirstOf(DEFAULT_ALTERNATIVE_COUNT)),
+        // 値を返す条件分岐（両分岐は同じ型）
+        TokenDefinition::new(
+            TokenMetadata::new("If", t())
+                .type_parameter(None)
+                .argument("condition", Bool.ty())
+                .argument("then", t())
+                .argument("else", t()),
+            "If",
+            |ast, args| {
+                with_any_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(IfNode::<T>::new(args.take()?, args.take()?, args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::If),
+        // Arithmetic（オーバーフローは飽和、0除算は EvaluationError）
+        TokenDefinition::new(
+            arithmetic("Add", "left", "right"),
+            "L+R",
+            |_, args| Ok(CompiledNode::from_node(ArithmeticNode::new(ArithmeticOperator::Add, args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::Add),
+        TokenDefinition::new(
+            arithmetic("Sub", "left", "right"),
+            "L-R",
+            |_, args| Ok(CompiledNode::from_node(ArithmeticNode::new(ArithmeticOperator::Sub, args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::Sub),
+        TokenDefinition::new(
+            arithmetic("Mul", "left", "right"),
+            "L*R",
+            |_, args| Ok(CompiledNode::from_node(ArithmeticNode::new(ArithmeticOperator::Mul, args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::Mul),
+        TokenDefinition::new(
+            arithmetic("Div", "left", "right"),
+            "L/R",
+            |_, args| Ok(CompiledNode::from_node(ArithmeticNode::new(ArithmeticOperator::Div, args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::Div),
+        TokenDefinition::new(
+            arithmetic("Percent", "base", "percent"),
+            "L%R",
+            |_, args| Ok(CompiledNode::from_node(ArithmeticNode::new(ArithmeticOperator::Percent, args.take()?, args.take()?))),
+        )
+        .flat(FlatTokenInput::Percent),
+        // Values
+        TokenDefinition::new(
+            TokenMetadata::new("Number", I32.ty()),
+            "Num",
+            |ast, _| {
+                let value = ast.value.ok_or_else(|| "Number requires a value".to_string())?;
+                Ok(CompiledNode::from_node(ConstantValueNode::new(value)))
+            },
+        )
+        .with_value()
+        .flat(FlatTokenInput::Number(50)),
+        TokenDefinition::new(
+            TokenMetadata::new("CharacterToHp", CharacterHP.ty()).argument("character", Character.ty()),
+            "CharToHp",
+            |_, args| Ok(CompiledNode::from_node(CharacterToHpNode::new(args.take()?))),
+        )
+        .flat(FlatTokenInput::CharacterToHp),
+        TokenDefinition::new(
+            TokenMetadata::new("CharacterHpToCharacter", Character.ty()).argument("character_hp", CharacterHP.ty()),
+            "CharHpToChar",
+            |_, args| Ok(CompiledNode::from_node(CharacterHpToCharacterNode::new(args.take()?))),
+        )
+        .flat(FlatTokenInput::CharacterHpToCharacter),
+        TokenDefinition::new(
+            TokenMetadata::new("CharacterToMp", CharacterMP.ty()).argument("character", Character.ty()),
+            "CharToMp",
+            |_, args| Ok(CompiledNode::from_node(CharacterToMpNode::new(args.take()?))),
+        )
+        .flat(FlatTokenInput::CharacterToMp),
+        TokenDefinition::new(
+            TokenMetadata::new("CharacterMpToCharacter", Character.ty()).argument("character_mp", CharacterMP.ty()),
+            "CharMpToChar",
+            |_, args| Ok(CompiledNode::from_node(CharacterMpToCharacterNode::new(args.take()?))),
+        )
+        .flat(FlatTokenInput::CharacterMpToCharacter),
+        TokenDefinition::new(
+            TokenMetadata::new("CharacterAttack", I32.ty()).argument("character", Character.ty()),
+            "CharAtk",
+            |_, args| Ok(CompiledNode::from_node(CharacterStatNode::new(CharacterStat::Attack, args.take()?))),
+        )
+        .flat(FlatTokenInput::CharacterAttack),
+        TokenDefinition::new(
+            TokenMetadata::new("MaxHp", I32.ty()).argument("character", Character.ty()),
+            "MaxHp",
+            |_, args| Ok(CompiledNode::from_node(CharacterStatNode::new(CharacterStat::MaxHp, args.take()?))),
+        )
+        .flat(FlatTokenInput::MaxHp),
+        TokenDefinition::new(
+            TokenMetadata::new("MaxMp", I32.ty()).argument("character", Character.ty()),
+            "MaxMp",
+            |_, args| Ok(CompiledNode::from_node(CharacterStatNode::new(CharacterStat::MaxMp, args.take()?))),
+        )
+        .flat(FlatTokenInput::MaxMp),
+        TokenDefinition::new(
+            TokenMetadata::new("HpPercent", I32.ty()).argument("character", Character.ty()),
+            "Hp%",
+            |_, args| Ok(CompiledNode::from_node(CharacterStatNode::new(CharacterStat::HpPercent, args.take()?))),
+        )
+        .flat(FlatTokenInput::HpPercent),
+        TokenDefinition::new(
+            TokenMetadata::new("ActingCharacter", Character.ty()),
+            "ActingChar",
+            |_, _| Ok(CompiledNode::from_node(ActingCharacterNode)),
+        )
+        .flat(FlatTokenInput::ActingCharacter),
+        // Arrays
+        TokenDefinition::new(
+            TokenMetadata::new("AllCharacters", array_of(Character.ty())),
+            "AllChars",
+            |_, _| Ok(CompiledNode::from_node(AllCharactersNode)),
+        )
+        .flat(FlatTokenInput::AllCharacters),
+        TokenDefinition::new(
+            TokenMetadata::new("TeamMembers", array_of(Character.ty())).argument("team_side", TeamSide.ty()),
+            "TeamMembers",
+            |_, args| Ok(CompiledNode::from_node(TeamMembersNode::new_with_node(args.take()?))),
+        )
+        .flat(FlatTokenInput::TeamMembers),
+        TokenDefinition::new(
+            TokenMetadata::new("AllTeamSides", array_of(TeamSide.ty())),
+            "AllTeamSides",
+            |_, _| Ok(CompiledNode::from_node(AllTeamSidesNode)),
+        ),
+        TokenDefinition::new(
+            TokenMetadata::new("RandomPick", t())
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t())),
+            "RandomPick",
+            |ast, args| {
+                with_value_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(RandomPickNode::<T>::new(args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::RandomPick),
+        TokenDefinition::new(
+            TokenMetadata::new("FilterList", array_of(t()))
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t()))
+                .argument_with_parameter("condition", Bool.ty(), "Element", t()),
+            "FilterList",
+            |ast, args| {
+                with_value_type!(element_type(&ast.node_type)?, |T| {
+                    Ok(CompiledNode::from_node(FilterListNode::<T>::new(args.take()?, args.take()?)))
+                })
+            },
+        )
+        .with_optional_name()
+        .flat(FlatTokenInput::FilterList),
+        TokenDefinition::new(
+            TokenMetadata::new("Map", array_of(u()))
+                .type_parameter(Some(Value.ty()))
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t()))
+                .argument_with_parameter("transform", u(), "Element", t()),
+            "Map",
+            |ast, args| {
+                with_value_type!(element_type(&ast.arguments[0].node_type)?, |T| {
+                    with_value_type!(element_type(&ast.node_type)?, |U| {
+                        Ok(CompiledNode::from_node(MappingNode::<T, U>::new(args.take()?, args.take()?)))
+                    })
+                })
+            },
+        )
+        .with_optional_name()
+        .flat(FlatTokenInput::Map),
+        TokenDefinition::new(
+            TokenMetadata::new("Eq", Bool.ty())
+                .type_parameter(Some(Value.ty()))
+                .argument("left", t())
+                .argument("right", t()),
+            "Eq",
+            |ast, args| {
+                with_value_type!(&ast.arguments[0].expected_type, |T| {
+                    Ok(CompiledNode::from_node(EqConditionNode::<T>::new(args.take()?, args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::Eq),
+        TokenDefinition::new(
+            TokenMetadata::new("NotEq", Bool.ty())
+                .type_parameter(Some(Value.ty()))
+                .argument("left", t())
+                .argument("right", t()),
+            "NotEq",
+            |ast, args| {
+                with_value_type!(&ast.arguments[0].expected_type, |T| {
+                    Ok(CompiledNode::from_node(NotEqConditionNode::<T>::new(args.take()?, args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::NotEq),
+        TokenDefinition::new(
+            TokenMetadata::new("CharacterTeam", TeamSide.ty()).argument("character", Character.ty()),
+            "CharTeam",
+            |_, args| Ok(CompiledNode::from_node(CharacterTeamNode::new(args.take()?))),
+        )
+        .flat(FlatTokenInput::CharacterTeam),
+        TokenDefinition::new(
+            TokenMetadata::binding("Element", "Element"),
+            "Element",
+            |ast, _| {
+                let depth = binding_depth(ast)?;
+                with_value_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(ElementNode::<T>::at(depth)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::Element),
+        TokenDefinition::new(
+            TokenMetadata::new("Enemy", TeamSide.ty()),
+            "Enemy",
+            |_, _| Ok(CompiledNode::from_node(EnemyNode)),
+        )
+        .flat(FlatTokenInput::Enemy),
+        TokenDefinition::new(
+            TokenMetadata::new("Hero", TeamSide.ty()),
+            "Hero",
+            |_, _| Ok(CompiledNode::from_node(HeroNode)),
+        )
+        .flat(FlatTokenInput::Hero),
+        TokenDefinition::new(
+            TokenMetadata::new("Max", t())
+                .type_parameter(Some(Numeric.ty()))
+                .argument("array", array_of(t())),
+            "Max",
+            |ast, args| {
+                with_numeric_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(MaxNode::<T>::new(args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::Max),
+        TokenDefinition::new(
+            TokenMetadata::new("Min", t())
+                .type_parameter(Some(Numeric.ty()))
+                .argument("array", array_of(t())),
+            "Min",
+            |ast, args| {
+                with_numeric_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(MinNode::<T>::new(args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::Min),
+        // 空配列は Break
+        TokenDefinition::new(
+            TokenMetadata::new("MaxBy", t())
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t()))
+                .argument_with_parameter("key", Numeric.ty(), "Element", t()),
+            "MaxBy",
+            |ast, args| {
+                with_value_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(ExtremeByNode::<T>::max(args.take()?, args.take()?)))
+                })
+            },
+        )
+        .with_optional_name()
+        .flat(FlatTokenInput::MaxBy),
+        TokenDefinition::new(
+            TokenMetadata::new("MinBy", t())
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t()))
+                .argument_with_parameter("key", Numeric.ty(), "Element", t()),
+            "MinBy",
+            |ast, args| {
+                with_value_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(ExtremeByNode::<T>::min(args.take()?, args.take()?)))
+                })
+            },
+        )
+        .with_optional_name()
+        .flat(FlatTokenInput::MinBy),
+        TokenDefinition::new(
+            TokenMetadata::new("Count", I32.ty())
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t())),
+            "Count",
+            |ast, args| {
+                with_value_type!(element_type(&ast.arguments[0].node_type)?, |T| {
+                    Ok(CompiledNode::from_node(CountArrayNode::<T>::new(args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::Count),
+        TokenDefinition::new(
+            TokenMetadata::new("Sum", I32.ty())
+                .type_parameter(Some(Numeric.ty()))
+                .argument("array", array_of(t())),
+            "Sum",
+            |ast, args| {
+                with_numeric_type!(element_type(&ast.arguments[0].node_type)?, |T| {
+                    Ok(CompiledNode::from_node(SumNode::<T>::new(args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::Sum),
+        // 空配列の平均は Break（First / Last / Take と同じく FirstOf の次の候補へ進む）
+        TokenDefinition::new(
+            TokenMetadata::new("Average", I32.ty())
+                .type_parameter(Some(Numeric.ty()))
+                .argument("array", array_of(t())),
+            "Avg",
+            |ast, args| {
+                with_numeric_type!(element_type(&ast.arguments[0].node_type)?, |T| {
+                    Ok(CompiledNode::from_node(AverageNode::<T>::new(args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::Average),
+        TokenDefinition::new(
+            TokenMetadata::new("Any", Bool.ty())
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t()))
+                .argument_with_parameter("condition", Bool.ty(), "Element", t()),
+            "Any",
+            |ast, args| {
+                with_value_type!(element_type(&ast.arguments[0].node_type)?, |T| {
+                    Ok(CompiledNode::from_node(AnyNode::<T>::new(args.take()?, args.take()?)))
+                })
+            },
+        )
+        .with_optional_name()
+        .flat(FlatTokenInput::Any),
+        TokenDefinition::new(
+            TokenMetadata::new("All", Bool.ty())
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t()))
+                .argument_with_parameter("condition", Bool.ty(), "Element", t()),
+            "All",
+            |ast, args| {
+                with_value_type!(element_type(&ast.arguments[0].node_type)?, |T| {
+                    Ok(CompiledNode::from_node(AllNode::<T>::new(args.take()?, args.take()?)))
+                })
+            },
+        )
+        .with_optional_name()
+        .flat(FlatTokenInput::All),
+        TokenDefinition::new(
+            TokenMetadata::new("IsEmpty", Bool.ty())
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t())),
+            "IsEmpty",
+            |ast, args| {
+                with_value_type!(element_type(&ast.arguments[0].node_type)?, |T| {
+                    Ok(CompiledNode::from_node(IsEmptyNode::<T>::new(args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::IsEmpty),
+        TokenDefinition::new(
+            TokenMetadata::new("SortBy", array_of(t()))
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t()))
+                .argument_with_parameter("key", Numeric.ty(), "Element", t()),
+            "SortBy",
+            |ast, args| {
+                with_value_type!(element_type(&ast.node_type)?, |T| {
+                    Ok(CompiledNode::from_node(SortByNode::<T>::new(args.take()?, args.take()?)))
+                })
+            },
+        )
+        .with_optional_name()
+        .flat(FlatTokenInput::SortBy),
+        TokenDefinition::new(
+            TokenMetadata::new("Take", array_of(t()))
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t()))
+                .argument("count", I32.ty()),
+            "Take",
+            |ast, args| {
+                with_value_type!(element_type(&ast.node_type)?, |T| {
+                    Ok(CompiledNode::from_node(TakeNode::<T>::new(args.take()?, args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::Take),
+        TokenDefinition::new(
+            TokenMetadata::new("First", t())
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t())),
+            "First",
+            |ast, args| {
+                with_value_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(FirstNode::<T>::new(args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::First),
+        TokenDefinition::new(
+            TokenMetadata::new("Last", t())
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t())),
+            "Last",
+            |ast, args| {
+                with_value_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(LastNode::<T>::new(args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::Last),
+        TokenDefinition::new(
+            TokenMetadata::new("Reverse", array_of(t()))
+                .type_parameter(Some(Value.ty()))
+                .argument("array", array_of(t())),
+            "Reverse",
+            |ast, args| {
+                with_value_type!(element_type(&ast.node_type)?, |T| {
+                    Ok(CompiledNode::from_node(ReverseNode::<T>::new(args.take()?)))
+                })
+            },
+        )
+        .flat(FlatTokenInput::Reverse),
+        TokenDefinition::new(
+            TokenMetadata::new("Let", u())
+                .type_parameter(Some(Value.ty()))
+                .type_parameter(None)
+                .argument("value", t())
+                .argument_binding_token_name("body", u(), t()),
+            "Let",
+            |ast, args| {
+                with_value_type!(&ast.arguments[0].node_type, |T| {
+                    with_any_type!(&ast.node_type, |U| {
+                        Ok(CompiledNode::from_node(LetNode::<T, U>::new(args.take()?, args.take()?)))
+                    })
+                })
+            },
+        )
+        .with_name()
+        .flat(FlatTokenInput::Let(DEFAULT_VARIABLE_NAME.to_string())),
+        TokenDefinition::new(
+            TokenMetadata::variable("Var"),
+            "Var",
+            |ast, _| {
+                let depth = binding_depth(ast)?;
+                with_value_type!(&ast.node_type, |T| {
+                    Ok(CompiledNode::from_node(ElementNode::<T>::at(depth)))
+                })
+            },
+        )
+        .with_name()
+        .flat(FlatTokenInput::Var(DEFAULT_VARIABLE_NAME.to_string())),
+        // 定義の参照。RuleSet::expanded_rules で展開されてから変換される（UIからは使えない）
+        TokenDefinition::new(
+            TokenMetadata::new("Ref", t()).type_parameter(None),
+            "Ref",
+            |ast, _| Err(format!("Ref {} is not expanded", ast.name.as_deref().unwrap_or_default())),
+        )
+        .with_name(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::type_system::TypeInferable;
+    use crate::{convert_flat_to_structured, StructuredTokenInput};
+
+    fn sample(definition: &TokenDefinition) -> StructuredTokenInput {
+        let arguments = definition
+            .slots()
+            .map(|_| StructuredTokenInput::Number { value: 1 })
+            .collect();
+        let value = definition.has_value.then_some(7);
+        let variable_name = (definition.name_field != NameField::Absent).then(|| "x".to_string());
+        StructuredTokenInput::from_parts(definition.name(), arguments, value, variable_name)
+            .unwrap_or_else(|e| panic!("{} cannot be constructed: {}", definition.name(), e))
+    }
+
+    #[test]
+    fn test_structured_tokens_agree_with_registry() {
+        for definition in registry().iter() {
+            let token = sample(definition);
+            assert_eq!(token.token_name(), definition.name());
+            let slots: Vec<_> = token.arguments().into_iter().map(|(slot, _)| slot).collect();
+            assert_eq!(slots, definition.slots().collect::<Vec<_>>(), "slots of {}", definition.name());
+            assert_eq!(token.value().is_some(), definition.has_value);
+            assert_eq!(token.name().is_some(), definition.name_field != NameField::Absent);
+        }
+    }
+
+    #[test]
+    fn test_token_names_are_unique() {
+        let names: Vec<_> = registry().iter().map(TokenDefinition::name).collect();
+        for name in &names {
+            assert_eq!(names.iter().filter(|n| *n == name).count(), 1, "{} is registered twice", name);
+        }
+    }
+
+    #[test]
+    fn test_slots_do_not_clash_with_json_keys() {
+        for definition in registry().iter() {
+            for slot in definition.slots() {
+                assert!(slot != "type" && slot != "name", "{} uses reserved slot name {}", definition.name(), slot);
+                // "value" は数値リテラルを持たないトークンでのみスロット名に使える
+                assert!(
+                    !(definition.has_value && slot == "value"),
+                    "{} uses reserved slot name {}",
+                    definition.name(),
+                    slot
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_flat_tokens_resolve_to_their_definition() {
+        for flat in registry().flat_tokens() {
+            let definition = flat.definition().expect("flat token should be registered");
+            assert_eq!(definition.flat_token.as_ref(), Some(&flat));
+            assert!(!flat.display_text().is_empty());
+
+            // 引数を全て Number で埋めれば1つのトークンとして読める
+            let mut row = vec![flat.clone()];
+            row.extend(definition.flat_slots(&flat).iter().map(|_| FlatTokenInput::Number(1)));
+            let structured = convert_flat_to_structured(&row).unwrap();
+            assert_eq!(structured.len(), 1);
+            assert_eq!(structured[0].token_name(), definition.name());
+        }
+    }
+
+    #[test]
+    fn test_display_text() {
+        assert_eq!(FlatTokenInput::TrueOrFalse.display_text(), "50/50");
+        assert_eq!(FlatTokenInput::GreaterThan.display_text(), "L-gt-R");
+        assert_eq!(FlatTokenInput::Number(50).display_text(), "50");
+        assert_eq!(FlatTokenInput::Number(3).display_text(), "3");
+        assert_eq!(FlatTokenInput::Let("x".to_string()).display_text(), "Let x");
+        assert_eq!(FlatTokenInput::Var("target".to_string()).display_text(), "Var target");
+        assert_eq!(FlatTokenInput::FirstOf(3).display_text(), "FirstOf 3");
+    }
+}
@@ -0,0 +1,936 @@
+// 言語コア型システム - ドメインに依存しない型表現と型推論エンジン
+//
+// 型の「形」と「関係性」だけを扱い、具体的な型（Character など）は
+// TypeSystem の実装側が TypeId として与える。
+
+use crate::path;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// 型の項。ドメイン固有の型コンストラクタと型変数からなる
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Type<Id> {
+    /// 型コンストラクタの適用（例: `Character`, `Array<Character>`）
+    Con(Id, Vec<Type<Id>>),
+    /// 型変数（トークン宣言では型パラメータ、推論中は未確定の型）
+    Var(u32),
+}
+
+impl<Id> Type<Id> {
+    pub fn con(id: Id) -> Self {
+        Type::Con(id, Vec::new())
+    }
+
+    pub fn app(id: Id, arguments: Vec<Type<Id>>) -> Self {
+        Type::Con(id, arguments)
+    }
+
+    /// トークン宣言で使う i 番目の型パラメータ
+    pub fn param(index: u32) -> Self {
+        Type::Var(index)
+    }
+
+    pub fn constructor(&self) -> Option<&Id> {
+        match self {
+            Type::Con(id, _) => Some(id),
+            Type::Var(_) => None,
+        }
+    }
+
+    pub fn arguments(&self) -> &[Type<Id>] {
+        match self {
+            Type::Con(_, arguments) => arguments,
+            Type::Var(_) => &[],
+        }
+    }
+
+    /// 型変数を含まないか
+    pub fn is_resolved(&self) -> bool {
+        match self {
+            Type::Con(_, arguments) => arguments.iter().all(Type::is_resolved),
+            Type::Var(_) => false,
+        }
+    }
+
+    fn contains_variable(&self, variable: u32) -> bool {
+        match self {
+            Type::Con(_, arguments) => arguments.iter().any(|a| a.contains_variable(variable)),
+            Type::Var(v) => *v == variable,
+        }
+    }
+}
+
+impl<Id: fmt::Display> fmt::Display for Type<Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Con(id, arguments) if arguments.is_empty() => write!(f, "{}", id),
+            Type::Con(id, arguments) => {
+                write!(f, "{}<", id)?;
+                for (i, argument) in arguments.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", argument)?;
+                }
+                write!(f, ">")
+            }
+            Type::Var(v) => write!(f, "T{}", v),
+        }
+    }
+}
+
+/// ドメイン固有の型の関係性を定義する
+pub trait TypeSystem {
+    type TypeId: Clone + Eq + Hash + fmt::Debug + fmt::Display;
+
+    /// `sub` を `super_` が要求される場所で使えるか（同一型は含まない）
+    fn is_subtype(&self, sub: &Type<Self::TypeId>, super_: &Type<Self::TypeId>) -> bool;
+}
+
+/// スコープに束縛・参照される名前
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BindingName {
+    /// トークン宣言で固定された名前（Element など）
+    Fixed(&'static str),
+    /// トークン自身が持つ名前（Let / Var など）
+    Token,
+}
+
+/// トークンの引数スロットの宣言
+#[derive(Clone, Debug)]
+pub struct ArgumentMetadata<Id> {
+    pub slot: &'static str,
+    pub argument_type: Type<Id>,
+    /// この引数の内側でスコープに追加される束縛。1つの値に複数の名前を付けられる
+    /// （名前を持たないトークンでは `BindingName::Token` は無視される）
+    pub bindings: Vec<(Vec<BindingName>, Type<Id>)>,
+    /// 同じスロットに1つ以上の子を並べられるか（FirstOf の候補など）
+    pub variadic: bool,
+}
+
+/// トークンの型シグネチャ
+#[derive(Clone, Debug)]
+pub struct TokenMetadata<Id> {
+    pub name: &'static str,
+    /// 型パラメータ毎の上限（`Type::param(i)` が i 番目に対応）
+    pub type_parameters: Vec<Option<Type<Id>>>,
+    pub arguments: Vec<ArgumentMetadata<Id>>,
+    pub return_type: Type<Id>,
+    /// スコープ上の名前を参照するトークンの場合、その名前
+    pub binding_reference: Option<BindingName>,
+}
+
+impl<Id> TokenMetadata<Id> {
+    pub fn new(name: &'static str, return_type: Type<Id>) -> Self {
+        Self {
+            name,
+            type_parameters: Vec::new(),
+            arguments: Vec::new(),
+            return_type,
+            binding_reference: None,
+        }
+    }
+
+    /// スコープに束縛された値をそのまま返すトークン（Element など）
+    pub fn binding(name: &'static str, binding: &'static str) -> Self {
+        Self {
+            name,
+            type_parameters: Vec::new(),
+            arguments: Vec::new(),
+            return_type: Type::param(0),
+            binding_reference: Some(BindingName::Fixed(binding)),
+        }
+    }
+
+    /// トークン自身が持つ名前で束縛された値を返すトークン（Var）
+    pub fn variable(name: &'static str) -> Self {
+        Self {
+            binding_reference: Some(BindingName::Token),
+            ..Self::binding(name, name)
+        }
+    }
+
+    /// 型パラメータを追加する（`bound` を指定するとその部分型に制限）
+    pub fn type_parameter(mut self, bound: Option<Type<Id>>) -> Self {
+        self.type_parameters.push(bound);
+        self
+    }
+
+    pub fn argument(self, slot: &'static str, argument_type: Type<Id>) -> Self {
+        self.argument_with_bindings(slot, argument_type, Vec::new())
+    }
+
+    pub fn argument_with_bindings(
+        mut self,
+        slot: &'static str,
+        argument_type: Type<Id>,
+        bindings: Vec<(&'static str, Type<Id>)>,
+    ) -> Self {
+        let bindings = bindings.into_iter().map(|(name, ty)| (vec![BindingName::Fixed(name)], ty)).collect();
+        self.arguments.push(ArgumentMetadata { slot, argument_type, bindings, variadic: false });
+        self
+    }
+
+    /// 1つ以上の子を並べられる引数。子はそれぞれ `argument_type` として推論される
+    pub fn variadic_argument(mut self, slot: &'static str, argument_type: Type<Id>) -> Self {
+        self.arguments.push(ArgumentMetadata { slot, argument_type, bindings: Vec::new(), variadic: true });
+        self
+    }
+
+    /// 引数の内側でトークン自身が持つ名前を `binding_type` として束縛する（Let の body）
+    pub fn argument_binding_token_name(
+        mut self,
+        slot: &'static str,
+        argument_type: Type<Id>,
+        binding_type: Type<Id>,
+    ) -> Self {
+        self.arguments.push(ArgumentMetadata {
+            slot,
+            argument_type,
+            bindings: vec![(vec![BindingName::Token], binding_type)],
+            variadic: false,
+        });
+        self
+    }
+
+    /// 引数の内側で `parameter_type` の値を `alias` とトークン自身が持つ名前（あれば）で束縛する
+    /// （配列操作の Element と名前付き引数）
+    pub fn argument_with_parameter(
+        mut self,
+        slot: &'static str,
+        argument_type: Type<Id>,
+        alias: &'static str,
+        parameter_type: Type<Id>,
+    ) -> Self {
+        self.arguments.push(ArgumentMetadata {
+            slot,
+            argument_type,
+            bindings: vec![(vec![BindingName::Fixed(alias), BindingName::Token], parameter_type)],
+            variadic: false,
+        });
+        self
+    }
+}
+
+/// 型推論の対象となる構文木
+pub trait TypeInferable {
+    /// トークンが直接保持する値（数値リテラルなど）
+    type Value: Clone;
+
+    fn token_name(&self) -> &str;
+    /// スロット名と子ノードの組
+    fn arguments(&self) -> Vec<(&'static str, &Self)>;
+    fn value(&self) -> Option<Self::Value> {
+        None
+    }
+    /// トークンが直接保持する名前（Let / Var の変数名など）
+    fn name(&self) -> Option<&str> {
+        None
+    }
+    /// まだ入力されていない穴か（`infer_partial` でのみ使う）
+    fn is_hole(&self) -> bool {
+        false
+    }
+}
+
+/// 型付きAST
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypedAST<Id, V> {
+    /// 式自身の型
+    pub node_type: Type<Id>,
+    /// 親の引数スロットが要求する型（部分型による暗黙の変換がある場合 node_type と異なる）
+    pub expected_type: Type<Id>,
+    pub token_name: String,
+    /// 親トークンでのスロット名（ルートでは空）
+    pub slot: String,
+    pub arguments: Vec<TypedAST<Id, V>>,
+    pub value: Option<V>,
+    pub name: Option<String>,
+    /// 束縛を参照するトークンの場合、参照先の深さ（0 = 最も内側の束縛）
+    pub binding_depth: Option<usize>,
+}
+
+pub type InferenceResult<Id, V> = Result<TypedAST<Id, V>, TypeError<Id>>;
+
+/// 部分的な構文木の穴と、そこに置けるトークンの型
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeHole<Id> {
+    pub path: Vec<String>,
+    /// 穴の型。まだ決まっていなければ型変数のまま
+    pub expected: Type<Id>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeErrorKind<Id> {
+    UnknownToken(String),
+    MissingArgument { token: String, slot: &'static str },
+    UnexpectedArgument { token: String, slot: &'static str },
+    Mismatch { expected: Type<Id>, found: Type<Id> },
+    UnboundName { token: String, name: String },
+    UnsatisfiedBound { token: String, bound: Type<Id>, found: Type<Id> },
+    Ambiguous { token: String },
+}
+
+impl<Id: fmt::Display> fmt::Display for TypeErrorKind<Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeErrorKind::UnknownToken(token) => write!(f, "Unknown token: {}", token),
+            TypeErrorKind::MissingArgument { token, slot } => {
+                write!(f, "{} requires argument '{}'", token, slot)
+            }
+            TypeErrorKind::UnexpectedArgument { token, slot } => {
+                write!(f, "{} does not take argument '{}'", token, slot)
+            }
+            TypeErrorKind::Mismatch { expected, found } => {
+                write!(f, "Type mismatch: expected {}, found {}", expected, found)
+            }
+            TypeErrorKind::UnboundName { token, name } => {
+                write!(f, "{} is used outside of a scope that binds {}", token, name)
+            }
+            TypeErrorKind::UnsatisfiedBound { token, bound, found } => {
+                write!(f, "{} requires {}, found {}", token, bound, found)
+            }
+            TypeErrorKind::Ambiguous { token } => {
+                write!(f, "Cannot determine the type of {}", token)
+            }
+        }
+    }
+}
+
+/// 型エラーと、それが起きたトークンまでのスロット名の列
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeError<Id> {
+    pub path: Vec<String>,
+    pub kind: TypeErrorKind<Id>,
+}
+
+impl<Id> TypeError<Id> {
+    pub fn new(path: &[String], kind: TypeErrorKind<Id>) -> Self {
+        Self { path: path.to_vec(), kind }
+    }
+
+    /// 要求されていた型
+    pub fn expected(&self) -> Option<&Type<Id>> {
+        match &self.kind {
+            TypeErrorKind::Mismatch { expected, .. } => Some(expected),
+            TypeErrorKind::UnsatisfiedBound { bound, .. } => Some(bound),
+            _ => None,
+        }
+    }
+
+    /// 実際に見つかった型
+    pub fn found(&self) -> Option<&Type<Id>> {
+        match &self.kind {
+            TypeErrorKind::Mismatch { found, .. } | TypeErrorKind::UnsatisfiedBound { found, .. } => Some(found),
+            _ => None,
+        }
+    }
+}
+
+impl<Id: fmt::Display> fmt::Display for TypeError<Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{}: {}", path::from_segments(&self.path), self.kind)
+        }
+    }
+}
+
+/// 推論中の型変数の状態
+struct InferenceState<Id> {
+    substitution: HashMap<u32, Type<Id>>,
+    next_variable: u32,
+    /// 穴を許すか。許す場合は見つかった穴を出現順に記録する
+    holes: Option<Vec<(Vec<String>, Type<Id>)>>,
+}
+
+impl<Id: Clone + PartialEq> InferenceState<Id> {
+    fn new(partial: bool) -> Self {
+        Self {
+            substitution: HashMap::new(),
+            next_variable: 0,
+            holes: partial.then(Vec::new),
+        }
+    }
+
+    fn fresh(&mut self) -> Type<Id> {
+        let variable = self.next_variable;
+        self.next_variable += 1;
+        Type::Var(variable)
+    }
+
+    fn resolve(&self, ty: &Type<Id>) -> Type<Id> {
+        match ty {
+            Type::Con(id, arguments) => {
+                Type::Con(id.clone(), arguments.iter().map(|a| self.resolve(a)).collect())
+            }
+            Type::Var(v) => match self.substitution.get(v) {
+                Some(bound) => self.resolve(bound),
+                None => ty.clone(),
+            },
+        }
+    }
+
+    fn bind(&mut self, variable: u32, ty: Type<Id>) -> bool {
+        if ty == Type::Var(variable) {
+            return true;
+        }
+        if ty.contains_variable(variable) {
+            return false;
+        }
+        self.substitution.insert(variable, ty);
+        true
+    }
+}
+
+/// シグネチャ中の型パラメータを推論用の型変数に置き換える
+fn instantiate<Id: Clone>(ty: &Type<Id>, parameters: &[Type<Id>]) -> Type<Id> {
+    match ty {
+        Type::Con(id, arguments) => {
+            Type::Con(id.clone(), arguments.iter().map(|a| instantiate(a, parameters)).collect())
+        }
+        Type::Var(index) => parameters[*index as usize].clone(),
+    }
+}
+
+/// スコープ上の束縛（名前の組と型）。内側ほど後ろに積まれる
+type Scope<Id> = Vec<(Vec<String>, Type<Id>)>;
+
+/// 登録されたトークンのシグネチャに基づいて構文木の型を推論する
+pub struct TypeInferenceEngine<S: TypeSystem> {
+    system: S,
+    metadata: HashMap<&'static str, TokenMetadata<S::TypeId>>,
+}
+
+impl<S: TypeSystem> TypeInferenceEngine<S> {
+    pub fn new(system: S) -> Self {
+        Self {
+            system,
+            metadata: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, metadata: TokenMetadata<S::TypeId>) {
+        self.metadata.insert(metadata.name, metadata);
+    }
+
+    pub fn metadata(&self, name: &str) -> Option<&TokenMetadata<S::TypeId>> {
+        self.metadata.get(name)
+    }
+
+    pub fn type_system(&self) -> &S {
+        &self.system
+    }
+
+    /// `ast` が `expected` として使える前提で型を推論する
+    pub fn infer<AST: TypeInferable>(
+        &self,
+        ast: &AST,
+        expected: &Type<S::TypeId>,
+    ) -> InferenceResult<S::TypeId, AST::Value> {
+        let mut state = InferenceState::new(false);
+        let mut path = Vec::new();
+        let mut typed = self.infer_node(ast, &Vec::new(), &mut state, &mut path)?;
+        self.constrain(&typed.node_type, expected, &mut state, true)
+            .map_err(|kind| TypeError::new(&path, kind))?;
+        typed.expected_type = expected.clone();
+        Self::finalize(&mut typed, &state, &mut path)?;
+        Ok(typed)
+    }
+
+    /// 穴を含む構文木を推論し、穴を出現順に返す。
+    /// 穴が埋まれば決まる型パラメータは未確定のままでもエラーにしない
+    pub fn infer_partial<AST: TypeInferable>(
+        &self,
+        ast: &AST,
+        expected: &Type<S::TypeId>,
+    ) -> Result<Vec<TypeHole<S::TypeId>>, TypeError<S::TypeId>> {
+        let mut state = InferenceState::new(true);
+        let mut path = Vec::new();
+        let typed = self.infer_node(ast, &Vec::new(), &mut state, &mut path)?;
+        self.constrain(&typed.node_type, expected, &mut state, true)
+            .map_err(|kind| TypeError::new(&path, kind))?;
+        let holes = state.holes.take().unwrap_or_default();
+        Ok(holes
+            .into_iter()
+            .map(|(path, ty)| TypeHole { path, expected: state.resolve(&ty) })
+            .collect())
+    }
+
+    fn infer_node<AST: TypeInferable>(
+        &self,
+        ast: &AST,
+        scope: &Scope<S::TypeId>,
+        state: &mut InferenceState<S::TypeId>,
+        path: &mut Vec<String>,
+    ) -> InferenceResult<S::TypeId, AST::Value> {
+        if ast.is_hole() && state.holes.is_some() {
+            // 穴の型は親のスロットとの制約で決まる
+            let ty = state.fresh();
+            if let Some(holes) = &mut state.holes {
+                holes.push((path.clone(), ty.clone()));
+            }
+            return Ok(TypedAST {
+                node_type: ty.clone(),
+                expected_type: ty,
+                token_name: String::new(),
+                slot: path.last().cloned().unwrap_or_default(),
+                arguments: Vec::new(),
+                value: None,
+                name: None,
+                binding_depth: None,
+            });
+        }
+
+        let token_name = ast.token_name();
+        let metadata = self
+            .metadata
+            .get(token_name)
+            .ok_or_else(|| TypeError::new(path, TypeErrorKind::UnknownToken(token_name.to_string())))?;
+
+        let missing_name = |path: &[String]| {
+            TypeError::new(path, TypeErrorKind::MissingArgument { token: token_name.to_string(), slot: "name" })
+        };
+        let resolve_name = |binding: &BindingName| match binding {
+            BindingName::Fixed(name) => Some(name.to_string()),
+            BindingName::Token => ast.name().map(str::to_string),
+        };
+
+        if let Some(binding) = &metadata.binding_reference {
+            let name = resolve_name(binding).ok_or_else(|| missing_name(path))?;
+            // 名前は変換時に束縛の深さへ解決する
+            let (depth, (_, bound_type)) = scope
+                .iter()
+                .rev()
+                .enumerate()
+                .find(|(_, (names, _))| names.contains(&name))
+                .ok_or_else(|| TypeError::new(path, TypeErrorKind::UnboundName {
+                    token: token_name.to_string(),
+                    name: name.clone(),
+                }))?;
+            return Ok(TypedAST {
+                node_type: bound_type.clone(),
+                expected_type: bound_type.clone(),
+                token_name: token_name.to_string(),
+                slot: path.last().cloned().unwrap_or_default(),
+                arguments: Vec::new(),
+                value: ast.value(),
+                name: Some(name),
+                binding_depth: Some(depth),
+            });
+        }
+
+        let provided = ast.arguments();
+        if let Some((slot, _)) = provided
+            .iter()
+            .find(|(slot, _)| !metadata.arguments.iter().any(|a| a.slot == *slot))
+        {
+            return Err(TypeError::new(path, TypeErrorKind::UnexpectedArgument {
+                token: token_name.to_string(),
+                slot,
+            }));
+        }
+
+        let parameters: Vec<_> = metadata.type_parameters.iter().map(|_| state.fresh()).collect();
+        let mut arguments = Vec::with_capacity(metadata.arguments.len());
+        for argument in &metadata.arguments {
+            let children: Vec<_> = provided
+                .iter()
+                .filter(|(slot, _)| *slot == argument.slot)
+                .map(|(_, child)| *child)
+                .collect();
+            if children.is_empty() {
+                return Err(TypeError::new(path, TypeErrorKind::MissingArgument {
+                    token: token_name.to_string(),
+                    slot: argument.slot,
+                }));
+            }
+            // 可変長スロットの子はパス上 `slot[i]` で区別する
+            let children: Vec<_> = if argument.variadic {
+                children.into_iter().enumerate().map(|(i, child)| (path::index(argument.slot, i), child)).collect()
+            } else {
+                vec![(argument.slot.to_string(), children[0])]
+            };
+
+            let mut child_scope = scope.clone();
+            for (binding_names, ty) in &argument.bindings {
+                let names: Vec<String> = binding_names.iter().filter_map(resolve_name).collect();
+                if names.is_empty() {
+                    return Err(missing_name(path));
+                }
+                child_scope.push((names, state.resolve(&instantiate(ty, &parameters))));
+            }
+
+            for (slot, child) in children {
+                let expected = state.resolve(&instantiate(&argument.argument_type, &parameters));
+                path.push(slot);
+                let mut typed = self.infer_node(child, &child_scope, state, path)?;
+                self.constrain(&typed.node_type, &expected, state, true)
+                    .map_err(|kind| TypeError::new(path, kind))?;
+                path.pop();
+                typed.expected_type = expected;
+                arguments.push(typed);
+            }
+        }
+
+        for (parameter, bound) in parameters.iter().zip(&metadata.type_parameters) {
+            let Some(bound) = bound else { continue };
+            let found = state.resolve(parameter);
+            if !found.is_resolved() && state.holes.is_some() {
+                continue;
+            }
+            if !found.is_resolved() {
+                return Err(TypeError::new(path, TypeErrorKind::Ambiguous { token: token_name.to_string() }));
+            }
+            if found != *bound && !self.system.is_subtype(&found, bound) {
+                return Err(TypeError::new(path, TypeErrorKind::UnsatisfiedBound {
+                    token: token_name.to_string(),
+                    bound: bound.clone(),
+                    found,
+                }));
+            }
+        }
+
+        let node_type = state.resolve(&instantiate(&metadata.return_type, &parameters));
+        Ok(TypedAST {
+            node_type: node_type.clone(),
+            expected_type: node_type,
+            token_name: token_name.to_string(),
+            slot: path.last().cloned().unwrap_or_default(),
+            arguments,
+            value: ast.value(),
+            name: ast.name().map(str::to_string),
+            binding_depth: None,
+        })
+    }
+
+    /// `found` を `expected` の場所で使えるように型変数を束縛する。
+    /// 部分型はトップレベルでのみ許可し、型引数は一致を要求する
+    fn constrain(
+        &self,
+        found: &Type<S::TypeId>,
+        expected: &Type<S::TypeId>,
+        state: &mut InferenceState<S::TypeId>,
+        allow_subtype: bool,
+    ) -> Result<(), TypeErrorKind<S::TypeId>> {
+        let found = state.resolve(found);
+        let expected = state.resolve(expected);
+        let mismatch = || TypeErrorKind::Mismatch {
+            expected: expected.clone(),
+            found: found.clone(),
+        };
+
+        match (&found, &expected) {
+            (_, Type::Var(v)) => state.bind(*v, found.clone()).then_some(()).ok_or_else(mismatch),
+            (Type::Var(v), _) => state.bind(*v, expected.clone()).then_some(()).ok_or_else(mismatch),
+            (Type::Con(found_id, found_arguments), Type::Con(expected_id, expected_arguments))
+                if found_id == expected_id && found_arguments.len() == expected_arguments.len() =>
+            {
+                for (f, e) in found_arguments.iter().zip(expected_arguments) {
+                    self.constrain(f, e, state, false).map_err(|_| mismatch())?;
+                }
+                Ok(())
+            }
+            _ if allow_subtype && self.system.is_subtype(&found, &expected) => Ok(()),
+            _ => Err(mismatch()),
+        }
+    }
+
+    fn finalize<V>(
+        typed: &mut TypedAST<S::TypeId, V>,
+        state: &InferenceState<S::TypeId>,
+        path: &mut Vec<String>,
+    ) -> Result<(), TypeError<S::TypeId>> {
+        typed.node_type = state.resolve(&typed.node_type);
+        typed.expected_type = state.resolve(&typed.expected_type);
+        if !typed.node_type.is_resolved() || !typed.expected_type.is_resolved() {
+            return Err(TypeError::new(path, TypeErrorKind::Ambiguous { token: typed.token_name.clone() }));
+        }
+        for argument in &mut typed.arguments {
+            path.push(argument.slot.clone());
+            Self::finalize(argument, state, path)?;
+            path.pop();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ドメインに依存しないことを確認するための最小の型システム
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    enum ToyType {
+        Int,
+        Text,
+        Any,
+        List,
+    }
+
+    impl fmt::Display for ToyType {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    struct ToySystem;
+
+    impl TypeSystem for ToySystem {
+        type TypeId = ToyType;
+
+        fn is_subtype(&self, sub: &Type<ToyType>, super_: &Type<ToyType>) -> bool {
+            *super_ == Type::con(ToyType::Any) && sub.arguments().is_empty()
+        }
+    }
+
+    struct Expr {
+        name: &'static str,
+        arguments: Vec<(&'static str, Expr)>,
+        variable: Option<&'static str>,
+    }
+
+    fn expr(name: &'static str, arguments: Vec<(&'static str, Expr)>) -> Expr {
+        Expr { name, arguments, variable: None }
+    }
+
+    fn named(name: &'static str, variable: &'static str, arguments: Vec<(&'static str, Expr)>) -> Expr {
+        Expr { name, arguments, variable: Some(variable) }
+    }
+
+    impl TypeInferable for Expr {
+        type Value = ();
+
+        fn token_name(&self) -> &str {
+            self.name
+        }
+
+        fn arguments(&self) -> Vec<(&'static str, &Self)> {
+            self.arguments.iter().map(|(slot, e)| (*slot, e)).collect()
+        }
+
+        fn name(&self) -> Option<&str> {
+            self.variable
+        }
+
+        fn is_hole(&self) -> bool {
+            self.name == "?"
+        }
+    }
+
+    fn int() -> Type<ToyType> {
+        Type::con(ToyType::Int)
+    }
+
+    fn list(element: Type<ToyType>) -> Type<ToyType> {
+        Type::app(ToyType::List, vec![element])
+    }
+
+    fn engine() -> TypeInferenceEngine<ToySystem> {
+        let mut engine = TypeInferenceEngine::new(ToySystem);
+        engine.register(TokenMetadata::new("One", int()));
+        engine.register(TokenMetadata::new("Hello", Type::con(ToyType::Text)));
+        engine.register(TokenMetadata::new("Ints", list(int())));
+        engine.register(TokenMetadata::new("Show", Type::con(ToyType::Text)).argument("value", Type::con(ToyType::Any)));
+        engine.register(
+            TokenMetadata::new("First", Type::param(0))
+                .type_parameter(None)
+                .argument("list", list(Type::param(0))),
+        );
+        engine.register(
+            TokenMetadata::new("Same", Type::con(ToyType::Int))
+                .type_parameter(None)
+                .argument("left", Type::param(0))
+                .argument("right", Type::param(0)),
+        );
+        engine.register(
+            TokenMetadata::new("Each", list(Type::param(0)))
+                .type_parameter(None)
+                .argument("list", list(Type::param(0)))
+                .argument_with_bindings("body", int(), vec![("It", Type::param(0))]),
+        );
+        engine.register(TokenMetadata::binding("It", "It"));
+        engine.register(
+            TokenMetadata::new("Let", Type::param(1))
+                .type_parameter(None)
+                .type_parameter(None)
+                .argument("value", Type::param(0))
+                .argument_binding_token_name("body", Type::param(1), Type::param(0)),
+        );
+        engine.register(TokenMetadata::variable("Var"));
+        engine.register(
+            TokenMetadata::new("Either", Type::param(0))
+                .type_parameter(None)
+                .variadic_argument("options", Type::param(0)),
+        );
+        engine.register(
+            TokenMetadata::new("Any", int())
+                .type_parameter(None)
+                .argument("list", list(Type::param(0)))
+                .argument_with_parameter("body", int(), "It", Type::param(0)),
+        );
+        engine
+    }
+
+    #[test]
+    fn test_generic_parameter_is_inferred_from_argument() {
+        let typed = engine()
+            .infer(&expr("First", vec![("list", expr("Ints", vec![]))]), &int())
+            .unwrap();
+        assert_eq!(typed.node_type, int());
+        assert_eq!(typed.arguments[0].node_type, list(int()));
+    }
+
+    #[test]
+    fn test_subtype_records_expected_type() {
+        let typed = engine()
+            .infer(&expr("Show", vec![("value", expr("One", vec![]))]), &Type::con(ToyType::Text))
+            .unwrap();
+        assert_eq!(typed.arguments[0].node_type, int());
+        assert_eq!(typed.arguments[0].expected_type, Type::con(ToyType::Any));
+    }
+
+    #[test]
+    fn test_mismatch_between_generic_arguments() {
+        let result = engine().infer(
+            &expr("Same", vec![("left", expr("One", vec![])), ("right", expr("Hello", vec![]))]),
+            &int(),
+        );
+        let error = result.unwrap_err();
+        assert_eq!(error.path, vec!["right".to_string()]);
+        assert_eq!(error.expected(), Some(&int()));
+        assert_eq!(error.found(), Some(&Type::con(ToyType::Text)));
+        assert_eq!(error.to_string(), "right: Type mismatch: expected Int, found Text");
+    }
+
+    #[test]
+    fn test_binding_is_scoped_to_argument() {
+        let typed = engine()
+            .infer(
+                &expr("Each", vec![("list", expr("Ints", vec![])), ("body", expr("It", vec![]))]),
+                &list(int()),
+            )
+            .unwrap();
+        assert_eq!(typed.arguments[1].node_type, int());
+
+        let result = engine().infer(&expr("It", vec![]), &int());
+        assert!(matches!(result.unwrap_err().kind, TypeErrorKind::UnboundName { .. }));
+    }
+
+    #[test]
+    fn test_token_named_binding() {
+        // Let a = Hello in Show(Var a)
+        let typed = engine()
+            .infer(
+                &named("Let", "a", vec![
+                    ("value", expr("Hello", vec![])),
+                    ("body", named("Var", "a", vec![])),
+                ]),
+                &Type::con(ToyType::Text),
+            )
+            .unwrap();
+        assert_eq!(typed.name.as_deref(), Some("a"));
+        assert_eq!(typed.arguments[1].node_type, Type::con(ToyType::Text));
+
+        let result = engine().infer(
+            &named("Let", "a", vec![("value", expr("One", vec![])), ("body", named("Var", "b", vec![]))]),
+            &int(),
+        );
+        let error = result.unwrap_err();
+        assert_eq!(error.path, vec!["body".to_string()]);
+        assert_eq!(error.kind, TypeErrorKind::UnboundName { token: "Var".to_string(), name: "b".to_string() });
+    }
+
+    #[test]
+    fn test_names_resolve_to_binding_depth() {
+        // Any(xs, list: Ints, body: Any(ys, list: Ints, body: Same(Var xs, It)))
+        let inner = named("Any", "ys", vec![
+            ("list", expr("Ints", vec![])),
+            ("body", expr("Same", vec![("left", named("Var", "xs", vec![])), ("right", expr("It", vec![]))])),
+        ]);
+        let typed = engine()
+            .infer(&named("Any", "xs", vec![("list", expr("Ints", vec![])), ("body", inner)]), &int())
+            .unwrap();
+        let same = &typed.arguments[1].arguments[1];
+        assert_eq!(same.arguments[0].binding_depth, Some(1));
+        assert_eq!(same.arguments[1].binding_depth, Some(0));
+
+        // 名前を付けなければ It のみで束縛される
+        let typed = engine()
+            .infer(&expr("Any", vec![("list", expr("Ints", vec![])), ("body", expr("It", vec![]))]), &int())
+            .unwrap();
+        assert_eq!(typed.arguments[1].binding_depth, Some(0));
+
+        // Let の束縛は It を隠さない
+        let typed = engine()
+            .infer(
+                &expr("Any", vec![
+                    ("list", expr("Ints", vec![])),
+                    ("body", named("Let", "a", vec![("value", expr("Hello", vec![])), ("body", expr("It", vec![]))])),
+                ]),
+                &int(),
+            )
+            .unwrap();
+        assert_eq!(typed.arguments[1].arguments[1].binding_depth, Some(1));
+    }
+
+    #[test]
+    fn test_variadic_argument() {
+        let either = |options: Vec<Expr>| expr("Either", options.into_iter().map(|e| ("options", e)).collect());
+
+        let typed = engine()
+            .infer(&either(vec![expr("One", vec![]), expr("One", vec![]), expr("One", vec![])]), &int())
+            .unwrap();
+        assert_eq!(typed.node_type, int());
+        let slots: Vec<_> = typed.arguments.iter().map(|a| a.slot.as_str()).collect();
+        assert_eq!(slots, vec!["options[0]", "options[1]", "options[2]"]);
+
+        // 全ての子が同じ型でなければならない
+        let error = engine().infer(&either(vec![expr("One", vec![]), expr("Hello", vec![])]), &int()).unwrap_err();
+        assert_eq!(error.to_string(), "options[1]: Type mismatch: expected Int, found Text");
+
+        let error = engine().infer(&either(vec![]), &int()).unwrap_err();
+        assert!(matches!(error.kind, TypeErrorKind::MissingArgument { slot: "options", .. }));
+    }
+
+    #[test]
+    fn test_missing_and_unknown_tokens() {
+        let result = engine().infer(&expr("First", vec![]), &int());
+        assert!(matches!(result.unwrap_err().kind, TypeErrorKind::MissingArgument { slot: "list", .. }));
+
+        let result = engine().infer(&expr("Nope", vec![]), &int());
+        assert_eq!(result.unwrap_err().kind, TypeErrorKind::UnknownToken("Nope".to_string()));
+    }
+
+    #[test]
+    fn test_partial_inference_reports_holes() {
+        let hole = || expr("?", vec![]);
+
+        // First(?) の穴は List<Int> が要求される
+        let holes = engine().infer_partial(&expr("First", vec![("list", hole())]), &int()).unwrap();
+        assert_eq!(holes, vec![TypeHole { path: vec!["list".to_string()], expected: list(int()) }]);
+
+        // Same(One, ?) の右辺は左辺から決まる
+        let holes = engine()
+            .infer_partial(&expr("Same", vec![("left", expr("One", vec![])), ("right", hole())]), &int())
+            .unwrap();
+        assert_eq!(holes[0].expected, int());
+
+        // Same(?, ?) ではまだ決まらない
+        let holes = engine().infer_partial(&expr("Same", vec![("left", hole()), ("right", hole())]), &int()).unwrap();
+        assert_eq!(holes.len(), 2);
+        assert!(!holes[0].expected.is_resolved());
+
+        // 穴の外側の矛盾はそのままエラー
+        let error = engine()
+            .infer_partial(&expr("Same", vec![("left", expr("One", vec![])), ("right", expr("Hello", vec![]))]), &int())
+            .unwrap_err();
+        assert_eq!(error.to_string(), "right: Type mismatch: expected Int, found Text");
+
+        // 穴は通常の推論では使えない
+        let error = engine().infer(&hole(), &int()).unwrap_err();
+        assert_eq!(error.kind, TypeErrorKind::UnknownToken("?".to_string()));
+    }
+}
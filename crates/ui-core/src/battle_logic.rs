@@ -1,41 +1,54 @@
 // Battle logic and orchestration without Bevy dependencies
 use battle::{TeamBattle, Team};
 use json_rule::RuleSet;
-use token_input::convert_to_rule_node;
+use token_input::{CompileError, Compiler};
 use crate::{CurrentRules, GameMode, GameState};
 
 pub struct BattleOrchestrator;
 
 impl BattleOrchestrator {
     // Create and setup a new battle with the given teams and rules
+    // ルールに変換できないものがあれば戦闘を作らずにエラーを返す
     pub fn create_battle(
         current_rules: &CurrentRules,
         player_team: Team,
         enemy_team: Team,
-        _enemy_rule_set: &RuleSet,
+        enemy_rule_set: &RuleSet,
         rng: rand::rngs::StdRng,
-    ) -> TeamBattle {
+    ) -> Result<TeamBattle, Vec<CompileError>> {
+        let compiler = Compiler::shared();
+        let mut errors = Vec::new();
+        
         // Convert UI rules for player characters
+        if let Err(player_errors) = current_rules.convert_to_rule_nodes() {
+            errors.extend(player_errors);
+        }
+        
+        // Convert enemy rule set
+        if let Err(enemy_errors) = compiler.compile_rule_set(enemy_rule_set) {
+            errors.extend(enemy_errors.into_iter().map(|error| error.with_prefix("enemy")));
+        }
+        
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        
+        // RuleNodeはCloneできないのでキャラクター毎に変換する
         let player_rules_per_character: Vec<_> = (0..player_team.members.len())
             .map(|_| current_rules.convert_to_rule_nodes())
-            .collect();
+            .collect::<Result<_, _>>()?;
         
-        // Convert enemy rule set for each enemy character
         let enemy_rules_per_character: Vec<_> = (0..enemy_team.members.len())
-            .map(|_| {
-                _enemy_rule_set.rules.iter()
-                    .filter_map(|token| convert_to_rule_node(token))
-                    .collect::<Vec<_>>()
-            })
-            .collect();
+            .map(|_| compiler.compile_rule_set(enemy_rule_set))
+            .collect::<Result<_, _>>()?;
         
-        TeamBattle::new(
+        Ok(TeamBattle::new(
             player_team, 
             enemy_team, 
             player_rules_per_character, 
             enemy_rules_per_character, 
             rng
-        )
+        ))
     }
     
     // Check if we should start a new battle when switching to battle mode
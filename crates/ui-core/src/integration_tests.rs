@@ -13,6 +13,7 @@ fn create_test_rng() -> StdRng {
 // Helper to create a RuleSet with a single Heal rule targeting a character
 fn create_heal_rule_set(target: StructuredTokenInput) -> RuleSet {
     RuleSet {
+        definitions: Vec::new(),
         rules: vec![StructuredTokenInput::Heal {
             target: Box::new(target),
         }],
@@ -40,7 +41,7 @@ mod tests {
         ]);
         
         // Enemy has no rules
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
@@ -48,7 +49,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle turn and verify damage was dealt
         let initial_enemy_hp = battle.enemy_team.members[0].hp;
@@ -149,7 +150,7 @@ mod tests {
         let current_rules = CurrentRules::with_rules(vec![flat_rule]);
         
         // Enemy has no rules
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
@@ -157,7 +158,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle turn and verify it doesn't crash
         let initial_enemy_hp = battle.enemy_team.members[0].hp;
@@ -206,7 +207,7 @@ mod tests {
         let current_rules = CurrentRules::with_rules(vec![flat_rule]);
         
         // Enemy has no rules
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
@@ -214,7 +215,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle turn
         battle.execute_turn();
@@ -281,7 +282,7 @@ mod tests {
             ];
             
             let current_rules = CurrentRules::with_rules(vec![test_flat_rule]);
-            let enemy_rule_set = RuleSet { rules: vec![] };
+            let enemy_rule_set = RuleSet::new(vec![]);
             
             let mut battle = BattleOrchestrator::create_battle(
                 &current_rules,
@@ -289,7 +290,7 @@ mod tests {
                 test_enemy_team,
                 &enemy_rule_set,
                 StdRng::seed_from_u64(12345 + run as u64), // Variable seed for each run
-            );
+            ).expect("Rules should compile");
             
             // Execute battle turn
             battle.execute_turn();
@@ -377,7 +378,7 @@ mod tests {
         ]);
         
         // Enemy has no rules
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
@@ -385,7 +386,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle turn and verify healing occurred
         let initial_player_hp = battle.player_team.members[0].hp;
@@ -427,7 +428,7 @@ mod tests {
         let current_rules = CurrentRules::with_rules(vec![flat_rule]);
         
         // Enemy has no rules
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
@@ -435,7 +436,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle turn and verify conditional strike occurred
         let initial_enemy_hp = battle.enemy_team.members[0].hp;
@@ -477,7 +478,7 @@ mod tests {
         let current_rules = CurrentRules::with_rules(vec![flat_rule]);
         
         // Enemy has no rules
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
@@ -485,7 +486,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle turn and verify NO strike occurred
         let initial_enemy_hp = battle.enemy_team.members[0].hp;
@@ -520,7 +521,7 @@ mod tests {
         let current_rules = CurrentRules::with_rules(vec![flat_rule]);
         
         // Enemy has no rules
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
@@ -528,7 +529,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle turn and verify self-targeting occurred
         let initial_player_hp = battle.player_team.members[0].hp;
@@ -574,7 +575,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute multiple turns and verify both strike and heal actions occur
         let _initial_enemy_hp = battle.enemy_team.members[0].hp;
@@ -600,7 +601,7 @@ mod tests {
     fn test_team_vs_team_battle_ui_to_battle_integration() {
         // Test full team vs team battle with both teams having actions
         let player_strike_rule = vec![FlatTokenInput::Strike, FlatTokenInput::RandomPick, FlatTokenInput::AllCharacters];
-        let _enemy_heal_rule = vec![FlatTokenInput::Heal, FlatTokenInput::ActingCharacter];
+        let _enemy_heal_rule = [FlatTokenInput::Heal, FlatTokenInput::ActingCharacter];
         
         // Setup battle with full teams
         let player_team = Team::new("Heroes".to_string(), vec![
@@ -619,6 +620,7 @@ mod tests {
         
         let player_current_rules = CurrentRules::with_rules(vec![player_strike_rule]);
         let enemy_rule_set = RuleSet {
+            definitions: Vec::new(),
             rules: vec![StructuredTokenInput::Heal {
                 target: Box::new(StructuredTokenInput::RandomPick {
                     array: Box::new(StructuredTokenInput::TeamMembers {
@@ -634,7 +636,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute full battle round and verify both teams acted
         let initial_battle_log_size = battle.battle_log.len();
@@ -702,7 +704,7 @@ mod tests {
         ]);
         
         // Enemy has no rules
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &rules,
@@ -710,7 +712,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle and verify rule works
         let initial_enemy_hp = battle.enemy_team.members[0].hp;
@@ -751,7 +753,7 @@ mod tests {
         ]);
         
         let current_rules = CurrentRules::with_rules(vec![rule1, rule2, rule3]);
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
@@ -759,7 +761,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle and verify one of the rules executed
         let initial_player_hp = battle.player_team.members[0].hp;
@@ -794,7 +796,7 @@ mod tests {
         ]);
         
         let current_rules = CurrentRules::with_rules(vec![strong_strike_rule]);
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
@@ -802,7 +804,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle until completion
         let mut turns_executed = 0;
@@ -833,7 +835,7 @@ mod tests {
         ]);
         
         let current_rules = CurrentRules::with_rules(vec![vec![]]); // Empty rules
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
@@ -841,7 +843,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle turn with empty rules
         let initial_player_hp = battle.player_team.members[0].hp;
@@ -894,34 +896,33 @@ mod tests {
             GameCharacter::new(4, "Target Enemy".to_string(), 70, 30, 18),
         ]);
         
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
             player_team,
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
-        
-        // Execute battle turn and verify complex conditional triggered (max HP = 80 > 60)
-        let initial_enemy_hp = battle.enemy_team.members[0].hp;
+        )
+        .expect("Map rule should compile");
+        
+        // Execute battle turn - max HP = 80 > 60, so the strike must happen
+        let total_hp = |battle: &TeamBattle| -> i32 {
+            battle.player_team.members.iter()
+                .chain(battle.enemy_team.members.iter())
+                .map(|c| c.hp)
+                .sum()
+        };
+        let initial_total_hp = total_hp(&battle);
         battle.execute_turn();
         
-        // Complex conditional should either trigger or not trigger based on rule conversion success
-        // If rule converts successfully and max HP (80) > 60, strike should occur
-        // If rule conversion fails, no action should occur
-        let enemy_took_damage = battle.enemy_team.members[0].hp < initial_enemy_hp;
-        let battle_log_exists = !battle.battle_log.is_empty();
-        
-        // Either the complex rule worked and caused damage, or it didn't work at all
-        // Both are valid outcomes for this complex rule test
         assert!(
-            enemy_took_damage || !enemy_took_damage, // Always true - just checking it doesn't panic
-            "Complex conditional test completed. Enemy HP: {} -> {}, Battle log exists: {}",
-            initial_enemy_hp,
-            battle.enemy_team.members[0].hp,
-            battle_log_exists
+            total_hp(&battle) < initial_total_hp,
+            "Strike should have hit someone since max HP (80) > 60. Total HP: {} -> {}",
+            initial_total_hp,
+            total_hp(&battle)
         );
+        assert!(!battle.battle_log.is_empty(), "Battle log should contain strike");
     }
 
     #[test]
@@ -970,14 +971,14 @@ mod tests {
             GameCharacter::new(2, "Test Enemy".to_string(), 80, 30, 20),
         ]);
         
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
             player_team,
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle and verify appropriate threshold rule triggered
         let initial_player_hp = battle.player_team.members[0].hp;
@@ -1015,14 +1016,14 @@ mod tests {
             GameCharacter::new(2, "Dummy Enemy".to_string(), 50, 20, 10),
         ]);
         
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
             player_team,
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle and check if healing occurred or was blocked by MP
         let initial_player_hp = battle.player_team.members[0].hp;
@@ -1063,14 +1064,14 @@ mod tests {
             GameCharacter::new(3, "Enemy".to_string(), 70, 30, 18),
         ]);
         
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
             player_team,
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute multiple turns - only alive characters should act
         let mut turns_executed = 0;
@@ -1110,7 +1111,7 @@ mod tests {
         for i in 0..3 {
             let rng = StdRng::seed_from_u64(42); // Fixed seed
             let current_rules = CurrentRules::with_rules(vec![strike_rule.clone()]);
-            let enemy_rule_set = RuleSet { rules: vec![] };
+            let enemy_rule_set = RuleSet::new(vec![]);
             
             let mut battle = BattleOrchestrator::create_battle(
                 &current_rules,
@@ -1118,7 +1119,7 @@ mod tests {
                 enemy_team.clone(), 
                 &enemy_rule_set,
                 rng,
-            );
+            ).expect("Rules should compile");
             
             let initial_enemy_hp: Vec<i32> = battle.enemy_team.members.iter().map(|c| c.hp).collect();
             battle.execute_turn();
@@ -1168,14 +1169,14 @@ mod tests {
             GameCharacter::new(2, "Boundary Enemy".to_string(), 60, 25, 18),
         ]);
         
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
             player_team,
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle - HP=50 should NOT be > 50, so no action expected
         let initial_enemy_hp = battle.enemy_team.members[0].hp;
@@ -1204,14 +1205,14 @@ mod tests {
             GameCharacter::new(2, "Test Enemy".to_string(), 60, 30, 20),
         ]);
         
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
             player_team,
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle - healing at max HP should either be ignored or cap at max
         let initial_player_hp = battle.player_team.members[0].hp;
@@ -1264,14 +1265,15 @@ mod tests {
             GameCharacter::new(4, "Test Enemy".to_string(), 70, 30, 18),
         ]);
         
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
             player_team,
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        )
+        .expect("Map rule should compile");
         
         // Execute battle - min HP = 15 < 20, so should NOT trigger heal
         let initial_player_hp = battle.player_team.members[0].hp;
@@ -1285,6 +1287,505 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_boolean_combinators_ui_to_battle_integration() {
+        // Check → Or → (50 > HP(ActingChar)) → 50/50 → Heal → ActingChar
+        // 左が true なので右の50/50は評価されず、必ず回復する
+        let or_rule = vec![
+            FlatTokenInput::Check,
+            FlatTokenInput::Or,
+            FlatTokenInput::GreaterThan,
+            FlatTokenInput::Number(50),
+            FlatTokenInput::CharacterToHp,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::TrueOrFalse,
+            FlatTokenInput::Heal,
+            FlatTokenInput::ActingCharacter,
+        ];
+        // Check → Not → (50 > HP(ActingChar)) → Strike → ActingChar
+        let not_rule = vec![
+            FlatTokenInput::Check,
+            FlatTokenInput::Not,
+            FlatTokenInput::GreaterThan,
+            FlatTokenInput::Number(50),
+            FlatTokenInput::CharacterToHp,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::Strike,
+            FlatTokenInput::ActingCharacter,
+        ];
+
+        for seed in 0..10 {
+            let mut low_hp_player = GameCharacter::new(1, "Wounded Hero".to_string(), 100, 50, 25);
+            low_hp_player.hp = 30;
+            let player_team = Team::new("Heroes".to_string(), vec![low_hp_player]);
+            let enemy_team = Team::new("Enemies".to_string(), vec![
+                GameCharacter::new(2, "Test Enemy".to_string(), 60, 30, 20),
+            ]);
+
+            // Not の行は false なので break し、Or の行で回復する
+            let current_rules = CurrentRules::with_rules(vec![not_rule.clone(), or_rule.clone()]);
+            let mut battle = BattleOrchestrator::create_battle(
+                &current_rules,
+                player_team,
+                enemy_team,
+                &RuleSet::new(vec![]),
+                StdRng::seed_from_u64(seed),
+            ).expect("Rules should compile");
+
+            battle.execute_turn();
+            assert!(
+                battle.player_team.members[0].hp > 30,
+                "Or rule should always heal when its left side is true (seed {})",
+                seed
+            );
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_ui_to_battle_integration() {
+        // Check → (HP(ActingChar) + 30 < 90) → Heal → ActingChar
+        let heal_rule = vec![
+            FlatTokenInput::Check,
+            FlatTokenInput::LessThan,
+            FlatTokenInput::Add,
+            FlatTokenInput::CharacterToHp,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::Number(30),
+            FlatTokenInput::Number(90),
+            FlatTokenInput::Heal,
+            FlatTokenInput::ActingCharacter,
+        ];
+        // 0除算は評価エラーになり、その行はスキップされる
+        // Check → (10 / (5 - 5) > 1) → Strike → ActingChar
+        let div_by_zero_rule = vec![
+            FlatTokenInput::Check,
+            FlatTokenInput::GreaterThan,
+            FlatTokenInput::Div,
+            FlatTokenInput::Number(10),
+            FlatTokenInput::Sub,
+            FlatTokenInput::Number(5),
+            FlatTokenInput::Number(5),
+            FlatTokenInput::Number(1),
+            FlatTokenInput::Strike,
+            FlatTokenInput::ActingCharacter,
+        ];
+        let current_rules = CurrentRules::with_rules(vec![div_by_zero_rule, heal_rule]);
+
+        let mut wounded_player = GameCharacter::new(1, "Wounded Hero".to_string(), 100, 50, 25);
+        wounded_player.hp = 50;
+        let player_team = Team::new("Heroes".to_string(), vec![wounded_player]);
+        let enemy_team = Team::new("Enemies".to_string(), vec![
+            GameCharacter::new(2, "Test Enemy".to_string(), 60, 30, 20),
+        ]);
+
+        let mut battle = BattleOrchestrator::create_battle(
+            &current_rules,
+            player_team,
+            enemy_team,
+            &RuleSet::new(vec![]),
+            create_test_rng(),
+        ).expect("Rules should compile");
+
+        // 50 + 30 = 80 < 90 なので回復する
+        battle.execute_turn();
+        assert!(
+            battle.player_team.members[0].hp > 50,
+            "Player should have healed. HP: {}",
+            battle.player_team.members[0].hp
+        );
+    }
+
+    #[test]
+    fn test_boundary_comparisons_ui_to_battle_integration() {
+        // Check → (HP(ActingChar) <= 50) → Heal → ActingChar
+        let heal_rule = vec![
+            FlatTokenInput::Check,
+            FlatTokenInput::LessOrEqual,
+            FlatTokenInput::CharacterToHp,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::Number(50),
+            FlatTokenInput::Heal,
+            FlatTokenInput::ActingCharacter,
+        ];
+        // Strike → RandomPick → FilterList(AllChars, CharTeam(Element) != CharTeam(ActingChar))
+        let strike_rule = vec![
+            FlatTokenInput::Strike,
+            FlatTokenInput::RandomPick,
+            FlatTokenInput::FilterList,
+            FlatTokenInput::AllCharacters,
+            FlatTokenInput::NotEq,
+            FlatTokenInput::CharacterTeam,
+            FlatTokenInput::Element,
+            FlatTokenInput::CharacterTeam,
+            FlatTokenInput::ActingCharacter,
+        ];
+
+        for (player_hp, should_heal) in [(50, true), (51, false)] {
+            let mut player = GameCharacter::new(1, "Hero".to_string(), 100, 50, 25);
+            player.hp = player_hp;
+            let player_team = Team::new("Heroes".to_string(), vec![player]);
+            let enemy_team = Team::new("Enemies".to_string(), vec![
+                GameCharacter::new(2, "Test Enemy".to_string(), 60, 30, 20),
+            ]);
+            let current_rules = CurrentRules::with_rules(vec![heal_rule.clone(), strike_rule.clone()]);
+            let mut battle = BattleOrchestrator::create_battle(
+                &current_rules,
+                player_team,
+                enemy_team,
+                &RuleSet::new(vec![]),
+                create_test_rng(),
+            ).expect("Rules should compile");
+
+            battle.execute_turn();
+            let healed = battle.player_team.members[0].hp > player_hp;
+            let enemy_damaged = battle.enemy_team.members[0].hp < 60;
+            assert_eq!(healed, should_heal, "HP {} <= 50 should be {}", player_hp, should_heal);
+            assert_eq!(enemy_damaged, !should_heal, "NotEq should only pick the other team");
+        }
+    }
+
+    #[test]
+    fn test_character_stats_ui_to_battle_integration() {
+        // MPがある時だけ回復: Check → (MP(ActingChar) >= 10) → Heal → ActingChar
+        let heal_rule = vec![
+            FlatTokenInput::Check,
+            FlatTokenInput::GreaterOrEqual,
+            FlatTokenInput::CharacterToMp,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::Number(10),
+            FlatTokenInput::Heal,
+            FlatTokenInput::ActingCharacter,
+        ];
+        // 攻撃力が最大の敵を攻撃:
+        // Strike → RandomPick → FilterList(TeamMembers(Enemy), Atk(Element) == Max(Map(TeamMembers(Enemy), Atk(Element))))
+        let strike_rule = vec![
+            FlatTokenInput::Strike,
+            FlatTokenInput::RandomPick,
+            FlatTokenInput::FilterList,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Enemy,
+            FlatTokenInput::Eq,
+            FlatTokenInput::CharacterAttack,
+            FlatTokenInput::Element,
+            FlatTokenInput::Max,
+            FlatTokenInput::Map,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Enemy,
+            FlatTokenInput::CharacterAttack,
+            FlatTokenInput::Element,
+        ];
+
+        let mut out_of_mp_healer = GameCharacter::new(1, "Healer".to_string(), 100, 50, 25);
+        out_of_mp_healer.hp = 40;
+        out_of_mp_healer.mp = 5;
+        let player_team = Team::new("Heroes".to_string(), vec![out_of_mp_healer]);
+        let enemy_team = Team::new("Enemies".to_string(), vec![
+            GameCharacter::new(2, "Weak Enemy".to_string(), 60, 30, 10),
+            GameCharacter::new(3, "Strong Enemy".to_string(), 60, 30, 40),
+        ]);
+
+        let current_rules = CurrentRules::with_rules(vec![heal_rule, strike_rule]);
+        let mut battle = BattleOrchestrator::create_battle(
+            &current_rules,
+            player_team,
+            enemy_team,
+            &RuleSet::new(vec![]),
+            create_test_rng(),
+        ).expect("Rules should compile");
+
+        battle.execute_turn();
+        assert_eq!(battle.player_team.members[0].hp, 40, "Healer without MP should not heal");
+        assert_eq!(battle.enemy_team.members[0].hp, 60, "Weak enemy should not be targeted");
+        assert!(battle.enemy_team.members[1].hp < 60, "Strongest attacker should be targeted");
+    }
+
+    #[test]
+    fn test_array_aggregates_ui_to_battle_integration() {
+        // HP30未満の味方が1人でもいれば、HP最小の味方を回復
+        let heal_rule = vec![
+            FlatTokenInput::Check,
+            FlatTokenInput::Any,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Hero,
+            FlatTokenInput::LessThan,
+            FlatTokenInput::CharacterToHp,
+            FlatTokenInput::Element,
+            FlatTokenInput::Number(30),
+            FlatTokenInput::Heal,
+            FlatTokenInput::CharacterHpToCharacter,
+            FlatTokenInput::Min,
+            FlatTokenInput::Map,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Hero,
+            FlatTokenInput::CharacterToHp,
+            FlatTokenInput::Element,
+        ];
+        // 敵が1体の時だけ攻撃: Count(TeamMembers(Enemy)) < 2
+        let strike_rule = vec![
+            FlatTokenInput::Check,
+            FlatTokenInput::LessThan,
+            FlatTokenInput::Count,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Enemy,
+            FlatTokenInput::Number(2),
+            FlatTokenInput::Strike,
+            FlatTokenInput::RandomPick,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Enemy,
+        ];
+
+        let mut hero = GameCharacter::new(1, "Hero".to_string(), 100, 50, 25);
+        hero.hp = 80;
+        let mut mage = GameCharacter::new(2, "Mage".to_string(), 100, 50, 25);
+        mage.hp = 20;
+        let player_team = Team::new("Heroes".to_string(), vec![hero, mage]);
+        let enemy_team = Team::new("Enemies".to_string(), vec![
+            GameCharacter::new(3, "Enemy1".to_string(), 60, 30, 10),
+            GameCharacter::new(4, "Enemy2".to_string(), 60, 30, 10),
+        ]);
+
+        let current_rules = CurrentRules::with_rules(vec![heal_rule, strike_rule]);
+        let mut battle = BattleOrchestrator::create_battle(
+            &current_rules,
+            player_team,
+            enemy_team,
+            &RuleSet::new(vec![]),
+            create_test_rng(),
+        ).expect("Rules should compile");
+
+        battle.execute_turn();
+        assert_eq!(battle.player_team.members[0].hp, 80, "Healthy hero should not be healed");
+        assert!(battle.player_team.members[1].hp > 20, "Weakest ally should be healed");
+        assert!(battle.enemy_team.members.iter().all(|enemy| enemy.hp == 60), "No strike with 2 enemies");
+    }
+
+    #[test]
+    fn test_array_shaping_ui_to_battle_integration() {
+        // 2番目にHPが低い敵を攻撃: Strike → Last(Take(SortBy(TeamMembers(Enemy), HP(Element)), 2))
+        let second_weakest_rule = vec![
+            FlatTokenInput::Strike,
+            FlatTokenInput::Last,
+            FlatTokenInput::Take,
+            FlatTokenInput::SortBy,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Enemy,
+            FlatTokenInput::CharacterToHp,
+            FlatTokenInput::Element,
+            FlatTokenInput::Number(2),
+        ];
+
+        let player_team = Team::new("Heroes".to_string(), vec![
+            GameCharacter::new(1, "Hero".to_string(), 100, 50, 25),
+        ]);
+        let mut enemies = vec![
+            GameCharacter::new(2, "Orc".to_string(), 100, 30, 10),
+            GameCharacter::new(3, "Goblin".to_string(), 100, 30, 10),
+            GameCharacter::new(4, "Slime".to_string(), 100, 30, 10),
+        ];
+        enemies[0].hp = 90;
+        enemies[1].hp = 30;
+        enemies[2].hp = 60;
+        let enemy_team = Team::new("Enemies".to_string(), enemies);
+
+        let current_rules = CurrentRules::with_rules(vec![second_weakest_rule]);
+        let mut battle = BattleOrchestrator::create_battle(
+            &current_rules,
+            player_team,
+            enemy_team,
+            &RuleSet::new(vec![]),
+            create_test_rng(),
+        ).expect("Rules should compile");
+
+        battle.execute_turn();
+        assert_eq!(battle.enemy_team.members[0].hp, 90, "Strongest enemy should not be targeted");
+        assert_eq!(battle.enemy_team.members[1].hp, 30, "Weakest enemy should not be targeted");
+        assert!(battle.enemy_team.members[2].hp < 60, "Second-weakest enemy should be targeted");
+    }
+
+    #[test]
+    fn test_max_by_min_by_ui_to_battle_integration() {
+        // HP割合が最も低い敵を攻撃: Strike → MinBy(TeamMembers(Enemy), Hp%(Element))
+        let strike_rule = vec![
+            FlatTokenInput::Strike,
+            FlatTokenInput::MinBy,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Enemy,
+            FlatTokenInput::HpPercent,
+            FlatTokenInput::Element,
+        ];
+
+        let player_team = Team::new("Heroes".to_string(), vec![
+            GameCharacter::new(1, "Hero".to_string(), 100, 50, 25),
+        ]);
+        // HPの絶対値は Giant の方が高いが、割合では Giant が最も低い
+        let mut giant = GameCharacter::new(2, "Giant".to_string(), 300, 30, 10);
+        giant.hp = 90;
+        let mut imp = GameCharacter::new(3, "Imp".to_string(), 50, 30, 10);
+        imp.hp = 40;
+        let enemy_team = Team::new("Enemies".to_string(), vec![giant, imp]);
+
+        let current_rules = CurrentRules::with_rules(vec![strike_rule]);
+        let mut battle = BattleOrchestrator::create_battle(
+            &current_rules,
+            player_team,
+            enemy_team,
+            &RuleSet::new(vec![]),
+            create_test_rng(),
+        ).expect("Rules should compile");
+
+        battle.execute_turn();
+        assert!(battle.enemy_team.members[0].hp < 90, "Enemy with the lowest HP percentage should be targeted");
+        assert_eq!(battle.enemy_team.members[1].hp, 40, "Imp should not be targeted");
+    }
+
+    #[test]
+    fn test_let_variable_ui_to_battle_integration() {
+        // Let x = RandomPick(TeamMembers(Enemy)) in Check(HP(x) < 50, Strike(x))
+        // 条件を確認したキャラクターと攻撃するキャラクターは常に同じ
+        let rule = vec![
+            FlatTokenInput::Let("x".to_string()),
+            FlatTokenInput::RandomPick,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Enemy,
+            FlatTokenInput::Check,
+            FlatTokenInput::LessThan,
+            FlatTokenInput::CharacterToHp,
+            FlatTokenInput::Var("x".to_string()),
+            FlatTokenInput::Number(50),
+            FlatTokenInput::Strike,
+            FlatTokenInput::Var("x".to_string()),
+        ];
+
+        let mut wounded_struck = 0;
+        for seed in 0..10 {
+            let player_team = Team::new("Heroes".to_string(), vec![
+                GameCharacter::new(1, "Hero".to_string(), 100, 50, 25),
+            ]);
+            let mut wounded = GameCharacter::new(2, "Wounded".to_string(), 100, 30, 10);
+            wounded.hp = 40;
+            let healthy = GameCharacter::new(3, "Healthy".to_string(), 100, 30, 10);
+            let enemy_team = Team::new("Enemies".to_string(), vec![wounded, healthy]);
+
+            let current_rules = CurrentRules::with_rules(vec![rule.clone()]);
+            let mut battle = BattleOrchestrator::create_battle(
+                &current_rules,
+                player_team,
+                enemy_team,
+                &RuleSet::new(vec![]),
+                StdRng::seed_from_u64(seed),
+            ).expect("Rules should compile");
+
+            battle.execute_turn();
+            assert_eq!(battle.enemy_team.members[1].hp, 100, "Healthy enemy should never be struck (seed {})", seed);
+            if battle.enemy_team.members[0].hp < 40 {
+                wounded_struck += 1;
+            }
+        }
+        assert!(wounded_struck > 0, "Wounded enemy should be struck when picked");
+    }
+
+    #[test]
+    fn test_if_value_ui_to_battle_integration() {
+        // Strike(If(HP(自分) > 50, First(敵), Last(敵)))
+        let rule = vec![
+            FlatTokenInput::Strike,
+            FlatTokenInput::If,
+            FlatTokenInput::GreaterThan,
+            FlatTokenInput::CharacterToHp,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::Number(50),
+            FlatTokenInput::First,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Enemy,
+            FlatTokenInput::Last,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Enemy,
+        ];
+
+        for (hero_hp, struck_index) in [(100, 0), (40, 1)] {
+            let mut hero = GameCharacter::new(1, "Hero".to_string(), 100, 50, 25);
+            hero.hp = hero_hp;
+            let player_team = Team::new("Heroes".to_string(), vec![hero]);
+            let enemy_team = Team::new("Enemies".to_string(), vec![
+                GameCharacter::new(2, "Front".to_string(), 100, 30, 10),
+                GameCharacter::new(3, "Back".to_string(), 100, 30, 10),
+            ]);
+
+            let current_rules = CurrentRules::with_rules(vec![rule.clone()]);
+            let mut battle = BattleOrchestrator::create_battle(
+                &current_rules,
+                player_team,
+                enemy_team,
+                &RuleSet::new(vec![]),
+                StdRng::seed_from_u64(42),
+            ).expect("Rules should compile");
+
+            battle.execute_turn();
+            assert!(battle.enemy_team.members[struck_index].hp < 100, "Enemy {} should be struck when hero HP is {}", struck_index, hero_hp);
+            assert_eq!(battle.enemy_team.members[1 - struck_index].hp, 100);
+        }
+
+        // 分岐の型が揃わないルールはコンパイルできない
+        let mismatched = vec![
+            FlatTokenInput::Strike,
+            FlatTokenInput::If,
+            FlatTokenInput::TrueOrFalse,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::Number(1),
+        ];
+        let result = BattleOrchestrator::create_battle(
+            &CurrentRules::with_rules(vec![mismatched]),
+            Team::new("Heroes".to_string(), vec![GameCharacter::new(1, "Hero".to_string(), 100, 50, 25)]),
+            Team::new("Enemies".to_string(), vec![GameCharacter::new(2, "Enemy".to_string(), 100, 30, 10)]),
+            &RuleSet::new(vec![]),
+            StdRng::seed_from_u64(42),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_first_of_ui_to_battle_integration() {
+        // FirstOf(2): Check(HP(自分) < 50, Heal(自分)) が Break したら Strike(Last(敵))
+        let rule = vec![
+            FlatTokenInput::FirstOf(2),
+            FlatTokenInput::Check,
+            FlatTokenInput::LessThan,
+            FlatTokenInput::CharacterToHp,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::Number(50),
+            FlatTokenInput::Heal,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::Strike,
+            FlatTokenInput::Last,
+            FlatTokenInput::TeamMembers,
+            FlatTokenInput::Enemy,
+        ];
+
+        for (hero_hp, expect_heal) in [(40, true), (100, false)] {
+            let mut hero = GameCharacter::new(1, "Hero".to_string(), 100, 50, 25);
+            hero.hp = hero_hp;
+            let player_team = Team::new("Heroes".to_string(), vec![hero]);
+            let enemy_team = Team::new("Enemies".to_string(), vec![
+                GameCharacter::new(2, "Front".to_string(), 100, 30, 10),
+                GameCharacter::new(3, "Back".to_string(), 100, 30, 10),
+            ]);
+
+            let current_rules = CurrentRules::with_rules(vec![rule.clone()]);
+            let mut battle = BattleOrchestrator::create_battle(
+                &current_rules,
+                player_team,
+                enemy_team,
+                &RuleSet::new(vec![]),
+                StdRng::seed_from_u64(42),
+            ).expect("Rules should compile");
+
+            battle.execute_turn();
+            assert_eq!(battle.player_team.members[0].hp > hero_hp, expect_heal, "Hero HP {}", hero_hp);
+            assert_eq!(battle.enemy_team.members[1].hp < 100, !expect_heal, "Hero HP {}", hero_hp);
+            assert_eq!(battle.enemy_team.members[0].hp, 100);
+        }
+    }
+
     #[test]
     fn test_character_team_filtering_ui_to_battle_integration() {
         // Test FilterList with CharacterTeam and Eq conditions
@@ -1312,14 +1813,14 @@ mod tests {
             GameCharacter::new(3, "Enemy2".to_string(), 65, 25, 18),
         ]);
         
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
             player_team,
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle - should target only enemies due to team filtering
         let initial_enemy1_hp = battle.enemy_team.members[0].hp;
@@ -1391,14 +1892,14 @@ mod tests {
             GameCharacter::new(2, "Test Enemy".to_string(), 70, 30, 18),
         ]);
         
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         let mut battle = BattleOrchestrator::create_battle(
             &current_rules,
             player_team,
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute battle - should trigger medium priority rule (heal)
         let initial_player_hp = battle.player_team.members[0].hp;
@@ -1425,7 +1926,7 @@ mod tests {
     fn test_extended_battle_duration_ui_to_battle_integration() {
         // Test longer battle with multiple rounds
         let balanced_strike_rule = vec![FlatTokenInput::Strike, FlatTokenInput::RandomPick, FlatTokenInput::AllCharacters];
-        let _balanced_heal_rule = vec![
+        let _balanced_heal_rule = [
             FlatTokenInput::Check,
             FlatTokenInput::GreaterThan,
             FlatTokenInput::Number(50),
@@ -1437,6 +1938,7 @@ mod tests {
         
         let player_current_rules = CurrentRules::with_rules(vec![balanced_strike_rule]);
         let enemy_rule_set = RuleSet {
+            definitions: Vec::new(),
             rules: vec![StructuredTokenInput::Check {
                 condition: Box::new(StructuredTokenInput::GreaterThan {
                     left: Box::new(StructuredTokenInput::Number { value: 50 }),
@@ -1466,7 +1968,7 @@ mod tests {
             enemy_team,
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute extended battle (up to 20 turns)
         let initial_total_hp: i32 = battle.player_team.members.iter().map(|c| c.hp).sum::<i32>() +
@@ -1606,7 +2108,6 @@ mod tests {
                  final_high_hp, final_medium_hp);
     }
     
-    /*
     #[test]
     fn test_structured_token_to_node_conversion_integration() {
         // Test the complete flow: StructuredTokenInput → Node → Battle execution
@@ -1623,6 +2124,7 @@ mod tests {
             then_action: Box::new(StructuredTokenInput::Strike {
                 target: Box::new(StructuredTokenInput::RandomPick {
                     array: Box::new(StructuredTokenInput::FilterList {
+                        name: None,
                         array: Box::new(StructuredTokenInput::AllCharacters),
                         condition: Box::new(StructuredTokenInput::Eq {
                             left: Box::new(StructuredTokenInput::CharacterTeam {
@@ -1636,17 +2138,16 @@ mod tests {
         };
         
         // Convert using Compiler and verify
-        let mut compiler = Compiler::new();
+        let compiler = Compiler::new();
         let compiled = compiler.compile(&complex_rule);
         assert!(compiled.is_ok());
         
         // Create RuleSet and convert to nodes
-        let ruleset = RuleSet {
-            rules: vec![complex_rule],
-        };
+        let ruleset = RuleSet::new(vec![complex_rule]);
         let rule_nodes: Vec<_> = ruleset.rules.iter()
-            .filter_map(|token| compiler.compile(token).ok())
-            .collect();
+            .map(|token| compiler.compile(token))
+            .collect::<Result<_, _>>()
+            .expect("Rules should compile");
         assert_eq!(rule_nodes.len(), 1);
         
         // Use the converted rules in actual battle
@@ -1681,9 +2182,7 @@ mod tests {
             "One enemy should have been attacked since hero HP (100) > 50"
         );
     }
-    */
     
-    /*
     #[test]
     fn test_all_team_sides_usage_integration() {
         // Test AllTeamSides token usage in a realistic scenario
@@ -1692,23 +2191,22 @@ mod tests {
         // This is a hypothetical test since AllTeamSides isn't directly used in UI
         // but demonstrates how it would work in token compilation
         let all_sides_token = StructuredTokenInput::AllTeamSides;
-        let mut compiler = Compiler::new();
+        let compiler = Compiler::new();
         // AllTeamSides returns Vec<TeamSide>, not an Action, so compilation will fail
         let result = compiler.compile(&all_sides_token);
         assert!(result.is_err());
     }
-    */
     
-    /*
     #[test]
     fn test_numeric_max_min_with_character_hp_integration() {
-        // Test NumericMax/NumericMin functionality with CharacterHP values
+        // Test Max over Map(CharacterToHp) returning the owning character
         use token_input::compiler::Compiler;
         
         let rule = StructuredTokenInput::Strike {
             target: Box::new(StructuredTokenInput::CharacterHpToCharacter {
-                character_hp: Box::new(StructuredTokenInput::NumericMax {
+                character_hp: Box::new(StructuredTokenInput::Max {
                     array: Box::new(StructuredTokenInput::Map {
+                        name: None,
                         array: Box::new(StructuredTokenInput::TeamMembers {
                             team_side: Box::new(StructuredTokenInput::Hero),
                         }),
@@ -1721,17 +2219,18 @@ mod tests {
         };
         
         // Convert and verify using Compiler
-        let mut compiler = Compiler::new();
+        let compiler = Compiler::new();
         let compiled = compiler.compile(&rule);
         if let Err(ref e) = compiled {
             eprintln!("Compilation failed: {:?}", e);
         }
         assert!(compiled.is_ok());
         
-        let ruleset = RuleSet { rules: vec![rule] };
+        let ruleset = RuleSet::new(vec![rule]);
         let rule_nodes: Vec<_> = ruleset.rules.iter()
-            .filter_map(|token| compiler.compile(token).ok())
-            .collect();
+            .map(|token| compiler.compile(token))
+            .collect::<Result<_, _>>()
+            .expect("Rules should compile");
         
         // Setup battle with multiple heroes having different HP
         let mut hero1 = GameCharacter::new(1, "Low HP Hero".to_string(), 100, 50, 25);
@@ -1767,9 +2266,7 @@ mod tests {
             battle.player_team.members[1].hp
         );
     }
-    */
     
-    /*
     #[test]
     fn test_team_members_with_dynamic_team_side_integration() {
         // Test TeamMembers with dynamically evaluated TeamSide
@@ -1786,14 +2283,15 @@ mod tests {
         };
         
         // Convert to nodes using Compiler
-        let mut compiler = Compiler::new();
+        let compiler = Compiler::new();
         let compiled = compiler.compile(&rule);
         assert!(compiled.is_ok());
         
-        let ruleset = RuleSet { rules: vec![rule] };
+        let ruleset = RuleSet::new(vec![rule]);
         let rule_nodes: Vec<_> = ruleset.rules.iter()
-            .filter_map(|token| compiler.compile(token).ok())
-            .collect();
+            .map(|token| compiler.compile(token))
+            .collect::<Result<_, _>>()
+            .expect("Rules should compile");
         
         // Setup battle
         let player_team = Team::new("Heroes".to_string(), vec![
@@ -1826,7 +2324,6 @@ mod tests {
             final_total_player_hp
         );
     }
-    */
 
     #[test]
     fn test_less_than_condition_ui_to_battle_integration() {
@@ -1849,7 +2346,7 @@ mod tests {
             rules.add_token_to_current_row(token);
         }
         
-        let _rule_nodes = rules.convert_to_rule_nodes();
+        let _rule_nodes = rules.convert_to_rule_nodes().expect("Rules should compile");
         
         // Setup battle
         let mut low_hp_hero = GameCharacter::new(1, "Low HP Hero".to_string(), 100, 50, 25);
@@ -1863,7 +2360,7 @@ mod tests {
         ]);
         
         // Enemy has no rules
-        let enemy_rule_set = RuleSet { rules: vec![] };
+        let enemy_rule_set = RuleSet::new(vec![]);
         
         let mut battle = BattleOrchestrator::create_battle(
             &rules,
@@ -1871,7 +2368,7 @@ mod tests {
             enemy_team.clone(),
             &enemy_rule_set,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute turn for low HP hero (should heal)
         let initial_low_hp = battle.player_team.members[0].hp;
@@ -1899,7 +2396,6 @@ mod tests {
         );
     }
 
-    /*
     #[test]
     fn test_less_than_with_numeric_values_integration() {
         // Test LessThan with mixed numeric types
@@ -1918,7 +2414,7 @@ mod tests {
         };
         
         // Convert using Compiler
-        let mut compiler = Compiler::new();
+        let compiler = Compiler::new();
         let compiled = compiler.compile(&rule).unwrap();
         
         // Setup battle
@@ -1950,7 +2446,6 @@ mod tests {
             battle.player_team.members[0].hp
         );
     }
-    */
 
     #[test]
     fn test_json_rules_loading_integration() {
@@ -1960,7 +2455,7 @@ mod tests {
         
         // Load rules from JSON file
         let json_path = Path::new("../../rules/enemy_rules.json");
-        let enemy_rules = load_rules_from_file(&json_path)
+        let enemy_rules = load_rules_from_file(json_path)
             .expect("Failed to load enemy_rules.json");
         
         // Verify loaded rules structure
@@ -1988,7 +2483,7 @@ mod tests {
             enemy_team,
             &enemy_rules,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Execute multiple turns to see enemy JSON rules in action
         let initial_player_hp = battle.player_team.members[0].hp;
@@ -2034,7 +2529,7 @@ mod tests {
         
         // Load enemy rules from JSON
         let json_path = Path::new("../../rules/enemy_rules.json");
-        let enemy_rules = load_rules_from_file(&json_path)
+        let enemy_rules = load_rules_from_file(json_path)
             .expect("Failed to load enemy_rules.json");
         
         // Setup player team with multiple characters
@@ -2078,7 +2573,7 @@ mod tests {
             enemy_team,
             &enemy_rules,
             create_test_rng(),
-        );
+        ).expect("Rules should compile");
         
         // Track initial state
         let initial_hero2_hp = battle.player_team.members[1].hp;
@@ -2118,4 +2613,43 @@ mod tests {
         
         println!("Complex JSON battle completed with {} log entries", battle.battle_log.len());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_json_rules_open_in_ui_editor_integration() {
+        // JSONで書いた敵ルールをUIの行に戻し、同じ構造に再変換できる
+        use json_rule::load_rules_from_file;
+        use std::path::Path;
+
+        let enemy_rules = load_rules_from_file(Path::new("../../rules/enemy_rules.json"))
+            .expect("Failed to load enemy_rules.json");
+        let current_rules = CurrentRules::from_rule_set(&enemy_rules).expect("Rules should be editable in the UI");
+
+        assert_eq!(current_rules.rules.len(), 5);
+        assert_eq!(current_rules.non_empty_rule_count(), 3);
+        assert_eq!(current_rules.rules[0][0], FlatTokenInput::Check);
+        assert!(current_rules.convert_to_rule_nodes().is_ok());
+
+        let expanded = enemy_rules.expanded_rules().unwrap();
+        for (row, rule) in expanded.iter().enumerate() {
+            let structured = crate::convert_flat_to_structured(&current_rules.rules[row]).unwrap();
+            assert_eq!(&structured[0], rule, "row {} should round trip", row);
+        }
+
+        // 引数名はUIの行に持てないのでエラーになる
+        let named = RuleSet::new(vec![StructuredTokenInput::Strike {
+            target: Box::new(StructuredTokenInput::RandomPick {
+                array: Box::new(StructuredTokenInput::FilterList {
+                    name: Some("enemy".to_string()),
+                    array: Box::new(StructuredTokenInput::AllCharacters),
+                    condition: Box::new(StructuredTokenInput::TrueOrFalseRandom),
+                }),
+            }),
+        }]);
+        let errors = CurrentRules::from_rule_set(&named).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "rules[0].target.array: FilterList with a parameter name cannot be written as a flat token"
+        );
+    }
+}
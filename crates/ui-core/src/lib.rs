@@ -2,6 +2,7 @@
 
 pub mod game_state;
 pub mod rule_management;
+pub mod rule_storage;
 pub mod battle_logic;
 
 #[cfg(test)]
@@ -10,5 +11,6 @@ mod integration_tests;
 // Re-export public types
 pub use game_state::{GameState, GameMode};
 pub use rule_management::CurrentRules;
-pub use token_input::{FlatTokenInput, convert_flat_to_structured};
+pub use rule_storage::{RuleStorage, DEFAULT_SAVE_DIRECTORY};
+pub use token_input::{CompileError, FlatTokenInput, RowCompletion, convert_flat_rule, convert_flat_to_structured, convert_structured_to_flat};
 pub use battle_logic::BattleOrchestrator;
\ No newline at end of file
@@ -1,11 +1,18 @@
 // Rule management logic - independent of Bevy
 
 use action_system::RuleNode;
-use token_input::{FlatTokenInput, convert_flat_to_structured, convert_to_rule_node};
+use token_input::{path, CompileError, Compiler, FlatTokenInput, RowCompletion, RuleSet, complete_flat_row, convert_flat_rule, convert_structured_to_flat, surplus_tokens};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
 
-#[derive(Default, Clone, Debug)]
+// UIに表示するルール行の数
+const DEFAULT_ROW_COUNT: usize = 5;
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub struct CurrentRules {
     pub rules: Vec<Vec<FlatTokenInput>>,
+    // 選択中の行は保存しない
+    #[serde(skip)]
     pub selected_row: usize,
 }
 
@@ -31,25 +38,74 @@ impl CurrentRules {
         }
     }
 
+    // JSONなどで読み込んだRuleSetをUIで編集できる行に戻す
+    // 定義は展開してから書き出すので、UIの行は定義に依存しない
+    pub fn from_rule_set(rule_set: &RuleSet) -> Result<Self, Vec<CompileError>> {
+        let mut rules = Vec::new();
+        let mut errors = Vec::new();
+        for (row, rule) in rule_set.expanded_rules()?.iter().enumerate() {
+            match convert_structured_to_flat(rule) {
+                Ok(rule_row) => rules.push(rule_row),
+                Err(error) => errors.push(error.with_prefix(&path::index("rules", row))),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let mut current_rules = Self::with_rules(rules);
+        current_rules.fill_empty_rows();
+        Ok(current_rules)
+    }
+
+    // UIの行数に満たなければ空行で埋める
+    pub(crate) fn fill_empty_rows(&mut self) {
+        if self.rules.len() < DEFAULT_ROW_COUNT {
+            self.rules.resize(DEFAULT_ROW_COUNT, Vec::new());
+        }
+    }
+
     // UIのFlatTokenInputからtoken-inputを経由してaction-systemのRuleNodeに変換
-    pub fn convert_to_rule_nodes(&self) -> Vec<RuleNode> {
-        self.rules
-            .iter()
-            .filter(|rule_row| !rule_row.is_empty())
-            .filter_map(|rule_row| {
-                match convert_flat_to_structured(rule_row) {
-                    Ok(structured_tokens) => {
-                        if structured_tokens.is_empty() {
-                            return None;
-                        }
-                        
-                        // StructuredTokenInputをRuleNodeに変換
-                        convert_to_rule_node(&structured_tokens[0])
-                    }
-                    Err(_) => None,
-                }
-            })
-            .collect()
+    // 変換できない行は `rules[行番号]` から始まるパス付きのエラーとして返す
+    pub fn convert_to_rule_nodes(&self) -> Result<Vec<RuleNode>, Vec<CompileError>> {
+        let compiler = Compiler::shared();
+        let mut rule_nodes = Vec::new();
+        let mut errors = Vec::new();
+        
+        for (row, rule_row) in self.rules.iter().enumerate() {
+            if rule_row.is_empty() {
+                continue;
+            }
+            match compile_row(compiler, row, rule_row) {
+                Ok(rule_node) => rule_nodes.push(rule_node),
+                Err(error) => errors.push(error),
+            }
+        }
+        
+        if errors.is_empty() {
+            Ok(rule_nodes)
+        } else {
+            Err(errors)
+        }
+    }
+    
+    // 指定行の変換エラー（空行・正しい行はNone）
+    pub fn row_error(&self, row: usize) -> Option<CompileError> {
+        let rule_row = self.rules.get(row)?;
+        if rule_row.is_empty() {
+            return None;
+        }
+        compile_row(Compiler::shared(), row, rule_row).err()
+    }
+    
+    // 指定行で1つ目の式の後に余っているトークンの範囲（UIで印を付ける）
+    pub fn row_surplus_tokens(&self, row: usize) -> Option<Range<usize>> {
+        surplus_tokens(self.rules.get(row)?)
+    }
+    
+    // 選択中の行の次の穴に置けるトークン（`candidates` のうち型が合うもの）と穴の型
+    pub fn current_row_completion(&self, candidates: &[FlatTokenInput]) -> Result<RowCompletion, CompileError> {
+        let rule_row = self.rules.get(self.selected_row).map(Vec::as_slice).unwrap_or_default();
+        complete_flat_row(rule_row, candidates).map_err(|error| error.with_prefix(&path::index("rules", self.selected_row)))
     }
     
     // ルール行の追加
@@ -122,6 +178,12 @@ impl CurrentRules {
     }
 }
 
+// 1行分のトークンをStructuredTokenInput経由でRuleNodeに変換
+fn compile_row(compiler: &Compiler, row: usize, rule_row: &[FlatTokenInput]) -> Result<RuleNode, CompileError> {
+    let prefix = path::index("rules", row);
+    let rule = convert_flat_rule(rule_row).map_err(|error| error.with_prefix(&prefix))?;
+    compiler.compile(&rule).map_err(|error| error.with_prefix(&prefix))
+}
 
 #[cfg(test)]
 mod tests {
@@ -140,8 +202,8 @@ mod tests {
         rules.add_token_to_current_row(FlatTokenInput::ActingCharacter);
         
         assert_eq!(rules.rules[0].len(), 4);
-        assert_eq!(rules.is_current_row_empty(), false);
-        assert_eq!(rules.has_valid_rules(), true);
+        assert!(!rules.is_current_row_empty());
+        assert!(rules.has_valid_rules());
         
         // Remove last token
         rules.remove_last_token_from_current_row();
@@ -149,8 +211,8 @@ mod tests {
         
         // Clear current row
         rules.clear_current_row();
-        assert_eq!(rules.is_current_row_empty(), true);
-        assert_eq!(rules.has_valid_rules(), false);
+        assert!(rules.is_current_row_empty());
+        assert!(!rules.has_valid_rules());
     }
     
     #[test]
@@ -181,7 +243,7 @@ mod tests {
         let mut rules = CurrentRules::new();
         
         assert_eq!(rules.non_empty_rule_count(), 1); // Default rule exists
-        assert_eq!(rules.has_valid_rules(), true); // Default rule is valid
+        assert!(rules.has_valid_rules()); // Default rule is valid
         
         // Clear first row and add new tokens
         rules.clear_current_row();
@@ -189,7 +251,7 @@ mod tests {
         rules.add_token_to_current_row(FlatTokenInput::RandomPick);
         rules.add_token_to_current_row(FlatTokenInput::AllCharacters);
         assert_eq!(rules.non_empty_rule_count(), 1);
-        assert_eq!(rules.has_valid_rules(), true);
+        assert!(rules.has_valid_rules());
         
         // Add tokens to second row
         rules.select_next_row();
@@ -200,13 +262,14 @@ mod tests {
         // Clear all
         rules.clear_all();
         assert_eq!(rules.non_empty_rule_count(), 0);
-        assert_eq!(rules.has_valid_rules(), false);
+        assert!(!rules.has_valid_rules());
         assert_eq!(rules.selected_row, 0);
     }
     
     #[test]
     fn test_complex_rule_creation() {
         let mut rules = CurrentRules::new();
+        rules.clear_current_row();
         
         // Create a complex rule pattern
         rules.add_token_to_current_row(FlatTokenInput::Check);
@@ -217,10 +280,75 @@ mod tests {
         rules.add_token_to_current_row(FlatTokenInput::Heal);
         rules.add_token_to_current_row(FlatTokenInput::ActingCharacter);
         
-        let rule_nodes = rules.convert_to_rule_nodes();
+        let rule_nodes = rules.convert_to_rule_nodes().expect("Should convert to valid rule nodes");
         assert_ne!(rule_nodes.len(), 0, "Should convert to valid rule nodes");
     }
     
+    #[test]
+    fn test_invalid_row_reports_error() {
+        let mut rules = CurrentRules::new();
+        
+        // 行2: Strikeの対象にTeamSideを渡す
+        rules.select_row(2);
+        rules.add_token_to_current_row(FlatTokenInput::Strike);
+        rules.add_token_to_current_row(FlatTokenInput::Enemy);
+        
+        let errors = rules.convert_to_rule_nodes().err().expect("Invalid row should not be dropped");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "rules[2].target");
+        assert_eq!(errors[0].message, "Type mismatch: expected Character, found TeamSide");
+        
+        assert!(rules.row_error(0).is_none());
+        assert!(rules.row_error(1).is_none());
+        assert_eq!(rules.row_error(2), Some(errors[0].clone()));
+        
+        // 不完全な行はパーサーのエラーとして報告される
+        rules.clear_current_row();
+        rules.add_token_to_current_row(FlatTokenInput::Heal);
+        let error = rules.row_error(2).expect("Incomplete row should report an error");
+        assert_eq!(error.path, "rules[2]");
+    }
+    
+    #[test]
+    fn test_surplus_tokens_are_reported() {
+        // 後半の Heal → ActingCharacter が黙って捨てられない
+        let rules = CurrentRules::with_rules(vec![vec![
+            FlatTokenInput::Strike,
+            FlatTokenInput::ActingCharacter,
+            FlatTokenInput::Heal,
+            FlatTokenInput::ActingCharacter,
+        ]]);
+
+        let errors = rules.convert_to_rule_nodes().err().expect("Surplus tokens should be an error");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "rules[0]: Unexpected token Heal at index 2: the rule already ended");
+        assert_eq!(rules.row_surplus_tokens(0), Some(2..4));
+        assert_eq!(rules.row_surplus_tokens(1), None);
+    }
+    
+    #[test]
+    fn test_current_row_completion() {
+        let mut rules = CurrentRules::new();
+        let candidates = vec![FlatTokenInput::Strike, FlatTokenInput::ActingCharacter, FlatTokenInput::Enemy];
+        
+        rules.select_row(1);
+        let completion = rules.current_row_completion(&candidates).unwrap();
+        assert_eq!(completion.valid_tokens, vec![FlatTokenInput::Strike]);
+        
+        rules.add_token_to_current_row(FlatTokenInput::Strike);
+        let completion = rules.current_row_completion(&candidates).unwrap();
+        assert_eq!(completion.hole.as_deref(), Some("target"));
+        assert_eq!(completion.valid_tokens, vec![FlatTokenInput::ActingCharacter]);
+        
+        rules.add_token_to_current_row(FlatTokenInput::ActingCharacter);
+        assert!(rules.current_row_completion(&candidates).unwrap().is_complete());
+        
+        rules.select_row(0);
+        rules.add_token_to_current_row(FlatTokenInput::Enemy);
+        let error = rules.current_row_completion(&candidates).unwrap_err();
+        assert_eq!(error.path, "rules[0]");
+    }
+    
     // Note: String formatting tests moved to bevy-ui crate
     
 }
\ No newline at end of file
@@ -0,0 +1,147 @@
+// Rule storage - 名前付きスロットにルール行を保存・読み込みする（ローカルディレクトリのJSONファイル）
+
+use crate::rule_management::CurrentRules;
+use std::fs;
+use std::path::PathBuf;
+
+// 保存先の既定ディレクトリ（実行ディレクトリからの相対パス）
+pub const DEFAULT_SAVE_DIRECTORY: &str = "saves";
+
+#[derive(Clone, Debug)]
+pub struct RuleStorage {
+    directory: PathBuf,
+}
+
+impl Default for RuleStorage {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAVE_DIRECTORY)
+    }
+}
+
+impl RuleStorage {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    // スロット `<directory>/<slot>.json` に保存する（同名のスロットは上書き）
+    pub fn save(&self, slot: &str, rules: &CurrentRules) -> Result<(), String> {
+        let path = self.slot_path(slot)?;
+        let json = serde_json::to_string_pretty(rules)
+            .map_err(|e| format!("Failed to serialize rules: {}", e))?;
+        fs::create_dir_all(&self.directory)
+            .map_err(|e| format!("Failed to create save directory: {}", e))?;
+        fs::write(path, json)
+            .map_err(|e| format!("Failed to write file: {}", e))
+    }
+
+    // 保存したルールを読み込む。途中の行もそのまま戻す（型検査はしない）
+    pub fn load(&self, slot: &str) -> Result<CurrentRules, String> {
+        let path = self.slot_path(slot)?;
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read slot {}: {}", slot, e))?;
+        let mut rules: CurrentRules = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse slot {}: {}", slot, e))?;
+        rules.fill_empty_rows();
+        Ok(rules)
+    }
+
+    // 保存済みのスロット名（名前順）。ディレクトリがなければ空
+    pub fn slots(&self) -> Result<Vec<String>, String> {
+        let Ok(entries) = fs::read_dir(&self.directory) else {
+            return Ok(Vec::new());
+        };
+        let mut slots = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| format!("Failed to read save directory: {}", e))?.path();
+            if path.extension().is_some_and(|extension| extension == "json") {
+                if let Some(slot) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    slots.push(slot.to_string());
+                }
+            }
+        }
+        slots.sort();
+        Ok(slots)
+    }
+
+    // スロット名はファイル名になるので英数字・`-`・`_` に限る
+    fn slot_path(&self, slot: &str) -> Result<PathBuf, String> {
+        let valid = !slot.is_empty() && slot.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(format!("Invalid slot name: {:?}", slot));
+        }
+        Ok(self.directory.join(format!("{}.json", slot)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FlatTokenInput;
+
+    fn temporary_storage(name: &str) -> RuleStorage {
+        let directory = std::env::temp_dir().join(format!("ui-core-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        RuleStorage::new(directory)
+    }
+
+    #[test]
+    fn test_save_and_load_slots() {
+        let storage = temporary_storage("save-and-load");
+        assert_eq!(storage.slots().unwrap(), Vec::<String>::new());
+
+        let mut rules = CurrentRules::new();
+        rules.select_row(1);
+        rules.add_token_to_current_row(FlatTokenInput::Let("me".to_string()));
+        rules.add_token_to_current_row(FlatTokenInput::ActingCharacter);
+        rules.select_row(2);
+        rules.add_token_to_current_row(FlatTokenInput::FirstOf(3));
+        rules.add_token_to_current_row(FlatTokenInput::Number(50));
+
+        storage.save("second", &rules).unwrap();
+        storage.save("first", &CurrentRules::new()).unwrap();
+        assert_eq!(storage.slots().unwrap(), vec!["first".to_string(), "second".to_string()]);
+
+        // 入力途中の行も含めてそのまま戻る。選択中の行は保存しない
+        let loaded = storage.load("second").unwrap();
+        assert_eq!(loaded.rules, rules.rules);
+        assert_eq!(loaded.selected_row, 0);
+
+        // 上書き
+        storage.save("second", &CurrentRules::new()).unwrap();
+        assert_eq!(storage.load("second").unwrap().rules, CurrentRules::new().rules);
+
+        let _ = fs::remove_dir_all(&storage.directory);
+    }
+
+    #[test]
+    fn test_saved_format() {
+        let storage = temporary_storage("saved-format");
+        let rules = CurrentRules::with_rules(vec![vec![
+            FlatTokenInput::Strike,
+            FlatTokenInput::Var("x".to_string()),
+            FlatTokenInput::Number(7),
+        ]]);
+        storage.save("format", &rules).unwrap();
+        assert_eq!(storage.load("format").unwrap().rules.len(), 5);
+
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(storage.slot_path("format").unwrap()).unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!({ "rules": [["Strike", { "Var": "x" }, { "Number": 7 }]] }));
+
+        let _ = fs::remove_dir_all(&storage.directory);
+    }
+
+    #[test]
+    fn test_storage_errors() {
+        let storage = temporary_storage("errors");
+        assert_eq!(storage.save("../escape", &CurrentRules::new()).unwrap_err(), "Invalid slot name: \"../escape\"");
+        assert!(storage.load("missing").unwrap_err().starts_with("Failed to read slot missing: "));
+
+        fs::create_dir_all(&storage.directory).unwrap();
+        fs::write(storage.slot_path("broken").unwrap(), r#"{ "rules": [["Fireball"]] }"#).unwrap();
+        assert!(storage.load("broken").unwrap_err().starts_with("Failed to parse slot broken: unknown variant `Fireball`"));
+
+        let _ = fs::remove_dir_all(&storage.directory);
+    }
+}